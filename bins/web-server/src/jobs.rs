@@ -0,0 +1,231 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use pulse_database::connection::Database;
+use pulse_database::model::idempotency::IDEMPOTENCY_KEY_TTL;
+use pulse_service::{
+    CommunityService, EventHub, EventService, IdempotencyService, LinkPreviewService, PayoutService, PayoutWebhook,
+    PresenceService,
+};
+
+/// How often the trending ranking is recomputed
+const TRENDING_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long to wait before listening again after the event listener fails
+const EVENT_LISTEN_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Most events read from the log at once
+const EVENT_BATCH_SIZE: i64 = 500;
+
+/// How often rounds are checked for an expired timer or pending settlement
+const ROUND_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long events are kept for clients to resume from
+const EVENT_RETENTION: chrono::Duration = chrono::Duration::hours(24);
+
+/// How often connections left behind by dead instances are cleared
+const PRESENCE_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often old events are pruned
+const EVENT_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often expired idempotency keys are pruned
+const IDEMPOTENCY_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often queued links are checked for previews to fetch
+const LINK_UNFURL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often payouts are checked for ones to send
+const PAYOUT_PROCESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Periodically refresh the trending scores used by community discovery
+pub fn spawn_trending_refresh(db: Arc<Database>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let service = CommunityService::new(db);
+        let mut interval = tokio::time::interval(TRENDING_REFRESH_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = service.refresh_trending().await {
+                eprintln!("❌ Failed to refresh trending communities: {}", e);
+            }
+        }
+    })
+}
+
+/// Feed events appended by any instance into this instance's hub.
+///
+/// Notifications only say that something was appended; the events themselves
+/// are always read from the log in id order, so a dropped connection or a
+/// burst of notifications can never reorder or lose them.
+pub fn spawn_event_dispatcher(db: Arc<Database>, hub: EventHub) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let service = EventService::new(db);
+
+        // Only dispatch events appended from now on; earlier ones are
+        // available to clients through replay
+        let mut last_id = loop {
+            match service.latest_event_id(None).await {
+                Ok(id) => break id,
+                Err(e) => {
+                    eprintln!("❌ Failed to read latest event id: {}", e);
+                    tokio::time::sleep(EVENT_LISTEN_RETRY_INTERVAL).await;
+                }
+            }
+        };
+
+        loop {
+            let mut listener = match service.listen().await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    eprintln!("❌ Failed to listen for events: {}", e);
+                    tokio::time::sleep(EVENT_LISTEN_RETRY_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            // Pick up anything appended while we weren't listening
+            catch_up(&service, &hub, &mut last_id).await;
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => {
+                        // One read can cover a whole burst of notifications
+                        let notified_id = notification.payload().parse::<i64>().unwrap_or(i64::MAX);
+                        if notified_id > last_id {
+                            catch_up(&service, &hub, &mut last_id).await;
+                        }
+                    }
+                    // The connection dropped; notifications sent meanwhile are
+                    // lost, so listen again and catch up from the log
+                    Ok(None) => {
+                        eprintln!("⚠️ Event listener connection lost, reconnecting");
+                        break;
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Event listener failed: {}", e);
+                        tokio::time::sleep(EVENT_LISTEN_RETRY_INTERVAL).await;
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Dispatch every logged event after `last_id`, advancing it as we go
+async fn catch_up(service: &EventService, hub: &EventHub, last_id: &mut i64) {
+    loop {
+        match service.events_after(None, *last_id, EVENT_BATCH_SIZE).await {
+            Ok(events) => {
+                let done = (events.len() as i64) < EVENT_BATCH_SIZE;
+                for event in events {
+                    *last_id = event.id;
+                    hub.dispatch(event);
+                }
+                if done {
+                    return;
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ Failed to read events: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// End rounds whose timer has run out so subscribers hear about it promptly,
+/// then settle the rounds that have ended
+pub fn spawn_round_sweeper(db: Arc<Database>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let service = CommunityService::new(db);
+        let mut interval = tokio::time::interval(ROUND_SWEEP_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = service.expire_due_rounds().await {
+                eprintln!("❌ Failed to expire rounds: {}", e);
+            }
+            if let Err(e) = service.settle_expired_rounds().await {
+                eprintln!("❌ Failed to settle rounds: {}", e);
+            }
+        }
+    })
+}
+
+/// Drop events that are too old to resume from
+pub fn spawn_event_pruner(db: Arc<Database>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let service = EventService::new(db);
+        let mut interval = tokio::time::interval(EVENT_PRUNE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = service.prune(Utc::now() - EVENT_RETENTION).await {
+                eprintln!("❌ Failed to prune events: {}", e);
+            }
+        }
+    })
+}
+
+/// Clear presence for connections whose instance stopped refreshing them
+pub fn spawn_presence_sweeper(db: Arc<Database>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let service = PresenceService::new(db);
+        let mut interval = tokio::time::interval(PRESENCE_SWEEP_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = service.sweep_stale().await {
+                eprintln!("❌ Failed to sweep presence: {}", e);
+            }
+        }
+    })
+}
+
+/// Fetch previews for links posted in messages
+pub fn spawn_link_unfurler(db: Arc<Database>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let service = LinkPreviewService::new(db);
+        let mut interval = tokio::time::interval(LINK_UNFURL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = service.unfurl_pending().await {
+                eprintln!("❌ Failed to unfurl links: {}", e);
+            }
+        }
+    })
+}
+
+/// Forget idempotency keys too old to be retried with
+pub fn spawn_idempotency_pruner(db: Arc<Database>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let service = IdempotencyService::new(db);
+        let mut interval = tokio::time::interval(IDEMPOTENCY_PRUNE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = service.prune(Utc::now() - IDEMPOTENCY_KEY_TTL).await {
+                eprintln!("❌ Failed to prune idempotency keys: {}", e);
+            }
+        }
+    })
+}
+
+/// Send referral reward claims and round winnings to the payment service
+pub fn spawn_payout_processor(db: Arc<Database>, webhook: PayoutWebhook) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let service = PayoutService::new(db);
+        let mut interval = tokio::time::interval(PAYOUT_PROCESS_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = service.process_due(&webhook).await {
+                eprintln!("❌ Failed to process payouts: {}", e);
+            }
+        }
+    })
+}
@@ -1,9 +1,11 @@
+pub mod jobs;
+#[allow(dead_code)]
 pub mod logbot;
 
-use axum::{http, Router};
-use pulse_handlers;
-use pulse_routes;
-use pulse_database;
+use axum::http;
+use pulse_handlers::AppState;
+use pulse_service::{blob_store_from_env, rate_limit_store_from_env, EventHub, ModerationPipeline, PayoutWebhook};
+use std::sync::Arc;
 use std::net::SocketAddr;
 use tower_http::cors::{Any, CorsLayer};
 
@@ -32,6 +34,7 @@ async fn main() {
             http::Method::GET,
             http::Method::POST,
             http::Method::PUT,
+            http::Method::PATCH,
             http::Method::DELETE,
             http::Method::OPTIONS
         ])
@@ -39,8 +42,62 @@ async fn main() {
         .allow_credentials(false);
 
 
-    // Get the router from routes crate with database connection
-    let app = pulse_routes::create_router(db)
+    // Uploaded images go to the local disk or an S3-compatible bucket
+    let blobs = match blob_store_from_env() {
+        Ok(blobs) => blobs,
+        Err(e) => {
+            eprintln!("❌ Failed to set up blob store: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Messages are checked before they are stored, optionally by an external classifier
+    let moderation = match ModerationPipeline::from_env() {
+        Ok(moderation) => Arc::new(moderation),
+        Err(e) => {
+            eprintln!("❌ Failed to set up moderation: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Posting limits are counted in this process, or in Redis when several instances share them
+    let rate_limits = match rate_limit_store_from_env().await {
+        Ok(rate_limits) => rate_limits,
+        Err(e) => {
+            eprintln!("❌ Failed to set up rate limiting: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Payouts are sent to an external payment service; without one they wait as pending
+    let payout_webhook = match PayoutWebhook::from_env() {
+        Ok(payout_webhook) => payout_webhook,
+        Err(e) => {
+            eprintln!("❌ Failed to set up payouts: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Live events are fanned out to WebSocket subscribers through the hub
+    let events = EventHub::new();
+
+    // Background jobs
+    jobs::spawn_trending_refresh(db.clone());
+    jobs::spawn_event_dispatcher(db.clone(), events.clone());
+    jobs::spawn_round_sweeper(db.clone());
+    jobs::spawn_event_pruner(db.clone());
+    jobs::spawn_presence_sweeper(db.clone());
+    jobs::spawn_link_unfurler(db.clone());
+    jobs::spawn_idempotency_pruner(db.clone());
+    match payout_webhook {
+        Some(webhook) => {
+            jobs::spawn_payout_processor(db.clone(), webhook);
+        },
+        None => println!("⚠️ PAYOUT_WEBHOOK_URL is not set; payouts will wait as pending"),
+    }
+
+    // Get the router from routes crate with the shared application state
+    let app = pulse_routes::create_router(AppState::new(db, events, blobs, moderation, rate_limits))
         .layer(cors);
 
     // Run it with hyper on localhost:8080
@@ -48,7 +105,8 @@ async fn main() {
     println!("🚀 Server starting on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // Rate limiting needs the address each request came from
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
 
 
 
@@ -0,0 +1,26 @@
+-- Add lifecycle status to communities. Existing rows are already accepting
+-- messages, so they start out live; new communities start as drafts.
+ALTER TABLE communities
+    ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'live',
+    ADD COLUMN status_updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+ALTER TABLE communities ALTER COLUMN status SET DEFAULT 'draft';
+
+ALTER TABLE communities
+    ADD CONSTRAINT communities_status_check
+    CHECK (status IN ('draft', 'funding', 'live', 'expired', 'settled', 'archived'));
+
+CREATE INDEX IF NOT EXISTS idx_communities_status ON communities(status);
+
+-- Audit trail of every status change
+CREATE TABLE IF NOT EXISTS community_status_transitions (
+    id UUID PRIMARY KEY,
+    community_id UUID NOT NULL,
+    from_status VARCHAR(16) NOT NULL,
+    to_status VARCHAR(16) NOT NULL,
+    transitioned_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (community_id) REFERENCES communities(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_community_status_transitions_community_id
+    ON community_status_transitions(community_id, transitioned_at);
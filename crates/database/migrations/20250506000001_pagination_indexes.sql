@@ -0,0 +1,7 @@
+-- Keyset pagination walks every list in (created_at DESC, id DESC) order
+CREATE INDEX IF NOT EXISTS idx_users_created_at_id ON users(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_communities_created_at_id ON communities(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_communities_creator_id ON communities(creator_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_content_created_at_id ON content(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_content_community_id ON content(community_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_content_sender_id ON content(sender_id, created_at DESC, id DESC);
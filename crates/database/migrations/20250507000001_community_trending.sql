@@ -0,0 +1,37 @@
+-- Trending ranking for the discovery endpoint. Scores are based on activity
+-- in the last hour and are recomputed periodically by the web server with
+-- REFRESH MATERIALIZED VIEW CONCURRENTLY, which needs the unique index below.
+CREATE MATERIALIZED VIEW IF NOT EXISTS community_trending AS
+SELECT
+    c.id AS community_id,
+    COALESCE(m.message_count, 0) AS recent_messages,
+    COALESCE(d.deposit_count, 0) AS recent_deposits,
+    COALESCE(d.deposit_amount, 0) AS recent_deposit_amount,
+    -- A deposit is worth several messages: it grows the pot everyone plays for
+    (COALESCE(m.message_count, 0) + 5 * COALESCE(d.deposit_count, 0))::DOUBLE PRECISION AS score,
+    NOW() AS refreshed_at
+FROM communities c
+LEFT JOIN (
+    SELECT community_id, COUNT(*) AS message_count
+    FROM content
+    WHERE created_at > NOW() - INTERVAL '1 hour'
+    GROUP BY community_id
+) m ON m.community_id = c.id
+LEFT JOIN (
+    SELECT community_id, COUNT(*) AS deposit_count, SUM(amount) AS deposit_amount
+    FROM depositor
+    WHERE deposited_at > NOW() - INTERVAL '1 hour'
+    GROUP BY community_id
+) d ON d.community_id = c.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_community_trending_community_id
+    ON community_trending(community_id);
+
+CREATE INDEX IF NOT EXISTS idx_community_trending_score
+    ON community_trending(score DESC);
+
+CREATE INDEX IF NOT EXISTS idx_communities_bounty_amount
+    ON communities(bounty_amount DESC);
+
+CREATE INDEX IF NOT EXISTS idx_depositor_community_id
+    ON depositor(community_id, deposited_at DESC);
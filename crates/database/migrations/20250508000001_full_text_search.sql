@@ -0,0 +1,20 @@
+-- Full-text search over communities and messages.
+--
+-- Each document is indexed twice: with the 'english' configuration for
+-- stemming, and with 'simple' so that words Postgres has no dictionary for
+-- (our Korean posts) are kept as-is. Queries search both.
+ALTER TABLE communities
+    ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('english', coalesce(description, '')), 'B') ||
+        setweight(to_tsvector('simple', coalesce(description, '')), 'B')
+    ) STORED;
+
+ALTER TABLE content
+    ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+        to_tsvector('english', content) || to_tsvector('simple', content)
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_communities_search_vector ON communities USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_content_search_vector ON content USING GIN (search_vector);
@@ -0,0 +1,17 @@
+-- Append-only log of live events per community. Ids are handed out in
+-- commit order (appends take an advisory lock), so a client that saw event N
+-- can resume by asking for everything after N.
+CREATE TABLE IF NOT EXISTS community_events (
+    id BIGSERIAL PRIMARY KEY,
+    community_id UUID NOT NULL,
+    event_type VARCHAR(32) NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (community_id) REFERENCES communities(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_community_events_community_id
+    ON community_events(community_id, id);
+
+CREATE INDEX IF NOT EXISTS idx_community_events_created_at
+    ON community_events(created_at);
@@ -0,0 +1,19 @@
+-- One row per open live connection. Every instance refreshes last_seen_at for
+-- its own connections, so rows left behind by an instance that died age out
+-- and presence stays consistent across instances.
+CREATE TABLE IF NOT EXISTS community_presence (
+    connection_id UUID PRIMARY KEY,
+    community_id UUID NOT NULL,
+    -- NULL for viewers who did not identify themselves
+    user_id UUID,
+    connected_at TIMESTAMPTZ NOT NULL,
+    last_seen_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (community_id) REFERENCES communities(id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_community_presence_community_id
+    ON community_presence(community_id, user_id);
+
+CREATE INDEX IF NOT EXISTS idx_community_presence_last_seen_at
+    ON community_presence(last_seen_at);
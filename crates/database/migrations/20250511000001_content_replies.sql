@@ -0,0 +1,8 @@
+-- Optional parent message for replies. Replies outlive a deleted parent,
+-- they just stop pointing at it.
+ALTER TABLE content
+    ADD COLUMN IF NOT EXISTS reply_to_id UUID REFERENCES content(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_content_reply_to_id
+    ON content(reply_to_id, created_at, id)
+    WHERE reply_to_id IS NOT NULL;
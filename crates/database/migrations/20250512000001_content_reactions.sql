@@ -0,0 +1,11 @@
+-- One reaction per user per message; reacting again replaces it.
+CREATE TABLE IF NOT EXISTS content_reactions (
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id),
+    reaction VARCHAR(32) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (content_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_content_reactions_content_id
+    ON content_reactions(content_id, reaction);
@@ -0,0 +1,19 @@
+-- Edits and deletes. Deleted messages stay as tombstones so a round's last
+-- message still counts; readers just stop seeing what it said.
+ALTER TABLE content
+    ADD COLUMN IF NOT EXISTS edited_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deleted_by UUID REFERENCES users(id),
+    ADD COLUMN IF NOT EXISTS delete_reason TEXT;
+
+-- What a message said before each edit
+CREATE TABLE IF NOT EXISTS content_edits (
+    id UUID PRIMARY KEY,
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    previous_content TEXT NOT NULL,
+    edited_by UUID NOT NULL,
+    edited_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_content_edits_content_id
+    ON content_edits(content_id, edited_at);
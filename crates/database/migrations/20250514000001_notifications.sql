@@ -0,0 +1,20 @@
+-- Per-user inbox: mentions, outbids and round wins.
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    kind VARCHAR(32) NOT NULL,
+    community_id UUID REFERENCES communities(id),
+    content_id UUID REFERENCES content(id) ON DELETE SET NULL,
+    -- The user whose action caused the notification, if any
+    actor_id UUID REFERENCES users(id),
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    read_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_id
+    ON notifications(user_id, created_at DESC, id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_unread
+    ON notifications(user_id)
+    WHERE read_at IS NULL;
@@ -0,0 +1,29 @@
+-- Uploaded images. The bytes live in the blob store under blob_key and
+-- thumbnail_key; this table only records what was stored.
+CREATE TABLE IF NOT EXISTS assets (
+    id UUID PRIMARY KEY,
+    -- NULL for uploads made before signing up, e.g. a first profile picture
+    owner_id UUID REFERENCES users(id),
+    mime_type VARCHAR(32) NOT NULL,
+    byte_size BIGINT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    blob_key VARCHAR(255) NOT NULL,
+    thumbnail_mime_type VARCHAR(32) NOT NULL,
+    thumbnail_key VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_assets_owner_id ON assets(owner_id);
+
+-- Images are referenced by asset instead of by client-supplied URL. The old
+-- URL columns are left in place so existing rows can still be migrated by
+-- hand; nothing reads or writes them any more.
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS profile_image_asset_id UUID REFERENCES assets(id) ON DELETE SET NULL;
+
+ALTER TABLE communities
+    ADD COLUMN IF NOT EXISTS image_asset_id UUID REFERENCES assets(id) ON DELETE SET NULL;
+
+ALTER TABLE content
+    ADD COLUMN IF NOT EXISTS image_asset_id UUID REFERENCES assets(id) ON DELETE SET NULL;
@@ -0,0 +1,28 @@
+-- Cached previews for links posted in messages, fetched in the background.
+-- One row per URL however many messages link to it.
+CREATE TABLE IF NOT EXISTS link_previews (
+    url VARCHAR(2048) PRIMARY KEY,
+    -- pending, fetching (claimed by an unfurler), ready or failed
+    status VARCHAR(16) NOT NULL,
+    title TEXT,
+    description TEXT,
+    image_url TEXT,
+    site_name TEXT,
+    -- When the row last changed status; for a claimed link, when it was claimed
+    updated_at TIMESTAMPTZ NOT NULL,
+    fetched_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_link_previews_queue
+    ON link_previews(updated_at)
+    WHERE status IN ('pending', 'fetching');
+
+-- The links in each message, in the order they appear
+CREATE TABLE IF NOT EXISTS content_links (
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    url VARCHAR(2048) NOT NULL REFERENCES link_previews(url),
+    position SMALLINT NOT NULL,
+    PRIMARY KEY (content_id, url)
+);
+
+CREATE INDEX IF NOT EXISTS idx_content_links_url ON content_links(url);
@@ -0,0 +1,31 @@
+-- Per-community moderation rules, set by the community's creator
+CREATE TABLE IF NOT EXISTS community_moderation (
+    community_id UUID PRIMARY KEY REFERENCES communities(id),
+    blocked_words TEXT[] NOT NULL DEFAULT '{}',
+    held_words TEXT[] NOT NULL DEFAULT '{}',
+    allowed_domains TEXT[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Messages a filter held back. They only become content once approved,
+-- and stay here afterwards as a record of the review.
+CREATE TABLE IF NOT EXISTS held_contents (
+    id UUID PRIMARY KEY,
+    community_id UUID NOT NULL REFERENCES communities(id),
+    sender_id UUID NOT NULL REFERENCES users(id),
+    sender_xid VARCHAR(255) NOT NULL,
+    content VARCHAR(2000) NOT NULL,
+    image_asset_id UUID REFERENCES assets(id) ON DELETE SET NULL,
+    reply_to_id UUID REFERENCES content(id) ON DELETE SET NULL,
+    wallet_address VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    reviewed_by UUID REFERENCES users(id),
+    reviewed_at TIMESTAMPTZ,
+    content_id UUID REFERENCES content(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_held_contents_queue
+    ON held_contents(community_id, created_at, id)
+    WHERE status = 'pending';
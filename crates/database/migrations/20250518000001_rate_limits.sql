@@ -0,0 +1,11 @@
+-- Per-community posting limits, set by the community's creator.
+-- NULL means the server default; a NULL per-member rate means no limit
+-- beyond the server-wide one for each user.
+CREATE TABLE IF NOT EXISTS community_rate_limits (
+    community_id UUID PRIMARY KEY REFERENCES communities(id),
+    posts_per_minute INTEGER,
+    post_burst INTEGER,
+    member_posts_per_minute INTEGER,
+    member_post_burst INTEGER,
+    updated_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,19 @@
+-- Responses to requests sent with an Idempotency-Key, replayed when the
+-- request is retried. Keys belong to the signed-in caller and are
+-- forgotten after a day; anonymous requests are never stored.
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    user_id UUID NOT NULL,
+    key VARCHAR(255) NOT NULL,
+    -- SHA-256 of the method, path and body, hex encoded
+    fingerprint VARCHAR(64) NOT NULL,
+    -- in_progress until the response is stored, then completed
+    status VARCHAR(16) NOT NULL,
+    response_status SMALLINT,
+    response_content_type VARCHAR(255),
+    response_body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (user_id, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
@@ -0,0 +1,43 @@
+-- Users who can ban and mute across the whole platform. Granted by hand.
+CREATE TABLE IF NOT EXISTS platform_admins (
+    user_id UUID PRIMARY KEY REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+-- Bans and mutes. A ban stops a user posting and depositing, a mute only
+-- stops them posting. Without a community they apply everywhere.
+CREATE TABLE IF NOT EXISTS sanctions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    community_id UUID REFERENCES communities(id),
+    kind VARCHAR(16) NOT NULL,
+    reason TEXT NOT NULL,
+    issued_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL,
+    -- NULL for sanctions that last until revoked
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    revoked_by UUID REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sanctions_user
+    ON sanctions(user_id)
+    WHERE revoked_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_sanctions_community
+    ON sanctions(community_id, created_at DESC, id DESC);
+
+-- Who did what to whom, kept after sanctions expire or are revoked
+CREATE TABLE IF NOT EXISTS moderation_audit_log (
+    id UUID PRIMARY KEY,
+    community_id UUID REFERENCES communities(id),
+    actor_id UUID NOT NULL REFERENCES users(id),
+    action VARCHAR(32) NOT NULL,
+    target_user_id UUID REFERENCES users(id),
+    sanction_id UUID REFERENCES sanctions(id),
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_audit_log_community
+    ON moderation_audit_log(community_id, created_at DESC, id DESC);
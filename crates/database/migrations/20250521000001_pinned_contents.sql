@@ -0,0 +1,11 @@
+-- Messages a community's creator keeps at the top, like rules or
+-- announcements. Pinning doesn't touch the round timer.
+CREATE TABLE IF NOT EXISTS pinned_contents (
+    content_id UUID PRIMARY KEY REFERENCES content(id),
+    community_id UUID NOT NULL REFERENCES communities(id),
+    pinned_by UUID NOT NULL REFERENCES users(id),
+    pinned_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_pinned_contents_community
+    ON pinned_contents(community_id, pinned_at DESC);
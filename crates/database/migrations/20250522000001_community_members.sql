@@ -0,0 +1,56 @@
+-- Who belongs to each community. Public communities let anyone join;
+-- private ones take an invite code or the creator's approval, and only
+-- their members can post.
+ALTER TABLE communities
+    ADD COLUMN IF NOT EXISTS visibility VARCHAR(16) NOT NULL DEFAULT 'public',
+    -- Active members, kept up to date with community_members
+    ADD COLUMN IF NOT EXISTS member_count INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE communities
+    DROP CONSTRAINT IF EXISTS communities_visibility_check,
+    ADD CONSTRAINT communities_visibility_check
+    CHECK (visibility IN ('public', 'private'));
+
+CREATE TABLE IF NOT EXISTS community_members (
+    community_id UUID NOT NULL REFERENCES communities(id),
+    user_id UUID NOT NULL REFERENCES users(id),
+    -- pending until the creator approves a request to join
+    status VARCHAR(16) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    joined_at TIMESTAMPTZ,
+    -- The invite code used to join, if any
+    invite_code VARCHAR(32),
+    PRIMARY KEY (community_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_community_members_community
+    ON community_members(community_id, status, created_at, user_id);
+
+CREATE INDEX IF NOT EXISTS idx_community_members_user
+    ON community_members(user_id)
+    WHERE status = 'active';
+
+-- Codes a creator hands out to let people into a private community
+CREATE TABLE IF NOT EXISTS community_invites (
+    code VARCHAR(32) PRIMARY KEY,
+    community_id UUID NOT NULL REFERENCES communities(id),
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_community_invites_community
+    ON community_invites(community_id, created_at DESC);
+
+-- Creators are members of their own communities
+INSERT INTO community_members (community_id, user_id, status, created_at, joined_at)
+SELECT c.id, c.creator_id, 'active', c.created_at, c.created_at
+FROM communities c
+JOIN users u ON u.id = c.creator_id
+ON CONFLICT DO NOTHING;
+
+UPDATE communities c
+SET member_count = (
+    SELECT COUNT(*) FROM community_members m
+    WHERE m.community_id = c.id AND m.status = 'active'
+);
@@ -0,0 +1,30 @@
+-- Invites can be limited to a number of uses and a lifetime, and any member
+-- can make one, not just the creator
+ALTER TABLE community_invites
+    ADD COLUMN max_uses INTEGER,
+    ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN expires_at TIMESTAMPTZ;
+
+-- Who brought each user into each community. The first invite a user joins
+-- through keeps the credit, even if they leave and come back another way.
+CREATE TABLE IF NOT EXISTS community_referrals (
+    community_id UUID NOT NULL REFERENCES communities(id),
+    user_id UUID NOT NULL REFERENCES users(id),
+    invite_code VARCHAR(32) NOT NULL REFERENCES community_invites(code),
+    referrer_id UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (community_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_community_referrals_referrer
+    ON community_referrals(referrer_id, community_id);
+
+INSERT INTO community_referrals (community_id, user_id, invite_code, referrer_id, created_at)
+SELECT m.community_id, m.user_id, m.invite_code, i.created_by, COALESCE(m.joined_at, m.created_at)
+FROM community_members m
+JOIN community_invites i ON i.code = m.invite_code
+WHERE i.created_by <> m.user_id
+ON CONFLICT DO NOTHING;
+
+UPDATE community_invites i
+SET use_count = (SELECT COUNT(*) FROM community_members m WHERE m.invite_code = i.code);
@@ -0,0 +1,32 @@
+-- Money owed to users, paid out as one record per claim. Only referral
+-- rewards go through here so far.
+CREATE TABLE IF NOT EXISTS payouts (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    kind VARCHAR(32) NOT NULL,
+    amount DECIMAL(20, 8) NOT NULL CHECK (amount > 0),
+    wallet_address VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_payouts_user
+    ON payouts(user_id, created_at DESC);
+
+-- A referrer's share of the platform fee on each deposit made by someone they
+-- brought in. The community and deposit aren't foreign keys so that rewards
+-- already earned survive the community being deleted. Claiming marks rewards
+-- before their payout is written, so payout_id is only checked at commit.
+CREATE TABLE IF NOT EXISTS referral_rewards (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    community_id UUID NOT NULL,
+    deposit_id UUID NOT NULL UNIQUE,
+    referred_user_id UUID NOT NULL REFERENCES users(id),
+    platform_fee DECIMAL(20, 8) NOT NULL,
+    amount DECIMAL(20, 8) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    payout_id UUID REFERENCES payouts(id) DEFERRABLE INITIALLY DEFERRED
+);
+
+CREATE INDEX IF NOT EXISTS idx_referral_rewards_unclaimed
+    ON referral_rewards(user_id) WHERE payout_id IS NULL;
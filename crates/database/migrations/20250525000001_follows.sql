@@ -0,0 +1,26 @@
+-- Users follow other users and communities to fill their home feed
+CREATE TABLE IF NOT EXISTS user_follows (
+    follower_id UUID NOT NULL REFERENCES users(id),
+    followed_id UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (follower_id, followed_id),
+    CHECK (follower_id <> followed_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_follows_followed
+    ON user_follows(followed_id);
+
+CREATE TABLE IF NOT EXISTS community_follows (
+    user_id UUID NOT NULL REFERENCES users(id),
+    community_id UUID NOT NULL REFERENCES communities(id),
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (user_id, community_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_community_follows_community
+    ON community_follows(community_id);
+
+-- The feed reads the latest round results of each followed community
+CREATE INDEX IF NOT EXISTS idx_community_status_transitions_round_results
+    ON community_status_transitions(community_id, transitioned_at DESC, id DESC)
+    WHERE to_status = 'expired';
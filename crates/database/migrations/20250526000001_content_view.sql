@@ -0,0 +1,30 @@
+-- Messages as clients see them: tombstones withhold their text, image and
+-- link cards, and each message carries its reply and reaction counts.
+-- Queries that return messages select from here instead of repeating this.
+CREATE OR REPLACE VIEW content_view AS
+SELECT
+    c.id,
+    CASE WHEN c.deleted_at IS NULL THEN c.content ELSE '' END as content,
+    c.created_at, c.sender_id, c.sender_xid,
+    CASE WHEN c.deleted_at IS NULL THEN c.image_asset_id END as image_asset_id,
+    c.community_id, c.wallet_address, c.reply_to_id, c.edited_at, c.deleted_at,
+    (SELECT COUNT(*) FROM content r WHERE r.reply_to_id = c.id) as reply_count,
+    (
+        SELECT COALESCE(jsonb_object_agg(x.reaction, x.count), '{}')
+        FROM (
+            SELECT reaction, COUNT(*) as count
+            FROM content_reactions
+            WHERE content_id = c.id
+            GROUP BY reaction
+        ) x
+    ) as reactions,
+    (
+        SELECT COALESCE(jsonb_agg(jsonb_build_object(
+            'url', p.url, 'title', p.title, 'description', p.description,
+            'imageUrl', p.image_url, 'siteName', p.site_name
+        ) ORDER BY l.position), '[]')
+        FROM content_links l
+        JOIN link_previews p ON p.url = l.url
+        WHERE l.content_id = c.id AND p.status = 'ready' AND c.deleted_at IS NULL
+    ) as previews
+FROM content c;
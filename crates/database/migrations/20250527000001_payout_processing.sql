@@ -0,0 +1,19 @@
+-- Round winnings are paid through the same ledger as referral rewards, one
+-- payout per winning message. The content isn't a foreign key so that a
+-- payout survives its community being deleted.
+ALTER TABLE payouts ADD COLUMN IF NOT EXISTS community_id UUID;
+ALTER TABLE payouts ADD COLUMN IF NOT EXISTS content_id UUID;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_payouts_round_winnings
+    ON payouts(content_id) WHERE kind = 'round_winnings';
+
+-- Every payout is handed to the payment service in the background. While a
+-- payout is pending, next_attempt_at is when to try it next; while it is
+-- processing, it is when the attempt counts as abandoned.
+ALTER TABLE payouts ADD COLUMN IF NOT EXISTS status VARCHAR(16) NOT NULL DEFAULT 'pending';
+ALTER TABLE payouts ADD COLUMN IF NOT EXISTS attempts INT NOT NULL DEFAULT 0;
+ALTER TABLE payouts ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE payouts ADD COLUMN IF NOT EXISTS sent_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_payouts_due
+    ON payouts(next_attempt_at) WHERE status IN ('pending', 'processing');
@@ -79,7 +79,7 @@ mod tests {
     #[tokio::test]
     async fn test_database_connection() {
         // This test only runs if the DATABASE_URL environment variable is set
-        if let Ok(_) = env::var("DATABASE_URL") {
+        if env::var("DATABASE_URL").is_ok() {
             let db = Database::new().await;
             assert!(db.is_ok(), "Database connection failed");
 
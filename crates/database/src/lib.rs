@@ -1,3 +1,6 @@
 pub mod connection;
 pub mod model;
 pub mod repository;
+
+// Re-exported so dependants can name sqlx types without depending on it directly
+pub use sqlx;
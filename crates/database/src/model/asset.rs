@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Image types accepted for upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar")]
+pub enum ImageMime {
+    #[serde(rename = "image/jpeg")]
+    #[sqlx(rename = "image/jpeg")]
+    Jpeg,
+    #[serde(rename = "image/png")]
+    #[sqlx(rename = "image/png")]
+    Png,
+    #[serde(rename = "image/gif")]
+    #[sqlx(rename = "image/gif")]
+    Gif,
+    #[serde(rename = "image/webp")]
+    #[sqlx(rename = "image/webp")]
+    Webp,
+}
+
+impl ImageMime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageMime::Jpeg => "image/jpeg",
+            ImageMime::Png => "image/png",
+            ImageMime::Gif => "image/gif",
+            ImageMime::Webp => "image/webp",
+        }
+    }
+
+    /// Work out the type from the file's leading bytes. Whatever the client
+    /// claims the file is, this is what decides whether it is accepted.
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageMime::Jpeg)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageMime::Png)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(ImageMime::Gif)
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            Some(ImageMime::Webp)
+        } else {
+            None
+        }
+    }
+}
+
+/// An uploaded image and its thumbnail
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Asset {
+    pub id: Uuid,
+    #[serde(rename = "ownerId")]
+    pub owner_id: Option<Uuid>,
+    #[serde(rename = "mimeType")]
+    pub mime_type: ImageMime,
+    #[serde(rename = "byteSize")]
+    pub byte_size: i64,
+    pub width: i32,
+    pub height: i32,
+    #[serde(skip)]
+    pub blob_key: String,
+    #[serde(rename = "thumbnailMimeType")]
+    pub thumbnail_mime_type: ImageMime,
+    #[serde(skip)]
+    pub thumbnail_key: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Asset {
+    /// Where the blob store keeps the original of asset `id`
+    pub fn blob_key_for(id: Uuid) -> String {
+        format!("assets/{}", id)
+    }
+
+    /// Where the blob store keeps the thumbnail of asset `id`
+    pub fn thumbnail_key_for(id: Uuid) -> String {
+        format!("assets/{}-thumb", id)
+    }
+}
+
+/// Which of an asset's stored files to fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetVariant {
+    Original,
+    Thumbnail,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_by_magic_bytes() {
+        assert_eq!(ImageMime::sniff(b"\xFF\xD8\xFF\xE0\x00\x10JFIF"), Some(ImageMime::Jpeg));
+        assert_eq!(ImageMime::sniff(b"\x89PNG\r\n\x1a\n\x00\x00"), Some(ImageMime::Png));
+        assert_eq!(ImageMime::sniff(b"GIF89a\x01\x00"), Some(ImageMime::Gif));
+        assert_eq!(ImageMime::sniff(b"RIFF\x24\x00\x00\x00WEBPVP8 "), Some(ImageMime::Webp));
+        assert_eq!(ImageMime::sniff(b"RIFF\x24\x00\x00\x00WAVEfmt "), None);
+        assert_eq!(ImageMime::sniff(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"), None);
+        assert_eq!(ImageMime::sniff(b""), None);
+    }
+}
@@ -1,9 +1,126 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use rust_decimal::Decimal;
 
+/// Lifecycle state of a community.
+///
+/// draft -> funding -> live -> expired -> settled -> archived, with a settled
+/// community allowed to open a new round and drafts/funding rounds allowed to
+/// be abandoned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum CommunityStatus {
+    Draft,
+    Funding,
+    Live,
+    Expired,
+    Settled,
+    Archived,
+}
+
+impl CommunityStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommunityStatus::Draft => "draft",
+            CommunityStatus::Funding => "funding",
+            CommunityStatus::Live => "live",
+            CommunityStatus::Expired => "expired",
+            CommunityStatus::Settled => "settled",
+            CommunityStatus::Archived => "archived",
+        }
+    }
+
+    /// Whether moving from `self` to `next` is an allowed transition
+    pub fn can_transition_to(&self, next: CommunityStatus) -> bool {
+        use CommunityStatus::*;
+        matches!(
+            (self, next),
+            (Draft, Funding)
+                | (Draft, Archived)
+                | (Funding, Live)
+                | (Funding, Archived)
+                | (Live, Expired)
+                | (Expired, Settled)
+                | (Settled, Funding)
+                | (Settled, Archived)
+        )
+    }
+
+    /// Whether the round engine alone moves communities into this status.
+    ///
+    /// A round only ends once its deadline has passed, and settlement pays
+    /// it out, so creators can trigger neither themselves.
+    pub fn is_set_by_round_engine(&self) -> bool {
+        matches!(self, CommunityStatus::Expired | CommunityStatus::Settled)
+    }
+
+    /// Messages can only be posted while the round is live
+    pub fn accepts_content(&self) -> bool {
+        *self == CommunityStatus::Live
+    }
+
+    /// Deposits are accepted while funding and during a live round
+    pub fn accepts_deposits(&self) -> bool {
+        matches!(self, CommunityStatus::Funding | CommunityStatus::Live)
+    }
+
+    /// Fields a creator may edit in this status, by their JSON names.
+    ///
+    /// Round parameters are frozen once money can be in the pot; only the
+    /// presentation of the community can change after that.
+    pub fn editable_fields(&self) -> &'static [&'static str] {
+        match self {
+            CommunityStatus::Draft => &[
+                "name", "description", "imageAssetId", "visibility", "timeLimit",
+                "baseFeePercentage", "contractAddress", "walletAddress",
+            ],
+            CommunityStatus::Funding => &["name", "description", "imageAssetId", "visibility", "timeLimit"],
+            CommunityStatus::Live
+            | CommunityStatus::Expired
+            | CommunityStatus::Settled => &["name", "description", "imageAssetId", "visibility"],
+            CommunityStatus::Archived => &[],
+        }
+    }
+
+    /// A community can only be deleted when it holds no undistributed funds
+    pub fn can_delete(&self) -> bool {
+        matches!(
+            self,
+            CommunityStatus::Draft | CommunityStatus::Settled | CommunityStatus::Archived
+        )
+    }
+}
+
+impl std::fmt::Display for CommunityStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Who can join a community and post in it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum CommunityVisibility {
+    /// Anyone can join, and anyone can post
+    #[default]
+    Public,
+    /// Joining takes an invite code or the creator's approval, and only members can post
+    Private,
+}
+
+impl CommunityVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommunityVisibility::Public => "public",
+            CommunityVisibility::Private => "private",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Community {
     pub id: Uuid,
@@ -27,8 +144,29 @@ pub struct Community {
     pub base_fee_percentage: Option<f32>,
     #[serde(rename = "walletAddress")]
     pub wallet_address: Option<String>,
-    #[serde(rename = "imageURL")]
-    pub image_url: Option<String>,
+    #[serde(rename = "imageAssetId")]
+    pub image_asset_id: Option<Uuid>,
+    pub status: CommunityStatus,
+    #[serde(rename = "statusUpdatedAt")]
+    pub status_updated_at: DateTime<Utc>,
+    pub visibility: CommunityVisibility,
+    /// Active members, the creator included
+    #[serde(rename = "memberCount")]
+    pub member_count: i32,
+}
+
+impl Community {
+    /// When the current round ends if nobody posts, or `None` if the community
+    /// has no time limit. The timer runs from the last message, or from when
+    /// the round went live if nobody has posted yet.
+    pub fn round_deadline(&self) -> Option<DateTime<Utc>> {
+        let time_limit = self.time_limit?;
+        let started = match self.last_message_time {
+            Some(last) if last > self.status_updated_at => last,
+            _ => self.status_updated_at,
+        };
+        Some(started + Duration::seconds(time_limit as i64))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -50,8 +188,10 @@ pub struct CreateCommunityDto {
     pub base_fee_percentage: Option<f32>,
     #[serde(rename = "walletAddress")]
     pub wallet_address: Option<String>,
-    #[serde(rename = "imageURL")]
-    pub image_url: Option<String>,
+    #[serde(rename = "imageAssetId")]
+    pub image_asset_id: Option<Uuid>,
+    /// Public if left out
+    pub visibility: Option<CommunityVisibility>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,6 +210,132 @@ pub struct UpdateCommunityDto {
     pub base_fee_percentage: Option<f32>,
     #[serde(rename = "walletAddress")]
     pub wallet_address: Option<String>,
-    #[serde(rename = "imageURL")]
-    pub image_url: Option<String>,
+    #[serde(rename = "imageAssetId")]
+    pub image_asset_id: Option<Uuid>,
+    pub visibility: Option<CommunityVisibility>,
+}
+
+impl UpdateCommunityDto {
+    /// JSON names of the fields this update sets
+    pub fn set_fields(&self) -> Vec<&'static str> {
+        [
+            ("name", self.name.is_some()),
+            ("description", self.description.is_some()),
+            ("lastMessageTime", self.last_message_time.is_some()),
+            ("contractAddress", self.contract_address.is_some()),
+            ("bountyAmount", self.bounty_amount.is_some()),
+            ("timeLimit", self.time_limit.is_some()),
+            ("baseFeePercentage", self.base_fee_percentage.is_some()),
+            ("walletAddress", self.wallet_address.is_some()),
+            ("imageAssetId", self.image_asset_id.is_some()),
+            ("visibility", self.visibility.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, set)| set.then_some(field))
+        .collect()
+    }
+}
+
+/// Query parameters for listing communities
+#[derive(Debug, Default, Deserialize)]
+pub struct CommunityListQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub status: Option<CommunityStatus>,
+    #[serde(rename = "creatorId")]
+    pub creator_id: Option<Uuid>,
+    #[serde(rename = "minBounty")]
+    pub min_bounty: Option<Decimal>,
+}
+
+/// Ranking used by the discovery endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoverSort {
+    /// Most recent message and deposit activity
+    #[default]
+    Trending,
+    /// Live rounds closest to running out of time
+    EndingSoon,
+    /// Biggest pots first
+    LargestBounty,
+    /// Most recently created first
+    Newest,
+}
+
+/// Query parameters for the discovery endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct DiscoverQuery {
+    #[serde(default)]
+    pub sort: DiscoverSort,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCommunityStatusDto {
+    pub status: CommunityStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CommunityStatusTransition {
+    pub id: Uuid,
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(rename = "fromStatus")]
+    pub from_status: CommunityStatus,
+    #[serde(rename = "toStatus")]
+    pub to_status: CommunityStatus,
+    #[serde(rename = "transitionedAt")]
+    pub transitioned_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_transitions() {
+        assert!(CommunityStatus::Draft.can_transition_to(CommunityStatus::Funding));
+        assert!(CommunityStatus::Funding.can_transition_to(CommunityStatus::Live));
+        assert!(CommunityStatus::Live.can_transition_to(CommunityStatus::Expired));
+        assert!(CommunityStatus::Expired.can_transition_to(CommunityStatus::Settled));
+        assert!(CommunityStatus::Settled.can_transition_to(CommunityStatus::Funding));
+
+        assert!(!CommunityStatus::Draft.can_transition_to(CommunityStatus::Live));
+        assert!(!CommunityStatus::Live.can_transition_to(CommunityStatus::Archived));
+        assert!(!CommunityStatus::Archived.can_transition_to(CommunityStatus::Draft));
+        assert!(!CommunityStatus::Live.can_transition_to(CommunityStatus::Live));
+    }
+
+    #[test]
+    fn test_round_engine_statuses() {
+        assert!(CommunityStatus::Expired.is_set_by_round_engine());
+        assert!(CommunityStatus::Settled.is_set_by_round_engine());
+        assert!(!CommunityStatus::Live.is_set_by_round_engine());
+        assert!(!CommunityStatus::Archived.is_set_by_round_engine());
+    }
+
+    #[test]
+    fn test_status_permissions() {
+        assert!(CommunityStatus::Live.accepts_content());
+        assert!(!CommunityStatus::Funding.accepts_content());
+        assert!(CommunityStatus::Funding.accepts_deposits());
+        assert!(CommunityStatus::Live.accepts_deposits());
+        assert!(!CommunityStatus::Expired.accepts_deposits());
+    }
+
+    #[test]
+    fn test_editable_fields_narrow_as_round_progresses() {
+        assert!(CommunityStatus::Draft.editable_fields().contains(&"baseFeePercentage"));
+        assert!(CommunityStatus::Funding.editable_fields().contains(&"timeLimit"));
+        assert!(!CommunityStatus::Live.editable_fields().contains(&"timeLimit"));
+        assert!(CommunityStatus::Live.editable_fields().contains(&"name"));
+        assert!(CommunityStatus::Archived.editable_fields().is_empty());
+
+        // Engine-managed fields are never editable
+        for status in [CommunityStatus::Draft, CommunityStatus::Funding, CommunityStatus::Live] {
+            assert!(!status.editable_fields().contains(&"bountyAmount"));
+            assert!(!status.editable_fields().contains(&"lastMessageTime"));
+        }
+    }
 }
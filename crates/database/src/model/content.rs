@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::types::Json;
+
+use crate::model::link_preview::LinkPreview;
+use crate::model::pagination::Page;
+use crate::model::reaction::ReactionCounts;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Content {
@@ -13,12 +18,75 @@ pub struct Content {
     pub sender_id: Uuid,
     #[serde(rename = "senderXid")]
     pub sender_xid: String,
-    #[serde(rename = "imageURL")]
-    pub image_url: Option<String>,
+    #[serde(rename = "imageAssetId")]
+    pub image_asset_id: Option<Uuid>,
     #[serde(rename = "communityId")]
     pub community_id: Uuid,
     #[serde(rename = "walletAddress")]
     pub wallet_address: String,
+    /// The message this one replies to
+    #[serde(rename = "replyToId")]
+    pub reply_to_id: Option<Uuid>,
+    /// Number of direct replies
+    #[serde(rename = "replyCount")]
+    pub reply_count: i64,
+    #[serde(rename = "editedAt")]
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Set on tombstones, whose text and image are withheld
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Number of reactions of each kind
+    pub reactions: Json<ReactionCounts>,
+    /// Cards for the links in the message, once they have been fetched
+    pub previews: Json<Vec<LinkPreview>>,
+}
+
+impl Content {
+    /// Whether the sender can still edit the message at `now`
+    pub fn editable_at(&self, now: DateTime<Utc>) -> bool {
+        self.deleted_at.is_none() && now < self.created_at + EDIT_WINDOW
+    }
+}
+
+/// How long after posting a sender can still edit a message
+pub const EDIT_WINDOW: Duration = Duration::minutes(5);
+
+/// Most messages a community can have pinned at once
+pub const MAX_PINNED_CONTENTS: i64 = 5;
+
+/// A page of a community's messages, with its pinned messages on top
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommunityContents {
+    #[serde(flatten)]
+    pub page: Page<Content>,
+    /// Most recently pinned first
+    pub pinned: Vec<Content>,
+}
+
+/// An earlier version of an edited message
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ContentEdit {
+    pub id: Uuid,
+    #[serde(rename = "contentId")]
+    pub content_id: Uuid,
+    #[serde(rename = "previousContent")]
+    pub previous_content: String,
+    #[serde(rename = "editedBy")]
+    pub edited_by: Uuid,
+    #[serde(rename = "editedAt")]
+    pub edited_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateContentDto {
+    pub content: String,
+}
+
+/// Query parameters for deleting content
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteContentQuery {
+    /// Required when a moderator deletes someone else's message
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,11 +96,65 @@ pub struct CreateContentDto {
     pub sender_id: Option<Uuid>,
     #[serde(rename = "senderXid")]
     pub sender_xid: Option<String>,
-    #[serde(rename = "imageURL")]
-    pub image_url: Option<String>,
+    #[serde(rename = "imageAssetId")]
+    pub image_asset_id: Option<Uuid>,
     #[serde(rename = "communityId")]
     pub community_id: Uuid,
     #[serde(rename = "walletAddress")]
     pub wallet_address: Option<String>,
+    /// Must be a message in the same community
+    #[serde(rename = "replyToId")]
+    pub reply_to_id: Option<Uuid>,
+}
+
+/// Query parameters for listing content
+#[derive(Debug, Default, Deserialize)]
+pub struct ContentListQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    #[serde(rename = "senderId")]
+    pub sender_id: Option<Uuid>,
+    /// Only content created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only content created before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for listing the replies to a message
+#[derive(Debug, Default, Deserialize)]
+pub struct ReplyListQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editable_within_window() {
+        let posted = Utc::now();
+        let content = Content {
+            id: Uuid::new_v4(),
+            content: "gm".to_string(),
+            created_at: posted,
+            sender_id: Uuid::new_v4(),
+            sender_xid: "xid".to_string(),
+            image_asset_id: None,
+            community_id: Uuid::new_v4(),
+            wallet_address: String::new(),
+            reply_to_id: None,
+            reply_count: 0,
+            edited_at: None,
+            deleted_at: None,
+            reactions: Json(ReactionCounts::new()),
+            previews: Json(Vec::new()),
+        };
+
+        assert!(content.editable_at(posted + Duration::minutes(4)));
+        assert!(!content.editable_at(posted + EDIT_WINDOW));
+
+        let deleted = Content { deleted_at: Some(posted), ..content };
+        assert!(!deleted.editable_at(posted));
+    }
+}
@@ -8,12 +8,25 @@ use rust_decimal::Decimal;
 pub struct Depositor {
     pub id: Uuid,
     #[serde(rename = "userId")]
-    pub user_id: String,
+    pub user_id: Uuid,
+    #[serde(rename = "userXid")]
+    pub user_xid: String,
     #[serde(rename = "communityId")]
-    pub community_id: String,
+    pub community_id: Uuid,
     pub amount: Decimal,
     #[serde(rename = "walletAddress")]
     pub wallet_address: Option<String>,
     #[serde(rename = "depositedAt")]
     pub deposited_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateDepositDto {
+    #[serde(rename = "userId")]
+    pub user_id: Option<Uuid>,
+    #[serde(rename = "userXid", skip_serializing_if = "Option::is_none")]
+    pub user_xid: Option<String>,
+    pub amount: Decimal,
+    #[serde(rename = "walletAddress")]
+    pub wallet_address: Option<String>,
+}
@@ -0,0 +1,389 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::model::community::Community;
+use crate::model::content::Content;
+use crate::model::depositor::Depositor;
+use crate::model::payout::Payout;
+use crate::model::presence::{PresenceChange, PresenceCounts};
+use crate::model::reaction::ReactionSummary;
+
+/// Kinds of live events streamed to community subscribers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum EventType {
+    /// A community left draft and opened for funding
+    CommunityLaunched,
+    /// A message was posted
+    ContentCreated,
+    /// A message's sender changed its text
+    ContentEdited,
+    /// A message was turned into a tombstone
+    ContentDeleted,
+    /// The bounty changed
+    PotUpdated,
+    /// A message restarted the round timer
+    TimerReset,
+    /// A round went live and its timer started
+    RoundStarted,
+    /// The round timer ran out
+    RoundEnded,
+    /// The pot of an ended round was paid out
+    RoundSettled,
+    /// Someone started or stopped watching
+    PresenceChanged,
+    /// A message's reactions changed
+    ReactionChanged,
+    /// A message was pinned or unpinned
+    PinsChanged,
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::CommunityLaunched => "community_launched",
+            EventType::ContentCreated => "content_created",
+            EventType::ContentEdited => "content_edited",
+            EventType::ContentDeleted => "content_deleted",
+            EventType::PotUpdated => "pot_updated",
+            EventType::TimerReset => "timer_reset",
+            EventType::RoundStarted => "round_started",
+            EventType::RoundEnded => "round_ended",
+            EventType::RoundSettled => "round_settled",
+            EventType::PresenceChanged => "presence_changed",
+            EventType::ReactionChanged => "reaction_changed",
+            EventType::PinsChanged => "pins_changed",
+        }
+    }
+
+    /// Parse the `snake_case` name used on the wire
+    pub fn parse(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
+
+    /// Whether every event of this type goes on the platform-wide ticker.
+    /// Deposits only do when they are big, see `CommunityEvent::is_global`;
+    /// everything else only matters to people watching the community.
+    pub fn is_global(&self) -> bool {
+        matches!(
+            self,
+            EventType::CommunityLaunched
+                | EventType::RoundStarted
+                | EventType::RoundEnded
+                | EventType::RoundSettled
+        )
+    }
+}
+
+/// An event in a community's live feed, as stored and as sent to clients
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CommunityEvent {
+    pub id: i64,
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: EventType,
+    #[serde(rename = "data")]
+    pub payload: serde_json::Value,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl CommunityEvent {
+    /// How this event moves the round deadline: `Some(Some(t))` for a new
+    /// deadline, `Some(None)` when the round is over, `None` if unchanged
+    pub fn deadline_change(&self) -> Option<Option<DateTime<Utc>>> {
+        match self.event_type {
+            EventType::RoundStarted | EventType::TimerReset => {
+                Some(serde_json::from_value(self.payload["deadline"].clone()).ok())
+            }
+            EventType::RoundEnded | EventType::RoundSettled => Some(None),
+            _ => None,
+        }
+    }
+
+    /// Whether the event goes on the platform-wide ticker: new communities,
+    /// round changes and deposits of at least `big_deposit`
+    pub fn is_global(&self, big_deposit: Decimal) -> bool {
+        match self.event_type {
+            EventType::PotUpdated => serde_json::from_value::<Decimal>(self.payload["deposit"]["amount"].clone())
+                .is_ok_and(|amount| amount >= big_deposit),
+            event_type => event_type.is_global(),
+        }
+    }
+}
+
+/// An event that has not been appended to the log yet
+#[derive(Debug, Clone)]
+pub struct NewCommunityEvent {
+    pub community_id: Uuid,
+    pub event_type: EventType,
+    pub payload: serde_json::Value,
+}
+
+impl NewCommunityEvent {
+    pub fn community_launched(community: &Community) -> Self {
+        Self {
+            community_id: community.id,
+            event_type: EventType::CommunityLaunched,
+            payload: serde_json::to_value(community).unwrap_or_default(),
+        }
+    }
+
+    pub fn content_created(content: &Content) -> Self {
+        Self {
+            community_id: content.community_id,
+            event_type: EventType::ContentCreated,
+            payload: serde_json::to_value(content).unwrap_or_default(),
+        }
+    }
+
+    pub fn content_edited(content: &Content) -> Self {
+        Self {
+            community_id: content.community_id,
+            event_type: EventType::ContentEdited,
+            payload: serde_json::to_value(content).unwrap_or_default(),
+        }
+    }
+
+    pub fn content_deleted(content: &Content, by_moderator: bool) -> Self {
+        Self {
+            community_id: content.community_id,
+            event_type: EventType::ContentDeleted,
+            payload: serde_json::json!({
+                "contentId": content.id,
+                "deletedAt": content.deleted_at,
+                "byModerator": by_moderator,
+            }),
+        }
+    }
+
+    pub fn pins_changed(content: &Content, pinned: bool) -> Self {
+        Self {
+            community_id: content.community_id,
+            event_type: EventType::PinsChanged,
+            payload: serde_json::json!({
+                "contentId": content.id,
+                "pinned": pinned,
+            }),
+        }
+    }
+
+    pub fn timer_reset(community: &Community) -> Self {
+        Self {
+            community_id: community.id,
+            event_type: EventType::TimerReset,
+            payload: serde_json::json!({
+                "lastMessageTime": community.last_message_time,
+                "deadline": community.round_deadline(),
+            }),
+        }
+    }
+
+    pub fn round_started(community: &Community) -> Self {
+        Self {
+            community_id: community.id,
+            event_type: EventType::RoundStarted,
+            payload: serde_json::json!({
+                "status": community.status,
+                "startedAt": community.status_updated_at,
+                "deadline": community.round_deadline(),
+            }),
+        }
+    }
+
+    pub fn pot_updated(community_id: Uuid, bounty_amount: Decimal, deposit: &Depositor) -> Self {
+        Self {
+            community_id,
+            event_type: EventType::PotUpdated,
+            payload: serde_json::json!({
+                "bountyAmount": bounty_amount,
+                "deposit": deposit,
+            }),
+        }
+    }
+
+    /// Goes to everyone watching, so it says nothing about who joined or
+    /// left; members get the list of online users from the presence
+    /// endpoint. The counts are absolute and always current
+    pub fn presence_changed(community_id: Uuid, change: PresenceChange, counts: PresenceCounts) -> Self {
+        Self {
+            community_id,
+            event_type: EventType::PresenceChanged,
+            payload: serde_json::json!({
+                "change": change,
+                "viewers": counts.viewers,
+                "onlineUsers": counts.online_users,
+            }),
+        }
+    }
+
+    /// `reaction` is the user's reaction now, `None` if they removed it;
+    /// the counts are absolute
+    pub fn reaction_changed(community_id: Uuid, user_id: Uuid, reaction: Option<&str>, summary: &ReactionSummary) -> Self {
+        Self {
+            community_id,
+            event_type: EventType::ReactionChanged,
+            payload: serde_json::json!({
+                "contentId": summary.content_id,
+                "userId": user_id,
+                "reaction": reaction,
+                "reactions": summary.reactions,
+            }),
+        }
+    }
+
+    /// `last_content` is the message that was standing when the timer ran out
+    pub fn round_ended(community: &Community, last_content: Option<&Content>) -> Self {
+        Self {
+            community_id: community.id,
+            event_type: EventType::RoundEnded,
+            payload: serde_json::json!({
+                "status": community.status,
+                "endedAt": community.status_updated_at,
+                "bountyAmount": community.bounty_amount,
+                "lastContent": last_content,
+            }),
+        }
+    }
+
+    /// `winnings` is what the round paid out, if it had a winner and a bounty
+    pub fn round_settled(community: &Community, winnings: Option<&Payout>) -> Self {
+        Self {
+            community_id: community.id,
+            event_type: EventType::RoundSettled,
+            payload: serde_json::json!({
+                "status": community.status,
+                "settledAt": community.status_updated_at,
+                "bountyAmount": community.bounty_amount,
+                "winnerId": winnings.map(|payout| payout.user_id),
+                "winnings": winnings.map(|payout| payout.amount),
+            }),
+        }
+    }
+}
+
+/// Query parameters for the global event stream
+#[derive(Debug, Default, Deserialize)]
+pub struct EventStreamQuery {
+    #[serde(rename = "communityId")]
+    pub community_id: Option<Uuid>,
+    /// Comma-separated event types to include
+    #[serde(rename = "type")]
+    pub types: Option<String>,
+}
+
+/// Which events a stream subscriber wants to see
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFilter {
+    pub community_id: Option<Uuid>,
+    /// `None` means every event meant for the global ticker
+    pub types: Option<Vec<EventType>>,
+    /// Smallest deposit the global ticker shows
+    pub big_deposit: Decimal,
+}
+
+impl EventFilter {
+    /// Build a filter from query parameters, or `None` if a type is unknown
+    pub fn from_query(query: &EventStreamQuery, big_deposit: Decimal) -> Option<Self> {
+        let types = match &query.types {
+            Some(types) => Some(
+                types
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(EventType::parse)
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+            None => None,
+        };
+
+        Some(Self { community_id: query.community_id, types, big_deposit })
+    }
+
+    pub fn matches(&self, event: &CommunityEvent) -> bool {
+        if self.community_id.is_some_and(|id| id != event.community_id) {
+            return false;
+        }
+
+        match &self.types {
+            Some(types) => types.contains(&event.event_type),
+            None => event.is_global(self.big_deposit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(community_id: Uuid, event_type: EventType) -> CommunityEvent {
+        CommunityEvent {
+            id: 1,
+            community_id,
+            event_type,
+            payload: serde_json::Value::Null,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_deadline_change() {
+        let deadline = DateTime::from_timestamp(1_714_780_860, 0).unwrap();
+        let mut reset = event(Uuid::new_v4(), EventType::TimerReset);
+        reset.payload = serde_json::json!({ "deadline": deadline });
+
+        assert_eq!(reset.deadline_change(), Some(Some(deadline)));
+        assert_eq!(event(reset.community_id, EventType::RoundEnded).deadline_change(), Some(None));
+        assert_eq!(event(reset.community_id, EventType::PotUpdated).deadline_change(), None);
+    }
+
+    #[test]
+    fn test_event_filter_from_query() {
+        let query = EventStreamQuery { community_id: None, types: Some("pot_updated, round_ended".to_string()) };
+        let filter = EventFilter::from_query(&query, Decimal::ONE_HUNDRED).unwrap();
+        assert_eq!(filter.types, Some(vec![EventType::PotUpdated, EventType::RoundEnded]));
+
+        let query = EventStreamQuery { community_id: None, types: Some("pot_updated,bogus".to_string()) };
+        assert_eq!(EventFilter::from_query(&query, Decimal::ONE_HUNDRED), None);
+    }
+
+    #[test]
+    fn test_event_filter_matches() {
+        let community_id = Uuid::new_v4();
+        let everything = EventFilter { community_id: None, types: None, big_deposit: Decimal::ONE_HUNDRED };
+        assert!(everything.matches(&event(community_id, EventType::RoundSettled)));
+        assert!(!everything.matches(&event(community_id, EventType::TimerReset)));
+        assert!(!everything.matches(&event(community_id, EventType::ContentCreated)));
+
+        let one_community = EventFilter {
+            community_id: Some(community_id),
+            types: Some(vec![EventType::TimerReset]),
+            big_deposit: Decimal::ONE_HUNDRED,
+        };
+        assert!(one_community.matches(&event(community_id, EventType::TimerReset)));
+        assert!(!one_community.matches(&event(Uuid::new_v4(), EventType::TimerReset)));
+    }
+
+    #[test]
+    fn test_only_big_deposits_are_global() {
+        let deposit = |amount: &str| {
+            let mut pot = event(Uuid::new_v4(), EventType::PotUpdated);
+            pot.payload = serde_json::json!({ "bountyAmount": "500", "deposit": { "amount": amount } });
+            pot
+        };
+        let everything = EventFilter { community_id: None, types: None, big_deposit: Decimal::ONE_HUNDRED };
+        assert!(everything.matches(&deposit("100")));
+        assert!(everything.matches(&deposit("250.5")));
+        assert!(!everything.matches(&deposit("99.99999999")));
+        assert!(!everything.matches(&event(Uuid::new_v4(), EventType::PotUpdated)));
+
+        // Asking for deposits by name gets all of them
+        let deposits = EventFilter { community_id: None, types: Some(vec![EventType::PotUpdated]), big_deposit: Decimal::ONE_HUNDRED };
+        assert!(deposits.matches(&deposit("1")));
+    }
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::content::Content;
+use crate::model::pagination::{Cursor, Page};
+
+/// The end of a round in a followed community
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct RoundResult {
+    /// The status transition that ended the round
+    pub id: Uuid,
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(rename = "communityName")]
+    pub community_name: String,
+    #[serde(rename = "endedAt")]
+    pub ended_at: DateTime<Utc>,
+    /// The last message before the round ended, `None` if nobody posted
+    #[serde(rename = "winningContentId")]
+    pub winning_content_id: Option<Uuid>,
+    #[serde(rename = "winnerId")]
+    pub winner_id: Option<Uuid>,
+    #[serde(rename = "winnerXid")]
+    pub winner_xid: Option<String>,
+}
+
+/// One entry in a user's home feed
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedItem {
+    Message(Content),
+    RoundResult(RoundResult),
+}
+
+impl FeedItem {
+    /// Position of the item in the feed, newest first
+    pub fn cursor(&self) -> Cursor {
+        match self {
+            FeedItem::Message(content) => Cursor::new(content.created_at, content.id),
+            FeedItem::RoundResult(result) => Cursor::new(result.ended_at, result.id),
+        }
+    }
+
+    /// Merge messages and round results, each fetched newest first with
+    /// `LIMIT limit + 1` from the same cursor, into one page
+    pub fn merge_page(messages: Vec<Content>, results: Vec<RoundResult>, limit: i64) -> Page<FeedItem> {
+        let mut items: Vec<FeedItem> = messages
+            .into_iter()
+            .map(FeedItem::Message)
+            .chain(results.into_iter().map(FeedItem::RoundResult))
+            .collect();
+
+        items.sort_by_key(|item| {
+            let cursor = item.cursor();
+            std::cmp::Reverse((cursor.created_at, cursor.id))
+        });
+
+        Page::from_rows(items, limit, FeedItem::cursor)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::types::Json;
+
+    fn message(created_at: DateTime<Utc>) -> Content {
+        Content {
+            id: Uuid::new_v4(),
+            content: "hello".to_string(),
+            created_at,
+            sender_id: Uuid::new_v4(),
+            sender_xid: "xid".to_string(),
+            image_asset_id: None,
+            community_id: Uuid::new_v4(),
+            wallet_address: "0xabc".to_string(),
+            reply_to_id: None,
+            reply_count: 0,
+            edited_at: None,
+            deleted_at: None,
+            reactions: Json(Default::default()),
+            previews: Json(Vec::new()),
+        }
+    }
+
+    fn round_result(ended_at: DateTime<Utc>) -> RoundResult {
+        RoundResult {
+            id: Uuid::new_v4(),
+            community_id: Uuid::new_v4(),
+            community_name: "community".to_string(),
+            ended_at,
+            winning_content_id: None,
+            winner_id: None,
+            winner_xid: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_page_interleaves_newest_first() {
+        let now = Utc::now();
+        let messages = vec![message(now), message(now - Duration::minutes(2))];
+        let results = vec![round_result(now - Duration::minutes(1))];
+
+        let page = FeedItem::merge_page(messages, results, 10);
+        let kinds: Vec<&str> = page
+            .items
+            .iter()
+            .map(|item| match item {
+                FeedItem::Message(_) => "message",
+                FeedItem::RoundResult(_) => "round_result",
+            })
+            .collect();
+        assert_eq!(kinds, ["message", "round_result", "message"]);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_merge_page_cursor_points_at_last_item() {
+        let now = Utc::now();
+        let messages = vec![message(now), message(now - Duration::minutes(2))];
+        let results = vec![round_result(now - Duration::minutes(1)), round_result(now - Duration::minutes(3))];
+
+        let page = FeedItem::merge_page(messages, results, 2);
+        assert_eq!(page.items.len(), 2);
+        let last = page.items.last().unwrap().cursor();
+        assert_eq!(page.next_cursor, Some(last.encode()));
+        assert_eq!(last.created_at, now - Duration::minutes(1));
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Most users, and separately most communities, one user can follow
+pub const MAX_FOLLOWS: i64 = 500;
+
+/// Everyone and everything a user follows, most recently followed first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Following {
+    pub users: Vec<Uuid>,
+    pub communities: Vec<Uuid>,
+}
@@ -0,0 +1,72 @@
+use chrono::Duration;
+use sqlx::FromRow;
+
+/// Longest idempotency key accepted, in bytes
+pub const MAX_IDEMPOTENCY_KEY_BYTES: usize = 255;
+
+/// How long a key is remembered
+pub const IDEMPOTENCY_KEY_TTL: Duration = Duration::hours(24);
+
+/// How long a request can hold its key without finishing before a retry may
+/// run it again, for when the instance handling it died
+pub const IN_PROGRESS_TIMEOUT: Duration = Duration::minutes(1);
+
+/// Where the request that first used a key has got to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum IdempotencyStatus {
+    /// Still being handled
+    InProgress,
+    /// Handled, with its response stored
+    Completed,
+}
+
+impl IdempotencyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdempotencyStatus::InProgress => "in_progress",
+            IdempotencyStatus::Completed => "completed",
+        }
+    }
+}
+
+/// A key and what became of the request that first used it
+#[derive(Debug, Clone, FromRow)]
+pub struct IdempotencyRecord {
+    pub fingerprint: String,
+    pub status: IdempotencyStatus,
+    pub response_status: Option<i16>,
+    pub response_content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+}
+
+/// A response as stored for replay
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Whether a client-supplied key is one we accept: non-empty, not too long,
+/// and printable ASCII so it can go back out in a header or a log line
+pub fn is_valid_idempotency_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= MAX_IDEMPOTENCY_KEY_BYTES
+        && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_idempotency_key() {
+        assert!(is_valid_idempotency_key("3f1c9a2e-7b4d-4c55-9f1e-2d8a6b0c4e11"));
+        assert!(is_valid_idempotency_key("retry:post/42"));
+        assert!(!is_valid_idempotency_key(""));
+        assert!(!is_valid_idempotency_key("has space"));
+        assert!(!is_valid_idempotency_key("ключ"));
+        assert!(!is_valid_idempotency_key(&"k".repeat(MAX_IDEMPOTENCY_KEY_BYTES + 1)));
+    }
+}
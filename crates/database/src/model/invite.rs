@@ -0,0 +1,99 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Most uses a single invite can be given
+pub const MAX_INVITE_USES: i32 = 10_000;
+
+/// A code that lets its holder into a community, crediting whoever made it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CommunityInvite {
+    pub code: String,
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(rename = "createdBy")]
+    pub created_by: Uuid,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "revokedAt")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// `None` for no limit
+    #[serde(rename = "maxUses")]
+    pub max_uses: Option<i32>,
+    #[serde(rename = "useCount")]
+    pub use_count: i32,
+    /// `None` for an invite that works until revoked
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CommunityInvite {
+    /// Whether the invite still lets people in at `now`
+    pub fn is_usable_at(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none()
+            && self.max_uses.is_none_or(|max_uses| self.use_count < max_uses)
+            && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CreateInviteDto {
+    /// Between 1 and `MAX_INVITE_USES`; leave out for no limit
+    #[serde(rename = "maxUses")]
+    pub max_uses: Option<i32>,
+    /// Leave out for an invite that works until revoked
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A new invite code: 12 URL-safe characters from 72 random bits
+pub fn generate_invite_code() -> String {
+    let random = Uuid::new_v4();
+    let bytes = random.as_bytes();
+    // Skip the version and variant bits so every character is random
+    URL_SAFE_NO_PAD.encode([&bytes[..6], &bytes[10..13]].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_generate_invite_code() {
+        let code = generate_invite_code();
+        assert_eq!(code.len(), 12);
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_ne!(code, generate_invite_code());
+    }
+
+    #[test]
+    fn test_invite_is_usable() {
+        let now = Utc::now();
+        let mut invite = CommunityInvite {
+            code: generate_invite_code(),
+            community_id: Uuid::new_v4(),
+            created_by: Uuid::new_v4(),
+            created_at: now,
+            revoked_at: None,
+            max_uses: None,
+            use_count: 3,
+            expires_at: None,
+        };
+        assert!(invite.is_usable_at(now));
+
+        invite.max_uses = Some(3);
+        assert!(!invite.is_usable_at(now));
+        invite.max_uses = Some(4);
+        assert!(invite.is_usable_at(now));
+
+        invite.expires_at = Some(now + Duration::hours(1));
+        assert!(invite.is_usable_at(now));
+        assert!(!invite.is_usable_at(now + Duration::hours(1)));
+
+        invite.revoked_at = Some(now);
+        assert!(!invite.is_usable_at(now));
+    }
+}
@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use chrono::Duration;
+
+/// Most links unfurled per message
+pub const MAX_LINKS_PER_MESSAGE: usize = 3;
+
+/// Longest URL stored, in bytes
+pub const MAX_URL_BYTES: usize = 2048;
+
+/// How long a fetched preview is used before it is fetched again
+pub const PREVIEW_TTL: Duration = Duration::hours(24);
+
+/// The card shown under a message for a link in it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkPreview {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "imageUrl")]
+    pub image_url: Option<String>,
+    #[serde(rename = "siteName")]
+    pub site_name: Option<String>,
+}
+
+/// What a page says about itself, as found in its HTML
+#[derive(Debug, Default, PartialEq)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// As written in the page, possibly relative
+    pub image: Option<String>,
+    pub site_name: Option<String>,
+}
+
+/// Find the http(s) links in a message, in order, without duplicates.
+///
+/// Trailing punctuation is left off, so a link at the end of a sentence or
+/// inside parentheses comes out clean. Nothing is checked beyond the scheme.
+pub fn extract_urls(text: &str) -> Vec<&str> {
+    let mut urls: Vec<&str> = Vec::new();
+
+    for word in text.split_whitespace() {
+        let Some(start) = ["https://", "http://"]
+            .iter()
+            .filter_map(|scheme| word.to_ascii_lowercase().find(scheme))
+            .min()
+        else {
+            continue;
+        };
+
+        let mut url = &word[start..];
+        loop {
+            let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '"', '>', ']', '}']);
+            // Keep closing parentheses that belong to the URL itself
+            let unbalanced = trimmed.matches(')').count() > trimmed.matches('(').count();
+            let trimmed = match trimmed.strip_suffix(')') {
+                Some(rest) if unbalanced => rest,
+                _ => trimmed,
+            };
+            if trimmed == url {
+                break;
+            }
+            url = trimmed;
+        }
+
+        let has_host = url.split_once("://").is_some_and(|(_, rest)| !rest.is_empty());
+        if has_host && url.len() <= MAX_URL_BYTES && !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+
+    urls
+}
+
+/// Pull OpenGraph data out of a page, falling back to Twitter cards, the
+/// description meta tag and `<title>`.
+///
+/// This is a scan for tags rather than a full HTML parser; pages put these
+/// in their `<head>` in a handful of well-worn shapes.
+pub fn parse_page_metadata(html: &str) -> PageMetadata {
+    let mut og = PageMetadata::default();
+    let mut twitter = PageMetadata::default();
+    let mut description = None;
+    let mut title = None;
+
+    let lower = html.to_ascii_lowercase();
+    let mut position = 0;
+    while let Some(offset) = lower[position..].find('<') {
+        let start = position + offset;
+        let rest = &lower[start..];
+
+        if rest.starts_with("<meta") {
+            let end = rest.find('>').map_or(html.len(), |end| start + end);
+            let attributes = parse_attributes(&html[start + "<meta".len()..end]);
+            let key = attribute(&attributes, "property").or_else(|| attribute(&attributes, "name"));
+            if let (Some(key), Some(content)) = (key, attribute(&attributes, "content")) {
+                let content = Some(content.to_string());
+                match key.to_ascii_lowercase().as_str() {
+                    "og:title" => set_once(&mut og.title, content),
+                    "og:description" => set_once(&mut og.description, content),
+                    "og:image" | "og:image:url" | "og:image:secure_url" => set_once(&mut og.image, content),
+                    "og:site_name" => set_once(&mut og.site_name, content),
+                    "twitter:title" => set_once(&mut twitter.title, content),
+                    "twitter:description" => set_once(&mut twitter.description, content),
+                    "twitter:image" | "twitter:image:src" => set_once(&mut twitter.image, content),
+                    "description" => set_once(&mut description, content),
+                    _ => {},
+                }
+            }
+            position = end;
+        } else if rest.starts_with("<title") && title.is_none() {
+            let Some(open_end) = rest.find('>') else { break };
+            let text_start = start + open_end + 1;
+            let text_end = lower[text_start..].find("</title").map_or(html.len(), |end| text_start + end);
+            title = Some(html[text_start..text_end].to_string());
+            position = text_end;
+        } else {
+            position = start + 1;
+        }
+    }
+
+    let clean = |value: Option<String>| {
+        value
+            .map(|value| decode_entities(value.split_whitespace().collect::<Vec<_>>().join(" ").as_str()))
+            .filter(|value| !value.is_empty())
+    };
+
+    PageMetadata {
+        title: clean(og.title.or(twitter.title).or(title)),
+        description: clean(og.description.or(twitter.description).or(description)),
+        image: clean(og.image.or(twitter.image)),
+        site_name: clean(og.site_name),
+    }
+}
+
+fn set_once(slot: &mut Option<String>, value: Option<String>) {
+    if slot.is_none() {
+        *slot = value;
+    }
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Split `name="value" name='value' name=value` into lowercased names and raw values
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut chars = tag.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() || c == '/' {
+            chars.next();
+            continue;
+        }
+
+        // Name
+        let mut name_end = start;
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() || c == '=' || c == '/' {
+                break;
+            }
+            name_end = i + c.len_utf8();
+            chars.next();
+        }
+        let name = tag[start..name_end].to_ascii_lowercase();
+
+        while chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none_or(|&(_, c)| c != '=') {
+            attributes.push((name, String::new()));
+            continue;
+        }
+        chars.next();
+        while chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+
+        // Value
+        let value = match chars.peek() {
+            Some(&(i, quote)) if quote == '"' || quote == '\'' => {
+                chars.next();
+                let value_start = i + 1;
+                let mut value_end = tag.len();
+                for (j, c) in chars.by_ref() {
+                    if c == quote {
+                        value_end = j;
+                        break;
+                    }
+                }
+                &tag[value_start..value_end.max(value_start)]
+            },
+            Some(&(i, _)) => {
+                let mut value_end = tag.len();
+                while let Some(&(j, c)) = chars.peek() {
+                    if c.is_whitespace() {
+                        value_end = j;
+                        break;
+                    }
+                    chars.next();
+                }
+                &tag[i..value_end]
+            },
+            None => "",
+        };
+        attributes.push((name, value.to_string()));
+    }
+
+    attributes
+}
+
+/// Decode the character references that show up in page titles
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest[1..].find(';').filter(|&end| end <= 10).map(|end| &rest[1..=end]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let number = entity.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            },
+        });
+
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            },
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_urls() {
+        assert_eq!(
+            extract_urls("see https://example.com/a?b=1, and (http://x.io/wiki/Foo_(bar)). dup https://example.com/a?b=1"),
+            vec!["https://example.com/a?b=1", "http://x.io/wiki/Foo_(bar)"]
+        );
+        assert_eq!(extract_urls("<https://example.com>"), vec!["https://example.com"]);
+        assert_eq!(extract_urls("ftp://example.com https:// example.com"), Vec::<&str>::new());
+        assert_eq!(extract_urls("링크:https://example.com/경로!"), vec!["https://example.com/경로"]);
+    }
+
+    #[test]
+    fn test_parse_open_graph() {
+        let html = r#"<html><head>
+            <title>Fallback title</title>
+            <meta property="og:title" content="Pulse &amp; friends" />
+            <META name='description' content='plain description'>
+            <meta content="https://cdn.example.com/card.png" property="og:image">
+            <meta property=og:site_name content=Pulse>
+            </head><body><meta property="og:title" content="ignored"></body></html>"#;
+
+        assert_eq!(
+            parse_page_metadata(html),
+            PageMetadata {
+                title: Some("Pulse & friends".to_string()),
+                description: Some("plain description".to_string()),
+                image: Some("https://cdn.example.com/card.png".to_string()),
+                site_name: Some("Pulse".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_falls_back_to_title() {
+        let metadata = parse_page_metadata("<head><title>\n  Just a &#8220;page&#x201D;  </title></head>");
+        assert_eq!(metadata.title.as_deref(), Some("Just a \u{201C}page\u{201D}"));
+        assert_eq!(metadata.image, None);
+
+        assert_eq!(parse_page_metadata("<meta property=\"og:title\""), PageMetadata::default());
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Where a user stands in a community
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum MemberStatus {
+    Active,
+    /// Asked to join a private community and waiting for its creator
+    Pending,
+}
+
+impl MemberStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemberStatus::Active => "active",
+            MemberStatus::Pending => "pending",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CommunityMember {
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(rename = "userId")]
+    pub user_id: Uuid,
+    pub status: MemberStatus,
+    /// When they joined or asked to
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    /// `None` while pending
+    #[serde(rename = "joinedAt")]
+    pub joined_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JoinCommunityDto {
+    /// Lets the caller straight into a private community
+    #[serde(rename = "inviteCode")]
+    pub invite_code: Option<String>,
+}
+
+/// Query parameters for listing a community's members
+#[derive(Debug, Default, Deserialize)]
+pub struct MemberListQuery {
+    /// `pending` lists requests to join, for the creator
+    pub status: Option<MemberStatus>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
@@ -1,3 +1,23 @@
 pub mod user;
+pub mod asset;
 pub mod community;
-pub mod content;
\ No newline at end of file
+pub mod content;
+pub mod depositor;
+pub mod event;
+pub mod feed;
+pub mod follow;
+pub mod idempotency;
+pub mod invite;
+pub mod link_preview;
+pub mod member;
+pub mod moderation;
+pub mod notification;
+pub mod payout;
+pub mod pagination;
+pub mod presence;
+pub mod rate_limit;
+pub mod reaction;
+pub mod referral;
+pub mod reward;
+pub mod sanction;
+pub mod search;
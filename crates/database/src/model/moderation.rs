@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::content::CreateContentDto;
+
+/// Longest message accepted, in characters
+pub const MAX_CONTENT_CHARS: usize = 2000;
+
+/// Longest run of one repeated character before a message counts as spam
+pub const MAX_REPEATED_CHARS: usize = 12;
+
+/// Most entries in each of a community's moderation lists
+pub const MAX_LIST_ENTRIES: usize = 500;
+
+/// A community's moderation rules. Communities without a row have none.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct CommunityModeration {
+    /// Messages containing any of these are refused
+    #[serde(rename = "blockedWords")]
+    pub blocked_words: Vec<String>,
+    /// Messages containing any of these wait for a moderator
+    #[serde(rename = "heldWords")]
+    pub held_words: Vec<String>,
+    /// If not empty, messages linking anywhere else wait for a moderator.
+    /// Subdomains of a listed domain are allowed too.
+    #[serde(rename = "allowedDomains")]
+    pub allowed_domains: Vec<String>,
+}
+
+/// Replaces whichever lists are given
+#[derive(Debug, Deserialize)]
+pub struct UpdateModerationDto {
+    #[serde(rename = "blockedWords")]
+    pub blocked_words: Option<Vec<String>>,
+    #[serde(rename = "heldWords")]
+    pub held_words: Option<Vec<String>>,
+    #[serde(rename = "allowedDomains")]
+    pub allowed_domains: Option<Vec<String>>,
+}
+
+/// Where a held message is in review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum HeldContentStatus {
+    /// Waiting for a moderator
+    Pending,
+    /// Posted to the community
+    Approved,
+    /// Turned down
+    Rejected,
+}
+
+impl HeldContentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeldContentStatus::Pending => "pending",
+            HeldContentStatus::Approved => "approved",
+            HeldContentStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// A message that a filter held back for a moderator to review
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct HeldContent {
+    pub id: Uuid,
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(rename = "senderId")]
+    pub sender_id: Uuid,
+    #[serde(rename = "senderXid")]
+    pub sender_xid: String,
+    pub content: String,
+    #[serde(rename = "imageAssetId")]
+    pub image_asset_id: Option<Uuid>,
+    #[serde(rename = "replyToId")]
+    pub reply_to_id: Option<Uuid>,
+    #[serde(rename = "walletAddress")]
+    pub wallet_address: String,
+    /// Why the message was held
+    pub reason: String,
+    pub status: HeldContentStatus,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "reviewedBy")]
+    pub reviewed_by: Option<Uuid>,
+    #[serde(rename = "reviewedAt")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// The message as posted, once approved
+    #[serde(rename = "contentId")]
+    pub content_id: Option<Uuid>,
+}
+
+impl HeldContent {
+    /// The message as it would have been posted
+    pub fn to_create_dto(&self) -> CreateContentDto {
+        CreateContentDto {
+            content: self.content.clone(),
+            sender_id: Some(self.sender_id),
+            sender_xid: Some(self.sender_xid.clone()),
+            image_asset_id: self.image_asset_id,
+            community_id: self.community_id,
+            wallet_address: Some(self.wallet_address.clone()),
+            reply_to_id: self.reply_to_id,
+        }
+    }
+}
+
+/// Query parameters for listing held messages
+#[derive(Debug, Default, Deserialize)]
+pub struct ModerationQueueQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+/// Trim, lowercase and dedupe a moderation list, dropping empty entries
+pub fn normalize_list(entries: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for entry in entries {
+        let entry = entry.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        let entry = entry.trim_start_matches("*.").trim_end_matches('.').to_string();
+        if !entry.is_empty() && !normalized.contains(&entry) {
+            normalized.push(entry);
+        }
+    }
+    normalized
+}
+
+/// The first listed word or phrase that appears in `text`.
+///
+/// Single words have to match a whole word, so "ass" does not catch
+/// "class"; phrases match anywhere once case and spacing are ignored.
+pub fn find_listed_word<'a>(text: &str, list: &'a [String]) -> Option<&'a str> {
+    let lowered = text.to_lowercase();
+    let words: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let spaced = words.join(" ");
+
+    list.iter()
+        .map(String::as_str)
+        .find(|entry| {
+            if entry.contains(char::is_whitespace) {
+                spaced.contains(entry)
+            } else {
+                words.contains(entry)
+            }
+        })
+}
+
+/// Whether `host` is one of the allowed domains or a subdomain of one
+pub fn domain_allowed(host: &str, allowed: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    allowed.iter().any(|domain| {
+        host == *domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+/// Length of the longest run of one character repeated, ignoring whitespace
+pub fn longest_repeated_run(text: &str) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous = None;
+
+    for c in text.chars() {
+        if c.is_whitespace() {
+            run = 0;
+            previous = None;
+            continue;
+        }
+        run = if previous == Some(c) { run + 1 } else { 1 };
+        previous = Some(c);
+        longest = longest.max(run);
+    }
+
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(entries: &[&str]) -> Vec<String> {
+        normalize_list(&entries.iter().map(|entry| entry.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_find_listed_word() {
+        let words = list(&["Scam", "free  money"]);
+        assert_eq!(find_listed_word("This is a SCAM!", &words), Some("scam"));
+        assert_eq!(find_listed_word("scammer", &words), None);
+        assert_eq!(find_listed_word("get FREE\nmoney now", &words), Some("free money"));
+        assert_eq!(find_listed_word("money for free", &words), None);
+    }
+
+    #[test]
+    fn test_domain_allowed() {
+        let allowed = list(&["Example.com", "*.docs.rs"]);
+        assert!(domain_allowed("example.com", &allowed));
+        assert!(domain_allowed("blog.EXAMPLE.com.", &allowed));
+        assert!(domain_allowed("serde.docs.rs", &allowed));
+        assert!(!domain_allowed("badexample.com", &allowed));
+        assert!(!domain_allowed("example.com.evil.io", &allowed));
+    }
+
+    #[test]
+    fn test_longest_repeated_run() {
+        assert_eq!(longest_repeated_run("hello"), 2);
+        assert_eq!(longest_repeated_run("gm   gm"), 1);
+        assert_eq!(longest_repeated_run("🚀🚀🚀 ok"), 3);
+        assert_eq!(longest_repeated_run(""), 0);
+    }
+}
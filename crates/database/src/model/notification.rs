@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::community::Community;
+use crate::model::content::Content;
+use crate::model::pagination::Page;
+
+/// Longest message excerpt kept in a notification, in characters
+const EXCERPT_CHARS: usize = 140;
+
+/// Longest username that can be mentioned
+const MAX_MENTION_CHARS: usize = 32;
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Someone mentioned the user in a message
+    Mention,
+    /// Someone posted after the user, so their message is no longer last
+    Outbid,
+    /// The user's message was the last one when the round ended
+    RoundWon,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Mention => "mention",
+            NotificationKind::Outbid => "outbid",
+            NotificationKind::RoundWon => "round_won",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    #[serde(rename = "userId")]
+    pub user_id: Uuid,
+    pub kind: NotificationKind,
+    #[serde(rename = "communityId")]
+    pub community_id: Option<Uuid>,
+    #[serde(rename = "contentId")]
+    pub content_id: Option<Uuid>,
+    #[serde(rename = "actorId")]
+    pub actor_id: Option<Uuid>,
+    #[serde(rename = "data")]
+    pub payload: serde_json::Value,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "readAt")]
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// A notification that has not been stored yet
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub user_id: Uuid,
+    pub kind: NotificationKind,
+    pub community_id: Option<Uuid>,
+    pub content_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub payload: serde_json::Value,
+}
+
+impl NewNotification {
+    pub fn mention(user_id: Uuid, content: &Content) -> Self {
+        Self {
+            user_id,
+            kind: NotificationKind::Mention,
+            community_id: Some(content.community_id),
+            content_id: Some(content.id),
+            actor_id: Some(content.sender_id),
+            payload: serde_json::json!({
+                "senderXid": content.sender_xid,
+                "excerpt": excerpt(&content.content),
+            }),
+        }
+    }
+
+    /// `previous` is the user's message that `content` came after
+    pub fn outbid(previous: &Content, content: &Content) -> Self {
+        Self {
+            user_id: previous.sender_id,
+            kind: NotificationKind::Outbid,
+            community_id: Some(content.community_id),
+            content_id: Some(content.id),
+            actor_id: Some(content.sender_id),
+            payload: serde_json::json!({
+                "senderXid": content.sender_xid,
+                "previousContentId": previous.id,
+            }),
+        }
+    }
+
+    pub fn round_won(community: &Community, winning: &Content) -> Self {
+        Self {
+            user_id: winning.sender_id,
+            kind: NotificationKind::RoundWon,
+            community_id: Some(community.id),
+            content_id: Some(winning.id),
+            actor_id: None,
+            payload: serde_json::json!({
+                "communityName": community.name,
+                "bountyAmount": community.bounty_amount,
+            }),
+        }
+    }
+}
+
+/// Query parameters for listing notifications
+#[derive(Debug, Default, Deserialize)]
+pub struct NotificationListQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    /// Only unread notifications
+    #[serde(default)]
+    pub unread: bool,
+}
+
+/// A page of the inbox plus how much of it is unread
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationInbox {
+    #[serde(flatten)]
+    pub page: Page<Notification>,
+    #[serde(rename = "unreadCount")]
+    pub unread_count: i64,
+}
+
+/// Usernames mentioned as `@name` in a message, lowercased and without duplicates.
+///
+/// An `@` only starts a mention at the beginning of the text or after a
+/// character that cannot be part of a name, so email addresses don't count.
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '.' || c == '-';
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+
+    for (i, c) in text.char_indices() {
+        if c == '@' && !previous.is_some_and(is_name_char) {
+            let rest = &text[i + 1..];
+            let end = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+            // Trailing punctuation ends a sentence rather than a name
+            let name = rest[..end].trim_end_matches(['.', '-']);
+
+            if !name.is_empty() && name.chars().count() <= MAX_MENTION_CHARS {
+                let name = name.to_lowercase();
+                if !mentions.contains(&name) {
+                    mentions.push(name);
+                }
+            }
+        }
+        previous = Some(c);
+    }
+
+    mentions
+}
+
+fn excerpt(text: &str) -> String {
+    match text.char_indices().nth(EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mentions() {
+        assert_eq!(parse_mentions("gm @alice and @Bob_1."), vec!["alice", "bob_1"]);
+        assert_eq!(parse_mentions("@alice @ALICE"), vec!["alice"]);
+        assert_eq!(parse_mentions("mail me at bob@example.com"), Vec::<String>::new());
+        assert_eq!(parse_mentions("(@서울)"), vec!["서울"]);
+        assert_eq!(parse_mentions("@ alone"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_excerpt_is_bounded() {
+        assert_eq!(excerpt("short"), "short");
+        assert_eq!(excerpt(&"가".repeat(200)).chars().count(), EXCERPT_CHARS + 1);
+    }
+}
@@ -0,0 +1,114 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Page size used when the client does not ask for one
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+
+/// Largest page a client can ask for
+pub const MAX_PAGE_LIMIT: i64 = 100;
+
+/// A page of results plus the cursor to fetch the next one
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Build a page from rows fetched with `LIMIT limit + 1`.
+    ///
+    /// The extra row only tells us whether there is another page; it is
+    /// dropped and the cursor points at the last row that is returned.
+    pub fn from_rows(mut rows: Vec<T>, limit: i64, cursor_of: impl Fn(&T) -> Cursor) -> Self {
+        let has_more = rows.len() as i64 > limit;
+        rows.truncate(limit as usize);
+
+        let next_cursor = if has_more {
+            rows.last().map(|row| cursor_of(row).encode())
+        } else {
+            None
+        };
+
+        Self { items: rows, next_cursor }
+    }
+}
+
+/// Position in a list ordered by `(created_at, id)`, newest first unless
+/// the list says otherwise.
+///
+/// Clients only ever see the encoded form, so the ordering key can change
+/// without breaking them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    pub fn encode(&self) -> String {
+        let raw = format!("{}|{}", self.created_at.timestamp_micros(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    /// Decode a cursor previously returned by `encode`
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let raw = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        let raw = String::from_utf8(raw).ok()?;
+        let (micros, id) = raw.split_once('|')?;
+
+        let created_at = DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+        let id = Uuid::parse_str(id).ok()?;
+
+        Some(Self { created_at, id })
+    }
+}
+
+/// Clamp a requested page size to `1..=MAX_PAGE_LIMIT`
+pub fn page_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor::new(
+            DateTime::from_timestamp_micros(1_714_780_800_123_456).unwrap(),
+            Uuid::new_v4(),
+        );
+
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(Cursor::decode("not-a-cursor"), None);
+    }
+
+    #[test]
+    fn test_page_from_rows() {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let cursor_of = |_: &i32| Cursor::new(now, id);
+
+        let page = Page::from_rows(vec![1, 2, 3], 2, cursor_of);
+        assert_eq!(page.items, vec![1, 2]);
+        assert!(page.next_cursor.is_some());
+
+        let page = Page::from_rows(vec![1, 2], 2, cursor_of);
+        assert_eq!(page.items, vec![1, 2]);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_page_limit_is_capped() {
+        assert_eq!(page_limit(None), DEFAULT_PAGE_LIMIT);
+        assert_eq!(page_limit(Some(0)), 1);
+        assert_eq!(page_limit(Some(10_000)), MAX_PAGE_LIMIT);
+    }
+}
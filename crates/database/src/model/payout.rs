@@ -0,0 +1,112 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::community::Community;
+use crate::model::content::Content;
+
+/// What a payout is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutKind {
+    ReferralReward,
+    RoundWinnings,
+}
+
+impl PayoutKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutKind::ReferralReward => "referral_reward",
+            PayoutKind::RoundWinnings => "round_winnings",
+        }
+    }
+}
+
+/// Where a payout is on its way to the payment service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutStatus {
+    /// Waiting to be sent, possibly after a failed attempt
+    Pending,
+    /// Being sent right now
+    Processing,
+    /// Accepted by the payment service
+    Sent,
+    /// Given up on; needs someone to look at it
+    Failed,
+}
+
+impl PayoutStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutStatus::Pending => "pending",
+            PayoutStatus::Processing => "processing",
+            PayoutStatus::Sent => "sent",
+            PayoutStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Money paid out to a user in one go
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Payout {
+    pub id: Uuid,
+    #[serde(rename = "userId")]
+    pub user_id: Uuid,
+    pub kind: PayoutKind,
+    pub amount: Decimal,
+    /// The user's wallet when the payout was made
+    #[serde(rename = "walletAddress")]
+    pub wallet_address: Option<String>,
+    /// The round won, for round winnings
+    #[serde(rename = "communityId")]
+    pub community_id: Option<Uuid>,
+    /// The winning message, for round winnings
+    #[serde(rename = "contentId")]
+    pub content_id: Option<Uuid>,
+    pub status: PayoutStatus,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "sentAt")]
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+/// A payout that has not been stored yet
+#[derive(Debug, Clone)]
+pub struct NewPayout {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: PayoutKind,
+    pub amount: Decimal,
+    pub community_id: Option<Uuid>,
+    pub content_id: Option<Uuid>,
+}
+
+impl NewPayout {
+    pub fn referral_reward(user_id: Uuid, amount: Decimal) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            kind: PayoutKind::ReferralReward,
+            amount,
+            community_id: None,
+            content_id: None,
+        }
+    }
+
+    /// The bounty for whoever sent the message that won the round
+    pub fn round_winnings(community: &Community, winning: &Content) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id: winning.sender_id,
+            kind: PayoutKind::RoundWinnings,
+            amount: community.bounty_amount,
+            community_id: Some(community.id),
+            content_id: Some(winning.id),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// How many people are watching a community right now
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceCounts {
+    /// Open connections, including anonymous ones
+    pub viewers: i64,
+    /// Distinct signed-in users with at least one open connection
+    #[serde(rename = "onlineUsers")]
+    pub online_users: i64,
+}
+
+/// A signed-in user currently watching a community
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct OnlineUser {
+    #[serde(rename = "userId")]
+    pub user_id: Uuid,
+    #[serde(rename = "userXid")]
+    pub user_xid: String,
+    pub username: String,
+    /// When the user's oldest open connection was made
+    #[serde(rename = "onlineSince")]
+    pub online_since: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Presence {
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(flatten)]
+    pub counts: PresenceCounts,
+    /// Only listed for signed-in callers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub users: Option<Vec<OnlineUser>>,
+}
+
+/// Whether a connection joined or left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceChange {
+    Joined,
+    Left,
+}
+
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Highest rate a community can set, in posts per minute
+pub const MAX_POSTS_PER_MINUTE: i32 = 10_000;
+
+/// Posts from one address, across all communities
+pub const DEFAULT_IP_LIMIT: RateLimit = RateLimit { burst: 30, per_minute: 60 };
+
+/// Posts from one user, across all communities
+pub const DEFAULT_USER_LIMIT: RateLimit = RateLimit { burst: 10, per_minute: 20 };
+
+/// Posts to one community, from everyone
+pub const DEFAULT_COMMUNITY_LIMIT: RateLimit = RateLimit { burst: 120, per_minute: 300 };
+
+/// A token bucket's shape: it holds up to `burst` tokens and refills at
+/// `per_minute` tokens a minute. Each post takes one token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub per_minute: u32,
+}
+
+impl RateLimit {
+    fn tokens_per_ms(&self) -> f64 {
+        f64::from(self.per_minute) / 60_000.0
+    }
+}
+
+/// How full a token bucket was when it was last touched
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TokenBucket {
+    pub fn full(limit: RateLimit, now: DateTime<Utc>) -> Self {
+        Self { tokens: f64::from(limit.burst), updated_at: now }
+    }
+
+    /// The bucket as of `now`, topped up for the time since it was last touched
+    pub fn refilled(&self, limit: RateLimit, now: DateTime<Utc>) -> Self {
+        let elapsed_ms = (now - self.updated_at).num_milliseconds().max(0) as f64;
+        let tokens = (self.tokens + elapsed_ms * limit.tokens_per_ms()).min(f64::from(limit.burst));
+        Self { tokens, updated_at: now.max(self.updated_at) }
+    }
+
+    /// How long until a token is free, or `None` if one is now
+    pub fn wait(&self, limit: RateLimit) -> Option<Duration> {
+        if self.tokens >= 1.0 {
+            return None;
+        }
+        let ms = ((1.0 - self.tokens) / limit.tokens_per_ms()).ceil() as i64;
+        Some(Duration::milliseconds(ms))
+    }
+
+    /// Whether the bucket has refilled completely, so forgetting it changes nothing
+    pub fn is_full(&self, limit: RateLimit) -> bool {
+        self.tokens >= f64::from(limit.burst)
+    }
+}
+
+/// A community's posting limits. Unset fields use the server defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct CommunityRateLimits {
+    /// Posts a minute from everyone together
+    #[serde(rename = "postsPerMinute")]
+    pub posts_per_minute: Option<i32>,
+    /// Posts allowed in a quick burst from everyone together
+    #[serde(rename = "postBurst")]
+    pub post_burst: Option<i32>,
+    /// Posts a minute from each member, i.e. slow mode
+    #[serde(rename = "memberPostsPerMinute")]
+    pub member_posts_per_minute: Option<i32>,
+    /// Posts allowed in a quick burst from each member
+    #[serde(rename = "memberPostBurst")]
+    pub member_post_burst: Option<i32>,
+}
+
+impl CommunityRateLimits {
+    /// Whether every field that is set is a usable rate
+    pub fn is_valid(&self) -> bool {
+        [self.posts_per_minute, self.post_burst, self.member_posts_per_minute, self.member_post_burst]
+            .into_iter()
+            .flatten()
+            .all(|value| (1..=MAX_POSTS_PER_MINUTE).contains(&value))
+    }
+
+    /// The limit on the whole community
+    pub fn community_limit(&self) -> RateLimit {
+        let per_minute = self.posts_per_minute.map_or(DEFAULT_COMMUNITY_LIMIT.per_minute, |rate| rate as u32);
+        // A custom rate without a burst allows a minute's worth at once
+        let burst = match (self.post_burst, self.posts_per_minute) {
+            (Some(burst), _) => burst as u32,
+            (None, Some(rate)) => rate as u32,
+            (None, None) => DEFAULT_COMMUNITY_LIMIT.burst,
+        };
+        RateLimit { burst, per_minute }
+    }
+
+    /// The limit on each member, if the community has one
+    pub fn member_limit(&self) -> Option<RateLimit> {
+        let per_minute = self.member_posts_per_minute? as u32;
+        let burst = self.member_post_burst.map_or(per_minute, |burst| burst as u32);
+        Some(RateLimit { burst, per_minute })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_refill() {
+        let limit = RateLimit { burst: 2, per_minute: 6 };
+        let start = Utc::now();
+
+        let bucket = TokenBucket { tokens: 0.0, updated_at: start };
+        assert_eq!(bucket.wait(limit), Some(Duration::seconds(10)));
+
+        let later = bucket.refilled(limit, start + Duration::seconds(5));
+        assert_eq!(later.tokens, 0.5);
+        assert_eq!(later.wait(limit), Some(Duration::seconds(5)));
+
+        let much_later = bucket.refilled(limit, start + Duration::hours(1));
+        assert!(much_later.is_full(limit));
+        assert_eq!(much_later.tokens, 2.0);
+        assert_eq!(much_later.wait(limit), None);
+    }
+
+    #[test]
+    fn test_community_rate_limits() {
+        let defaults = CommunityRateLimits::default();
+        assert_eq!(defaults.community_limit(), DEFAULT_COMMUNITY_LIMIT);
+        assert_eq!(defaults.member_limit(), None);
+
+        let slow = CommunityRateLimits {
+            posts_per_minute: Some(30),
+            member_posts_per_minute: Some(2),
+            member_post_burst: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(slow.community_limit(), RateLimit { burst: 30, per_minute: 30 });
+        assert_eq!(slow.member_limit(), Some(RateLimit { burst: 1, per_minute: 2 }));
+        assert!(slow.is_valid());
+        assert!(!CommunityRateLimits { post_burst: Some(0), ..Default::default() }.is_valid());
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Longest reaction accepted, in bytes. Enough for emoji built from several
+/// code points, like flags and skin tones.
+pub const MAX_REACTION_BYTES: usize = 32;
+
+/// Number of reactions of each kind on a message
+pub type ReactionCounts = BTreeMap<String, i64>;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateReactionDto {
+    pub reaction: String,
+}
+
+/// A message's reactions after a change
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReactionSummary {
+    #[serde(rename = "contentId")]
+    pub content_id: Uuid,
+    pub reactions: ReactionCounts,
+}
+
+/// Trim a reaction and check it is something we can store and display,
+/// returning `None` if it is not
+pub fn normalize_reaction(reaction: &str) -> Option<&str> {
+    let reaction = reaction.trim();
+
+    let valid = !reaction.is_empty()
+        && reaction.len() <= MAX_REACTION_BYTES
+        && !reaction.chars().any(|c| c.is_whitespace() || c.is_control());
+
+    valid.then_some(reaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_reaction() {
+        assert_eq!(normalize_reaction(" 🔥 "), Some("🔥"));
+        assert_eq!(normalize_reaction("👍🏽"), Some("👍🏽"));
+        assert_eq!(normalize_reaction(""), None);
+        assert_eq!(normalize_reaction("two words"), None);
+        assert_eq!(normalize_reaction(&"🔥".repeat(9)), None);
+    }
+}
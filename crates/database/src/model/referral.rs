@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// How many people one user has brought into one community
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReferralStats {
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(rename = "referrerId")]
+    pub referrer_id: Uuid,
+    /// Everyone who joined through the referrer's invites
+    pub joins: i64,
+    /// Those of them who are still members
+    #[serde(rename = "activeMembers")]
+    pub active_members: i64,
+}
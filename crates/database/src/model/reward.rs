@@ -0,0 +1,115 @@
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// How many of the latest rewards come back with a balance
+pub const RECENT_REWARDS: i64 = 20;
+
+/// Decimal places money is stored with, matching `DECIMAL(20, 8)`
+const MONEY_SCALE: u32 = 8;
+
+/// A referrer's share of the platform fee on one deposit
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReferralReward {
+    pub id: Uuid,
+    #[serde(rename = "userId")]
+    pub user_id: Uuid,
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    #[serde(rename = "depositId")]
+    pub deposit_id: Uuid,
+    #[serde(rename = "referredUserId")]
+    pub referred_user_id: Uuid,
+    #[serde(rename = "platformFee")]
+    pub platform_fee: Decimal,
+    pub amount: Decimal,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    /// Set once the reward has been claimed
+    #[serde(rename = "payoutId")]
+    pub payout_id: Option<Uuid>,
+}
+
+/// A reward about to be credited along with the deposit it comes from
+#[derive(Debug, Clone)]
+pub struct RewardShare {
+    pub referrer_id: Uuid,
+    pub platform_fee: Decimal,
+    pub amount: Decimal,
+}
+
+impl RewardShare {
+    /// The referrer's `percent` of the platform fee on a deposit, or `None`
+    /// if that comes to nothing
+    pub fn for_deposit(
+        referrer_id: Uuid,
+        amount: Decimal,
+        base_fee_percentage: Option<f32>,
+        percent: Decimal,
+    ) -> Option<Self> {
+        let platform_fee = percent_of(amount, Decimal::from_f32(base_fee_percentage?)?);
+        let reward = percent_of(platform_fee, percent);
+        if reward <= Decimal::ZERO {
+            return None;
+        }
+
+        Some(Self { referrer_id, platform_fee, amount: reward })
+    }
+}
+
+/// A user's referral rewards, earned and paid out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewardBalance {
+    pub accrued: Decimal,
+    pub claimed: Decimal,
+    /// What a claim would pay out now
+    pub balance: Decimal,
+    /// The share of platform fees referrers currently earn
+    #[serde(rename = "rewardPercent")]
+    pub reward_percent: Decimal,
+    #[serde(rename = "recentRewards")]
+    pub recent_rewards: Vec<ReferralReward>,
+}
+
+/// `percent` percent of `amount`, rounded down to what can be stored
+fn percent_of(amount: Decimal, percent: Decimal) -> Decimal {
+    (amount * percent / Decimal::ONE_HUNDRED)
+        .round_dp_with_strategy(MONEY_SCALE, RoundingStrategy::ToZero)
+        .normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_reward_share_for_deposit() {
+        let referrer_id = Uuid::new_v4();
+
+        let share = RewardShare::for_deposit(referrer_id, dec("200"), Some(2.5), dec("10")).unwrap();
+        assert_eq!(share.referrer_id, referrer_id);
+        assert_eq!(share.platform_fee, dec("5"));
+        assert_eq!(share.amount, dec("0.5"));
+
+        let share = RewardShare::for_deposit(referrer_id, dec("1"), Some(0.1), dec("10")).unwrap();
+        assert_eq!(share.platform_fee, dec("0.001"));
+        assert_eq!(share.amount, dec("0.0001"));
+    }
+
+    #[test]
+    fn test_reward_share_rounds_down_to_nothing() {
+        let referrer_id = Uuid::new_v4();
+        assert!(RewardShare::for_deposit(referrer_id, dec("100"), None, dec("10")).is_none());
+        assert!(RewardShare::for_deposit(referrer_id, dec("100"), Some(0.0), dec("10")).is_none());
+        assert!(RewardShare::for_deposit(referrer_id, dec("100"), Some(2.0), dec("0")).is_none());
+        assert!(RewardShare::for_deposit(referrer_id, dec("0.00000001"), Some(1.0), dec("10")).is_none());
+    }
+}
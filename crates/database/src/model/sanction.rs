@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What a sanction stops a user doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum SanctionKind {
+    /// No posting and no deposits
+    Ban,
+    /// No posting
+    Mute,
+}
+
+impl SanctionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SanctionKind::Ban => "ban",
+            SanctionKind::Mute => "mute",
+        }
+    }
+}
+
+/// A ban or mute on a user, in one community or across the platform
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Sanction {
+    pub id: Uuid,
+    #[serde(rename = "userId")]
+    pub user_id: Uuid,
+    /// `None` for platform-wide sanctions
+    #[serde(rename = "communityId")]
+    pub community_id: Option<Uuid>,
+    pub kind: SanctionKind,
+    pub reason: String,
+    #[serde(rename = "issuedBy")]
+    pub issued_by: Uuid,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    /// `None` for sanctions that last until revoked
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(rename = "revokedAt")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(rename = "revokedBy")]
+    pub revoked_by: Option<Uuid>,
+}
+
+impl Sanction {
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// The sanction that decides what a user may do, out of those in force on
+/// them: a ban over a mute, then whichever lasts longest
+pub fn strictest(sanctions: &[Sanction]) -> Option<&Sanction> {
+    sanctions.iter().max_by_key(|sanction| {
+        // `None` sorts below `Some`, but lasting until revoked is the longest
+        let lasts = sanction.expires_at.map_or((1, DateTime::<Utc>::MIN_UTC), |expires_at| (0, expires_at));
+        (sanction.kind == SanctionKind::Ban, lasts)
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSanctionDto {
+    #[serde(rename = "userId")]
+    pub user_id: Uuid,
+    pub kind: SanctionKind,
+    pub reason: String,
+    /// Leave out for a sanction that lasts until revoked
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for listing sanctions
+#[derive(Debug, Default, Deserialize)]
+pub struct SanctionListQuery {
+    /// Include expired and revoked sanctions
+    #[serde(default)]
+    pub all: bool,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+/// Something a moderator did, as recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum AuditAction {
+    Banned,
+    Muted,
+    SanctionRevoked,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Banned => "banned",
+            AuditAction::Muted => "muted",
+            AuditAction::SanctionRevoked => "sanction_revoked",
+        }
+    }
+
+    pub fn issued(kind: SanctionKind) -> Self {
+        match kind {
+            SanctionKind::Ban => AuditAction::Banned,
+            SanctionKind::Mute => AuditAction::Muted,
+        }
+    }
+}
+
+/// An entry in the moderation audit log
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    /// `None` for platform-wide actions
+    #[serde(rename = "communityId")]
+    pub community_id: Option<Uuid>,
+    #[serde(rename = "actorId")]
+    pub actor_id: Uuid,
+    pub action: AuditAction,
+    #[serde(rename = "targetUserId")]
+    pub target_user_id: Option<Uuid>,
+    #[serde(rename = "sanctionId")]
+    pub sanction_id: Option<Uuid>,
+    pub reason: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query parameters for reading the audit log
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditLogQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn sanction(kind: SanctionKind, expires_at: Option<DateTime<Utc>>) -> Sanction {
+        Sanction {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            community_id: None,
+            kind,
+            reason: "spam".to_string(),
+            issued_by: Uuid::new_v4(),
+            created_at: Utc::now(),
+            expires_at,
+            revoked_at: None,
+            revoked_by: None,
+        }
+    }
+
+    #[test]
+    fn test_sanction_is_active() {
+        let now = Utc::now();
+        let mut sanction = sanction(SanctionKind::Mute, None);
+        assert!(sanction.is_active_at(now));
+
+        sanction.expires_at = Some(now + Duration::hours(1));
+        assert!(sanction.is_active_at(now));
+        assert!(!sanction.is_active_at(now + Duration::hours(1)));
+
+        sanction.revoked_at = Some(now);
+        assert!(!sanction.is_active_at(now));
+    }
+    #[test]
+    fn test_strictest() {
+        let now = Utc::now();
+        let short_mute = sanction(SanctionKind::Mute, Some(now + Duration::hours(1)));
+        let long_mute = sanction(SanctionKind::Mute, Some(now + Duration::days(1)));
+        let open_mute = sanction(SanctionKind::Mute, None);
+        let short_ban = sanction(SanctionKind::Ban, Some(now + Duration::hours(1)));
+
+        assert!(strictest(&[]).is_none());
+
+        let sanctions = [short_mute.clone(), long_mute.clone()];
+        assert_eq!(strictest(&sanctions).unwrap().id, long_mute.id);
+
+        let sanctions = [long_mute.clone(), open_mute.clone(), short_mute.clone()];
+        assert_eq!(strictest(&sanctions).unwrap().id, open_mute.id);
+
+        let sanctions = [open_mute, short_ban.clone(), long_mute];
+        assert_eq!(strictest(&sanctions).unwrap().id, short_ban.id);
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// What a search hit points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum SearchResultKind {
+    Community,
+    Content,
+}
+
+impl SearchResultKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchResultKind::Community => "community",
+            SearchResultKind::Content => "content",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SearchResult {
+    pub kind: SearchResultKind,
+    pub id: Uuid,
+    #[serde(rename = "communityId")]
+    pub community_id: Uuid,
+    /// Name of the community the hit belongs to
+    pub title: String,
+    /// HTML-escaped matching excerpt with hits wrapped in `<mark>` tags
+    pub snippet: String,
+    pub rank: f32,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query parameters for search
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    /// Restrict results to communities or messages
+    #[serde(rename = "type")]
+    pub kind: Option<SearchResultKind>,
+    pub limit: Option<i64>,
+}
+
+/// Markers `ts_headline` puts around hits. They are private-use characters so
+/// they cannot collide with user text, and are swapped for `<mark>` tags once
+/// the rest of the snippet has been HTML-escaped.
+pub const HIGHLIGHT_START: char = '\u{E000}';
+pub const HIGHLIGHT_END: char = '\u{E001}';
+
+/// Options passed to `ts_headline` when building snippets
+pub fn headline_options() -> String {
+    format!(
+        "StartSel={}, StopSel={}, MaxWords=35, MinWords=15, MaxFragments=2",
+        HIGHLIGHT_START, HIGHLIGHT_END
+    )
+}
+
+/// Escape a raw `ts_headline` snippet for HTML and turn the highlight markers
+/// into `<mark>` tags
+pub fn render_snippet(raw: &str) -> String {
+    let mut html = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            HIGHLIGHT_START => html.push_str("<mark>"),
+            HIGHLIGHT_END => html.push_str("</mark>"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+/// Turn free text into a `simple` tsquery that matches word prefixes.
+///
+/// Korean attaches particles to words ("서울에서" is "서울" + "에서"), and
+/// Postgres has no dictionary to strip them, so each term is matched as a
+/// prefix instead. Characters with meaning in tsquery syntax are dropped.
+/// Returns `None` if nothing searchable is left.
+pub fn prefix_tsquery(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.chars().filter(|c| c.is_alphanumeric()).collect::<String>())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("{}:*", word.to_lowercase()))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" & "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_tsquery() {
+        assert_eq!(prefix_tsquery("서울 Pot"), Some("서울:* & pot:*".to_string()));
+        assert_eq!(prefix_tsquery("it's (big) | !"), Some("its:* & big:*".to_string()));
+        assert_eq!(prefix_tsquery("  & | ! "), None);
+    }
+
+    #[test]
+    fn test_render_snippet_escapes_user_text() {
+        let raw = format!("<b>{}pot{}</b> & more", HIGHLIGHT_START, HIGHLIGHT_END);
+        assert_eq!(render_snippet(&raw), "&lt;b&gt;<mark>pot</mark>&lt;/b&gt; &amp; more");
+    }
+}
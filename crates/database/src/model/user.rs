@@ -8,8 +8,8 @@ pub struct User {
     pub id: Uuid,
     pub xid: String, 
     pub username: String,
-    #[serde(rename = "profileImageUrl")]
-    pub profile_image_url: Option<String>,
+    #[serde(rename = "profileImageAssetId")]
+    pub profile_image_asset_id: Option<Uuid>,
     #[serde(rename = "walletAddress")]
     pub wallet_address: String,
     pub email: String,
@@ -22,8 +22,8 @@ pub struct User {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateUserDto {
     pub username: String,
-    #[serde(rename = "profileImageUrl")]
-    pub profile_image_url: Option<String>,
+    #[serde(rename = "profileImageAssetId")]
+    pub profile_image_asset_id: Option<Uuid>,
     #[serde(rename = "walletAddress")]
     pub wallet_address: String,
     pub email: String,
@@ -33,12 +33,17 @@ pub struct CreateUserDto {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateUserDto {
     pub username: Option<String>,
-    #[serde(rename = "profileImageUrl")]
-    pub profile_image_url: Option<String>,
+    #[serde(rename = "profileImageAssetId")]
+    pub profile_image_asset_id: Option<Uuid>,
     #[serde(rename = "walletAddress")]
     pub wallet_address: Option<String>,
     pub email: Option<String>,
     pub password: Option<String>,
 }
 
-
+/// Query parameters for listing users
+#[derive(Debug, Default, Deserialize)]
+pub struct UserListQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
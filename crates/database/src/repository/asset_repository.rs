@@ -0,0 +1,72 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::model::asset::{Asset, ImageMime};
+
+pub struct AssetRepository;
+
+impl AssetRepository {
+    /// Record an asset whose blobs have already been stored
+    pub async fn create(pool: &Pool<Postgres>, asset: &Asset) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO assets (
+                id, owner_id, mime_type, byte_size, width, height,
+                blob_key, thumbnail_mime_type, thumbnail_key, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            asset.id,
+            asset.owner_id,
+            asset.mime_type.as_str(),
+            asset.byte_size,
+            asset.width,
+            asset.height,
+            asset.blob_key,
+            asset.thumbnail_mime_type.as_str(),
+            asset.thumbnail_key,
+            asset.created_at
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(pool: &Pool<Postgres>, id: Uuid) -> Result<Option<Asset>, sqlx::Error> {
+        let asset = sqlx::query_as!(
+            Asset,
+            r#"
+            SELECT
+                id, owner_id, mime_type as "mime_type: ImageMime", byte_size, width, height,
+                blob_key, thumbnail_mime_type as "thumbnail_mime_type: ImageMime", thumbnail_key, created_at
+            FROM assets WHERE id = $1
+            "#,
+            id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(asset)
+    }
+
+    /// Whether `user_id` may attach the asset to something: it has to exist
+    /// and belong to them. Uploads made before signing up belong to nobody and
+    /// can only be used where there is no user yet (`None`).
+    pub async fn is_usable_by(pool: &Pool<Postgres>, id: Uuid, user_id: Option<Uuid>) -> Result<bool, sqlx::Error> {
+        let usable = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM assets
+                WHERE id = $1 AND owner_id IS NOT DISTINCT FROM $2
+            ) as "usable!"
+            "#,
+            id,
+            user_id
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(usable)
+    }
+}
@@ -1,29 +1,60 @@
-use sqlx::{Pool, Postgres};
+use sqlx::{Pool, Postgres, Transaction};
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 
-use crate::model::community::{Community, CreateCommunityDto, UpdateCommunityDto};
+use crate::model::community::{
+    Community, CommunityListQuery, CommunityStatus, CommunityStatusTransition, CommunityVisibility,
+    CreateCommunityDto, UpdateCommunityDto,
+};
+use crate::model::content::Content;
+use crate::model::feed::RoundResult;
+use crate::model::pagination::{Cursor, Page};
+use crate::model::payout::{NewPayout, Payout};
+use crate::repository::PayoutRepository;
 
 pub struct CommunityRepository;
 
 impl CommunityRepository {
-    /// Find all communities
-    pub async fn find_all(pool: &Pool<Postgres>) -> Result<Vec<Community>, sqlx::Error> {
+    /// Find a page of communities, newest first.
+    ///
+    /// `after` is the position of the last community on the previous page.
+    pub async fn find_page(
+        pool: &Pool<Postgres>,
+        query: &CommunityListQuery,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Page<Community>, sqlx::Error> {
         let communities = sqlx::query_as!(
             Community,
             r#"
             SELECT 
                 id, name, description, created_at, creator_id, creator_xid, last_message_time, 
                 contract_address, bounty_amount, time_limit, 
-                base_fee_percentage, wallet_address, image_url
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
             FROM communities
-            "#
+            WHERE ($1::varchar IS NULL OR status = $1)
+                AND ($2::uuid IS NULL OR creator_id = $2)
+                AND ($3::numeric IS NULL OR bounty_amount >= $3)
+                AND ($4::timestamptz IS NULL OR (created_at, id) < ($4, $5::uuid))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $6
+            "#,
+            query.status.map(|status| status.as_str()),
+            query.creator_id,
+            query.min_bounty,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
         )
             .fetch_all(pool)
             .await?;
 
-        Ok(communities)
+        Ok(Page::from_rows(communities, limit, |community| {
+            Cursor::new(community.created_at, community.id)
+        }))
     }
 
     /// Find a community by ID
@@ -34,7 +65,9 @@ impl CommunityRepository {
             SELECT 
                 id, name, description, created_at, creator_id, creator_xid, last_message_time, 
                 contract_address, bounty_amount, time_limit, 
-                base_fee_percentage, wallet_address, image_url
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
             FROM communities WHERE id = $1
             "#,
             id
@@ -53,7 +86,9 @@ impl CommunityRepository {
             SELECT 
                 id, name, description, created_at, creator_id, creator_xid, last_message_time, 
                 contract_address, bounty_amount, time_limit, 
-                base_fee_percentage, wallet_address, image_url
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
             FROM communities WHERE creator_id = $1
             "#,
             creator_id
@@ -64,7 +99,7 @@ impl CommunityRepository {
         Ok(communities)
     }
 
-    /// Create a new community
+    /// Create a new community, with its creator as the first member
     pub async fn create(pool: &Pool<Postgres>, dto: CreateCommunityDto) -> Result<Community, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -75,19 +110,24 @@ impl CommunityRepository {
         let creator_id = dto.creator_id.unwrap_or(default_uuid);
         let creator_xid = dto.creator_xid.unwrap_or_else(|| "default-user".to_string());
 
+        let mut tx = pool.begin().await?;
+
         let community = sqlx::query_as!(
             Community,
             r#"
             INSERT INTO communities (
                 id, name, description, created_at, creator_id, creator_xid,
                 contract_address, bounty_amount, time_limit,
-                base_fee_percentage, wallet_address, image_url
+                base_fee_percentage, wallet_address, image_asset_id,
+                status, status_updated_at, visibility, member_count
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $4, $14, 1)
             RETURNING 
                 id, name, description, created_at, creator_id, creator_xid, last_message_time,
                 contract_address, bounty_amount, time_limit,
-                base_fee_percentage, wallet_address, image_url
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
             "#,
             id,
             dto.name,
@@ -100,11 +140,26 @@ impl CommunityRepository {
             dto.time_limit,
             dto.base_fee_percentage,
             dto.wallet_address,
-            dto.image_url
+            dto.image_asset_id,
+            CommunityStatus::Draft.as_str(),
+            dto.visibility.unwrap_or_default().as_str()
+        )
+            .fetch_one(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO community_members (community_id, user_id, status, created_at, joined_at)
+            VALUES ($1, $2, 'active', $3, $3)
+            "#,
+            id,
+            creator_id,
+            now
         )
-            .fetch_one(pool)
+            .execute(&mut *tx)
             .await?;
 
+        tx.commit().await?;
         Ok(community)
     }
 
@@ -129,12 +184,15 @@ impl CommunityRepository {
                 time_limit = COALESCE($6, time_limit),
                 base_fee_percentage = COALESCE($7, base_fee_percentage),
                 wallet_address = COALESCE($8, wallet_address),
-                image_url = COALESCE($9, image_url)
-            WHERE id = $10
+                image_asset_id = COALESCE($9, image_asset_id),
+                visibility = COALESCE($10, visibility)
+            WHERE id = $11
             RETURNING 
                 id, name, description, created_at, creator_id, creator_xid, last_message_time,
                 contract_address, bounty_amount, time_limit,
-                base_fee_percentage, wallet_address, image_url
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
             "#,
             dto.name,
             dto.description,
@@ -144,7 +202,8 @@ impl CommunityRepository {
             dto.time_limit,
             dto.base_fee_percentage,
             dto.wallet_address,
-            dto.image_url,
+            dto.image_asset_id,
+            dto.visibility.map(|visibility| visibility.as_str()),
             id
         )
             .fetch_optional(pool)
@@ -153,35 +212,515 @@ impl CommunityRepository {
         Ok(community)
     }
 
-    /// Delete a community
+    /// Delete a community along with its content, deposits, status history and events
     pub async fn delete(pool: &Pool<Postgres>, id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!("DELETE FROM notifications WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM held_contents WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_moderation WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_rate_limits WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM moderation_audit_log WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM sanctions WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_follows WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_referrals WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_invites WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_members WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM pinned_contents WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM content WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM depositor WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_status_transitions WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_events WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM community_presence WHERE community_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
         let result = sqlx::query!(
             r#"
             DELETE FROM communities WHERE id = $1
             "#,
             id
         )
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
 
+        tx.commit().await?;
+
         Ok(result.rows_affected() > 0)
     }
 
-    /// Update last message time
-    pub async fn update_last_message_time(pool: &Pool<Postgres>, id: Uuid) -> Result<bool, sqlx::Error> {
+    /// Move a community from `from` to `to`, recording the transition.
+    ///
+    /// The update only applies if the community is still in `from`, so two
+    /// concurrent transitions cannot both succeed. Returns `None` if the
+    /// community does not exist or its status has changed in the meantime.
+    pub async fn transition_status(
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        from: CommunityStatus,
+        to: CommunityStatus,
+    ) -> Result<Option<Community>, sqlx::Error> {
         let now = Utc::now();
-        let result = sqlx::query!(
+        let mut tx = pool.begin().await?;
+
+        let community = sqlx::query_as!(
+            Community,
             r#"
             UPDATE communities
-            SET last_message_time = $1
+            SET status = $1, status_updated_at = $2
+            WHERE id = $3 AND status = $4
+            RETURNING 
+                id, name, description, created_at, creator_id, creator_xid, last_message_time,
+                contract_address, bounty_amount, time_limit,
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
+            "#,
+            to.as_str(),
+            now,
+            id,
+            from.as_str()
+        )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if community.is_none() {
+            return Ok(None);
+        }
+
+        Self::record_transition(&mut tx, id, from, to, now).await?;
+        tx.commit().await?;
+
+        Ok(community)
+    }
+
+    /// Settle an expired round, paying its bounty to the sender of `winning`.
+    ///
+    /// The payout is recorded in the same transaction as the status change,
+    /// and the bounty it pays is cleared so the next round starts from
+    /// nothing. A round without a winner, or without a bounty, keeps it.
+    pub async fn settle_round(
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        winning: Option<&Content>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<(Community, Option<Payout>)>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let community = sqlx::query_as!(
+            Community,
+            r#"
+            SELECT 
+                id, name, description, created_at, creator_id, creator_xid, last_message_time,
+                contract_address, bounty_amount, time_limit,
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
+            FROM communities
+            WHERE id = $1 AND status = 'expired'
+            FOR UPDATE
+            "#,
+            id
+        )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(community) = community else {
+            return Ok(None);
+        };
+
+        let payout = match winning {
+            Some(winning) if community.bounty_amount > Decimal::ZERO => {
+                let payout = NewPayout::round_winnings(&community, winning);
+                Some(PayoutRepository::create(&mut tx, &payout, now).await?)
+            },
+            _ => None,
+        };
+
+        let community = sqlx::query_as!(
+            Community,
+            r#"
+            UPDATE communities
+            SET status = 'settled', status_updated_at = $1,
+                bounty_amount = CASE WHEN $3 THEN 0 ELSE bounty_amount END
+            WHERE id = $2
+            RETURNING 
+                id, name, description, created_at, creator_id, creator_xid, last_message_time,
+                contract_address, bounty_amount, time_limit,
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
+            "#,
+            now,
+            id,
+            payout.is_some()
+        )
+            .fetch_one(&mut *tx)
+            .await?;
+
+        Self::record_transition(&mut tx, id, CommunityStatus::Expired, CommunityStatus::Settled, now).await?;
+        tx.commit().await?;
+
+        Ok(Some((community, payout)))
+    }
+
+    /// End a live round, but only if its timer has really run out as of `now`.
+    ///
+    /// The deadline is re-checked under the row lock, so a message that
+    /// extended the timer first always wins over a stale expiry.
+    pub async fn expire_round(pool: &Pool<Postgres>, id: Uuid, now: DateTime<Utc>) -> Result<Option<Community>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let community = sqlx::query_as!(
+            Community,
+            r#"
+            UPDATE communities
+            SET status = 'expired', status_updated_at = $1
             WHERE id = $2
+                AND status = 'live'
+                AND time_limit IS NOT NULL
+                AND GREATEST(COALESCE(last_message_time, status_updated_at), status_updated_at)
+                    + time_limit * INTERVAL '1 second' <= $1
+            RETURNING 
+                id, name, description, created_at, creator_id, creator_xid, last_message_time,
+                contract_address, bounty_amount, time_limit,
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
             "#,
             now,
             id
         )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if community.is_none() {
+            return Ok(None);
+        }
+
+        Self::record_transition(&mut tx, id, CommunityStatus::Live, CommunityStatus::Expired, now).await?;
+        tx.commit().await?;
+
+        Ok(community)
+    }
+
+    async fn record_transition(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        from: CommunityStatus,
+        to: CommunityStatus,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO community_status_transitions (id, community_id, from_status, to_status, transitioned_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            Uuid::new_v4(),
+            id,
+            from.as_str(),
+            to.as_str(),
+            now
+        )
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Find the status history of a community, oldest first
+    pub async fn find_status_transitions(pool: &Pool<Postgres>, community_id: Uuid) -> Result<Vec<CommunityStatusTransition>, sqlx::Error> {
+        let transitions = sqlx::query_as!(
+            CommunityStatusTransition,
+            r#"
+            SELECT 
+                id, community_id,
+                from_status as "from_status: CommunityStatus",
+                to_status as "to_status: CommunityStatus",
+                transitioned_at
+            FROM community_status_transitions
+            WHERE community_id = $1
+            ORDER BY transitioned_at
+            "#,
+            community_id
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(transitions)
+    }
+
+    /// Find the rounds that ended in the communities a user follows, newest
+    /// first, leaving out private communities they aren't in. The winner is
+    /// picked as settlement picks it: the last message posted while the round
+    /// was live, so a round nobody posted in has none.
+    ///
+    /// Like `ContentRepository::find_feed`, each community contributes at most
+    /// `limit + 1` rows from its own index, and that many are returned.
+    pub async fn find_round_results(pool: &Pool<Postgres>, user_id: Uuid, after: Option<Cursor>, limit: i64) -> Result<Vec<RoundResult>, sqlx::Error> {
+        let results = sqlx::query_as!(
+            RoundResult,
+            r#"
+            SELECT
+                t.id, t.community_id, cm.name as community_name, t.transitioned_at as ended_at,
+                w.id as "winning_content_id?", w.sender_id as "winner_id?", w.sender_xid as "winner_xid?"
+            FROM community_follows cf
+            JOIN communities cm ON cm.id = cf.community_id
+            CROSS JOIN LATERAL (
+                SELECT s.id, s.community_id, s.transitioned_at
+                FROM community_status_transitions s
+                WHERE s.community_id = cf.community_id
+                    AND s.to_status = 'expired'
+                    AND ($2::timestamptz IS NULL OR (s.transitioned_at, s.id) < ($2, $3::uuid))
+                ORDER BY s.transitioned_at DESC, s.id DESC
+                LIMIT $4
+            ) t
+            LEFT JOIN LATERAL (
+                SELECT c.id, c.sender_id, c.sender_xid
+                FROM content c
+                WHERE c.community_id = t.community_id AND c.created_at <= t.transitioned_at
+                    AND c.created_at >= COALESCE(
+                        (
+                            SELECT MAX(l.transitioned_at)
+                            FROM community_status_transitions l
+                            WHERE l.community_id = t.community_id AND l.to_status = 'live'
+                                AND l.transitioned_at <= t.transitioned_at
+                        ),
+                        '-infinity'
+                    )
+                    AND NOT EXISTS (SELECT 1 FROM held_contents h WHERE h.content_id = c.id)
+                ORDER BY c.created_at DESC, c.id DESC
+                LIMIT 1
+            ) w ON true
+            WHERE cf.user_id = $1
+                AND (
+                    cm.visibility = 'public'
+                    OR EXISTS (
+                        SELECT 1 FROM community_members m
+                        WHERE m.community_id = cm.id AND m.user_id = $1 AND m.status = 'active'
+                    )
+                )
+            ORDER BY t.transitioned_at DESC, t.id DESC
+            LIMIT $4
+            "#,
+            user_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(results)
+    }
+
+    /// Find open communities ranked by recent activity.
+    ///
+    /// Scores come from the `community_trending` materialized view, so they are
+    /// only as fresh as the last `refresh_trending`.
+    pub async fn find_trending(pool: &Pool<Postgres>, limit: i64) -> Result<Vec<Community>, sqlx::Error> {
+        let communities = sqlx::query_as!(
+            Community,
+            r#"
+            SELECT 
+                c.id, c.name, c.description, c.created_at, c.creator_id, c.creator_xid, c.last_message_time,
+                c.contract_address, c.bounty_amount, c.time_limit,
+                c.base_fee_percentage, c.wallet_address, c.image_asset_id,
+                c.status as "status: CommunityStatus", c.status_updated_at,
+                c.visibility as "visibility: CommunityVisibility", c.member_count
+            FROM communities c
+            LEFT JOIN community_trending t ON t.community_id = c.id
+            WHERE c.status IN ('funding', 'live')
+            ORDER BY COALESCE(t.score, 0) DESC, c.created_at DESC, c.id DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(communities)
+    }
+
+    /// Find live communities whose round ends soonest, skipping ones already timed out
+    pub async fn find_ending_soon(pool: &Pool<Postgres>, limit: i64) -> Result<Vec<Community>, sqlx::Error> {
+        let communities = sqlx::query_as!(
+            Community,
+            r#"
+            SELECT 
+                c.id, c.name, c.description, c.created_at, c.creator_id, c.creator_xid, c.last_message_time,
+                c.contract_address, c.bounty_amount, c.time_limit,
+                c.base_fee_percentage, c.wallet_address, c.image_asset_id,
+                c.status as "status: CommunityStatus", c.status_updated_at,
+                c.visibility as "visibility: CommunityVisibility", c.member_count
+            FROM communities c
+            CROSS JOIN LATERAL (
+                SELECT GREATEST(COALESCE(c.last_message_time, c.status_updated_at), c.status_updated_at)
+                    + c.time_limit * INTERVAL '1 second' AS deadline
+            ) d
+            WHERE c.status = 'live'
+                AND c.time_limit IS NOT NULL
+                AND d.deadline > NOW()
+            ORDER BY d.deadline ASC, c.id
+            LIMIT $1
+            "#,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(communities)
+    }
+
+    /// Find open communities with the biggest bounties
+    pub async fn find_largest_bounty(pool: &Pool<Postgres>, limit: i64) -> Result<Vec<Community>, sqlx::Error> {
+        let communities = sqlx::query_as!(
+            Community,
+            r#"
+            SELECT 
+                c.id, c.name, c.description, c.created_at, c.creator_id, c.creator_xid, c.last_message_time,
+                c.contract_address, c.bounty_amount, c.time_limit,
+                c.base_fee_percentage, c.wallet_address, c.image_asset_id,
+                c.status as "status: CommunityStatus", c.status_updated_at,
+                c.visibility as "visibility: CommunityVisibility", c.member_count
+            FROM communities c
+            WHERE c.status IN ('funding', 'live')
+            ORDER BY c.bounty_amount DESC, c.created_at DESC, c.id DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(communities)
+    }
+
+    /// Find the most recently created open communities
+    pub async fn find_newest(pool: &Pool<Postgres>, limit: i64) -> Result<Vec<Community>, sqlx::Error> {
+        let communities = sqlx::query_as!(
+            Community,
+            r#"
+            SELECT 
+                c.id, c.name, c.description, c.created_at, c.creator_id, c.creator_xid, c.last_message_time,
+                c.contract_address, c.bounty_amount, c.time_limit,
+                c.base_fee_percentage, c.wallet_address, c.image_asset_id,
+                c.status as "status: CommunityStatus", c.status_updated_at,
+                c.visibility as "visibility: CommunityVisibility", c.member_count
+            FROM communities c
+            WHERE c.status IN ('funding', 'live')
+            ORDER BY c.created_at DESC, c.id DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(communities)
+    }
+
+    /// Recompute the trending scores without blocking readers
+    pub async fn refresh_trending(pool: &Pool<Postgres>) -> Result<(), sqlx::Error> {
+        sqlx::query!("REFRESH MATERIALIZED VIEW CONCURRENTLY community_trending")
             .execute(pool)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(())
+    }
+
+    /// Find live communities whose round timer has already run out
+    pub async fn find_due_for_expiry(pool: &Pool<Postgres>, limit: i64) -> Result<Vec<Community>, sqlx::Error> {
+        let communities = sqlx::query_as!(
+            Community,
+            r#"
+            SELECT 
+                c.id, c.name, c.description, c.created_at, c.creator_id, c.creator_xid, c.last_message_time,
+                c.contract_address, c.bounty_amount, c.time_limit,
+                c.base_fee_percentage, c.wallet_address, c.image_asset_id,
+                c.status as "status: CommunityStatus", c.status_updated_at,
+                c.visibility as "visibility: CommunityVisibility", c.member_count
+            FROM communities c
+            WHERE c.status = 'live'
+                AND c.time_limit IS NOT NULL
+                AND GREATEST(COALESCE(c.last_message_time, c.status_updated_at), c.status_updated_at)
+                    + c.time_limit * INTERVAL '1 second' <= NOW()
+            LIMIT $1
+            "#,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(communities)
+    }
+
+    /// Find rounds that have ended and are waiting to be settled
+    pub async fn find_due_for_settlement(pool: &Pool<Postgres>, limit: i64) -> Result<Vec<Community>, sqlx::Error> {
+        let communities = sqlx::query_as!(
+            Community,
+            r#"
+            SELECT 
+                c.id, c.name, c.description, c.created_at, c.creator_id, c.creator_xid, c.last_message_time,
+                c.contract_address, c.bounty_amount, c.time_limit,
+                c.base_fee_percentage, c.wallet_address, c.image_asset_id,
+                c.status as "status: CommunityStatus", c.status_updated_at,
+                c.visibility as "visibility: CommunityVisibility", c.member_count
+            FROM communities c
+            WHERE c.status = 'expired'
+            ORDER BY c.status_updated_at
+            LIMIT $1
+            "#,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(communities)
     }
-}
\ No newline at end of file
+}
@@ -1,27 +1,74 @@
-use sqlx::{Pool, Postgres};
+use sqlx::{types::Json, PgExecutor, Pool, Postgres};
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
-use crate::model::content::{Content, CreateContentDto};
+use crate::model::community::{Community, CommunityStatus, CommunityVisibility};
+use crate::model::content::{Content, ContentEdit, ContentListQuery, CreateContentDto};
+use crate::model::link_preview::LinkPreview;
+use crate::model::pagination::{Cursor, Page};
+use crate::model::reaction::ReactionCounts;
 
 pub struct ContentRepository;
 
 impl ContentRepository {
-    /// Find all content
-    pub async fn find_all(pool: &Pool<Postgres>) -> Result<Vec<Content>, sqlx::Error> {
+    /// Find a page of content, newest first, optionally limited to one community.
+    /// Messages in private communities are left out unless `viewer` is a member.
+    ///
+    /// Messages are read from `content_view`, whose reply and reaction counts
+    /// come from correlated subqueries on indexed columns, so a page costs one
+    /// round trip however many rows it has. sqlx can't tell which view columns
+    /// are never null, hence the `!` on each one.
+    ///
+    /// `after` is the position of the last content on the previous page.
+    pub async fn find_page(
+        pool: &Pool<Postgres>,
+        community_id: Option<Uuid>,
+        viewer: Option<Uuid>,
+        query: &ContentListQuery,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Page<Content>, sqlx::Error> {
         let contents = sqlx::query_as!(
             Content,
             r#"
-            SELECT 
-                id, content, created_at, sender_id, sender_xid, image_url, community_id, wallet_address
-            FROM content
-            ORDER BY created_at DESC
-            "#
+            SELECT
+                c.id as "id!", c.content as "content!", c.created_at as "created_at!",
+                c.sender_id as "sender_id!", c.sender_xid as "sender_xid!", c.image_asset_id,
+                c.community_id as "community_id!", c.wallet_address as "wallet_address!",
+                c.reply_to_id, c.edited_at, c.deleted_at, c.reply_count as "reply_count!",
+                c.reactions as "reactions!: Json<ReactionCounts>", c.previews as "previews!: Json<Vec<LinkPreview>>"
+            FROM content_view c
+            JOIN communities cm ON cm.id = c.community_id
+            WHERE ($1::uuid IS NULL OR c.community_id = $1)
+                AND ($2::uuid IS NULL OR c.sender_id = $2)
+                AND ($3::timestamptz IS NULL OR c.created_at >= $3)
+                AND ($4::timestamptz IS NULL OR c.created_at < $4)
+                AND ($5::timestamptz IS NULL OR (c.created_at, c.id) < ($5, $6::uuid))
+                AND (
+                    cm.visibility = 'public'
+                    OR EXISTS (
+                        SELECT 1 FROM community_members m
+                        WHERE m.community_id = cm.id AND m.user_id = $8 AND m.status = 'active'
+                    )
+                )
+            ORDER BY c.created_at DESC, c.id DESC
+            LIMIT $7
+            "#,
+            community_id,
+            query.sender_id,
+            query.since,
+            query.until,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1,
+            viewer
         )
             .fetch_all(pool)
             .await?;
 
-        Ok(contents)
+        Ok(Page::from_rows(contents, limit, |content| {
+            Cursor::new(content.created_at, content.id)
+        }))
     }
 
     /// Find content by ID
@@ -29,9 +76,13 @@ impl ContentRepository {
         let content = sqlx::query_as!(
             Content,
             r#"
-            SELECT 
-                id, content, created_at, sender_id, sender_xid, image_url, community_id, wallet_address
-            FROM content WHERE id = $1
+            SELECT
+                c.id as "id!", c.content as "content!", c.created_at as "created_at!",
+                c.sender_id as "sender_id!", c.sender_xid as "sender_xid!", c.image_asset_id,
+                c.community_id as "community_id!", c.wallet_address as "wallet_address!",
+                c.reply_to_id, c.edited_at, c.deleted_at, c.reply_count as "reply_count!",
+                c.reactions as "reactions!: Json<ReactionCounts>", c.previews as "previews!: Json<Vec<LinkPreview>>"
+            FROM content_view c WHERE c.id = $1
             "#,
             id
         )
@@ -41,17 +92,112 @@ impl ContentRepository {
         Ok(content)
     }
 
-    /// Find content by community ID
-    pub async fn find_by_community_id(pool: &Pool<Postgres>, community_id: Uuid) -> Result<Vec<Content>, sqlx::Error> {
+    /// Find a page of direct replies to a message, oldest first.
+    ///
+    /// `after` is the position of the last reply on the previous page.
+    pub async fn find_replies(pool: &Pool<Postgres>, parent_id: Uuid, after: Option<Cursor>, limit: i64) -> Result<Page<Content>, sqlx::Error> {
+        let replies = sqlx::query_as!(
+            Content,
+            r#"
+            SELECT
+                c.id as "id!", c.content as "content!", c.created_at as "created_at!",
+                c.sender_id as "sender_id!", c.sender_xid as "sender_xid!", c.image_asset_id,
+                c.community_id as "community_id!", c.wallet_address as "wallet_address!",
+                c.reply_to_id, c.edited_at, c.deleted_at, c.reply_count as "reply_count!",
+                c.reactions as "reactions!: Json<ReactionCounts>", c.previews as "previews!: Json<Vec<LinkPreview>>"
+            FROM content_view c
+            WHERE c.reply_to_id = $1
+                AND ($2::timestamptz IS NULL OR (c.created_at, c.id) > ($2, $3::uuid))
+            ORDER BY c.created_at, c.id
+            LIMIT $4
+            "#,
+            parent_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(Page::from_rows(replies, limit, |reply| {
+            Cursor::new(reply.created_at, reply.id)
+        }))
+    }
+
+    /// Find messages from the users and communities someone follows, newest
+    /// first, leaving out tombstones and private communities they aren't in.
+    ///
+    /// Each followed user and community contributes at most a page of rows
+    /// from its own index, so the cost grows with what is followed rather than
+    /// with all content. `after` is the position of the last item on the
+    /// previous page; `limit + 1` rows are returned so the caller can tell
+    /// whether there is more.
+    pub async fn find_feed(pool: &Pool<Postgres>, user_id: Uuid, after: Option<Cursor>, limit: i64) -> Result<Vec<Content>, sqlx::Error> {
         let contents = sqlx::query_as!(
             Content,
             r#"
-            SELECT 
-                id, content, created_at, sender_id, sender_xid, image_url, community_id, wallet_address
-            FROM content WHERE community_id = $1
-            ORDER BY created_at DESC
+            WITH followed AS (
+                SELECT f.id, f.created_at
+                FROM user_follows uf
+                CROSS JOIN LATERAL (
+                    SELECT c.id, c.created_at
+                    FROM content c
+                    JOIN communities cm ON cm.id = c.community_id
+                    WHERE c.sender_id = uf.followed_id
+                        AND c.deleted_at IS NULL
+                        AND ($2::timestamptz IS NULL OR (c.created_at, c.id) < ($2, $3::uuid))
+                        AND (
+                            cm.visibility = 'public'
+                            OR EXISTS (
+                                SELECT 1 FROM community_members m
+                                WHERE m.community_id = cm.id AND m.user_id = $1 AND m.status = 'active'
+                            )
+                        )
+                    ORDER BY c.created_at DESC, c.id DESC
+                    LIMIT $4
+                ) f
+                WHERE uf.follower_id = $1
+                UNION
+                SELECT f.id, f.created_at
+                FROM community_follows cf
+                JOIN communities cm ON cm.id = cf.community_id
+                CROSS JOIN LATERAL (
+                    SELECT c.id, c.created_at
+                    FROM content c
+                    WHERE c.community_id = cf.community_id
+                        AND c.deleted_at IS NULL
+                        AND ($2::timestamptz IS NULL OR (c.created_at, c.id) < ($2, $3::uuid))
+                    ORDER BY c.created_at DESC, c.id DESC
+                    LIMIT $4
+                ) f
+                WHERE cf.user_id = $1
+                    AND (
+                        cm.visibility = 'public'
+                        OR EXISTS (
+                            SELECT 1 FROM community_members m
+                            WHERE m.community_id = cm.id AND m.user_id = $1 AND m.status = 'active'
+                        )
+                    )
+            ),
+            page AS (
+                SELECT id, created_at FROM followed
+                ORDER BY created_at DESC, id DESC
+                LIMIT $4
+            )
+            SELECT
+                c.id as "id!", c.content as "content!", c.created_at as "created_at!",
+                c.sender_id as "sender_id!", c.sender_xid as "sender_xid!", c.image_asset_id,
+                c.community_id as "community_id!", c.wallet_address as "wallet_address!",
+                c.reply_to_id, c.edited_at, c.deleted_at, c.reply_count as "reply_count!",
+                c.reactions as "reactions!: Json<ReactionCounts>", c.previews as "previews!: Json<Vec<LinkPreview>>"
+            FROM page
+            JOIN content_view c ON c.id = page.id
+            ORDER BY c.created_at DESC, c.id DESC
             "#,
-            community_id
+            user_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
         )
             .fetch_all(pool)
             .await?;
@@ -59,16 +205,147 @@ impl ContentRepository {
         Ok(contents)
     }
 
+    /// Find the message posted in a community just before `before`
+    pub async fn find_previous(pool: &Pool<Postgres>, community_id: Uuid, before: Cursor) -> Result<Option<Content>, sqlx::Error> {
+        let content = sqlx::query_as!(
+            Content,
+            r#"
+            SELECT
+                c.id as "id!", c.content as "content!", c.created_at as "created_at!",
+                c.sender_id as "sender_id!", c.sender_xid as "sender_xid!", c.image_asset_id,
+                c.community_id as "community_id!", c.wallet_address as "wallet_address!",
+                c.reply_to_id, c.edited_at, c.deleted_at, c.reply_count as "reply_count!",
+                c.reactions as "reactions!: Json<ReactionCounts>", c.previews as "previews!: Json<Vec<LinkPreview>>"
+            FROM content_view c
+            WHERE c.community_id = $1 AND (c.created_at, c.id) < ($2, $3)
+            ORDER BY c.created_at DESC, c.id DESC
+            LIMIT 1
+            "#,
+            community_id,
+            before.created_at,
+            before.id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(content)
+    }
+
+    /// The message that won a community's latest round: the last one that
+    /// restarted its timer, as long as it was posted after the round started.
+    /// Messages that never touched the timer, like approved held ones, can't win.
+    pub async fn find_round_winner(pool: &Pool<Postgres>, community_id: Uuid) -> Result<Option<Content>, sqlx::Error> {
+        let content = sqlx::query_as!(
+            Content,
+            r#"
+            SELECT
+                c.id as "id!", c.content as "content!", c.created_at as "created_at!",
+                c.sender_id as "sender_id!", c.sender_xid as "sender_xid!", c.image_asset_id,
+                c.community_id as "community_id!", c.wallet_address as "wallet_address!",
+                c.reply_to_id, c.edited_at, c.deleted_at, c.reply_count as "reply_count!",
+                c.reactions as "reactions!: Json<ReactionCounts>", c.previews as "previews!: Json<Vec<LinkPreview>>"
+            FROM content_view c
+            JOIN communities cm ON cm.id = c.community_id
+            WHERE c.community_id = $1
+                AND c.created_at <= cm.last_message_time
+                AND c.created_at >= COALESCE(
+                    (
+                        SELECT MAX(s.transitioned_at)
+                        FROM community_status_transitions s
+                        WHERE s.community_id = $1 AND s.to_status = 'live'
+                    ),
+                    '-infinity'
+                )
+                AND NOT EXISTS (SELECT 1 FROM held_contents h WHERE h.content_id = c.id)
+            ORDER BY c.created_at DESC, c.id DESC
+            LIMIT 1
+            "#,
+            community_id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(content)
+    }
+
     /// Create a new content
     pub async fn create(pool: &Pool<Postgres>, dto: CreateContentDto) -> Result<Content, sqlx::Error> {
+        Self::insert(pool, dto, Utc::now()).await
+    }
+
+    /// Post a message into a live round and restart its timer, atomically.
+    ///
+    /// This is where a last-second message is decided: it counts only if the
+    /// round is still live and its deadline is after the server's `now` while
+    /// the community row is locked. Returns `None` if the round was closed,
+    /// along with the updated community otherwise.
+    pub async fn create_in_round(pool: &Pool<Postgres>, dto: CreateContentDto) -> Result<Option<(Content, Community)>, sqlx::Error> {
         let now = Utc::now();
+        let mut tx = pool.begin().await?;
+
+        let community = sqlx::query_as!(
+            Community,
+            r#"
+            UPDATE communities
+            SET last_message_time = $1
+            WHERE id = $2
+                AND status = 'live'
+                AND (
+                    time_limit IS NULL
+                    OR GREATEST(COALESCE(last_message_time, status_updated_at), status_updated_at)
+                        + time_limit * INTERVAL '1 second' > $1
+                )
+            RETURNING 
+                id, name, description, created_at, creator_id, creator_xid, last_message_time,
+                contract_address, bounty_amount, time_limit,
+                base_fee_percentage, wallet_address, image_asset_id,
+                status as "status: CommunityStatus", status_updated_at,
+                visibility as "visibility: CommunityVisibility", member_count
+            "#,
+            now,
+            dto.community_id
+        )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(community) = community else {
+            return Ok(None);
+        };
+
+        let content = Self::insert(&mut *tx, dto, now).await?;
+        tx.commit().await?;
+
+        Ok(Some((content, community)))
+    }
+
+    /// Post an approved held message at the time it was sent, linking it to
+    /// its place in the moderation queue. The round timer is left alone.
+    pub async fn create_approved(
+        pool: &Pool<Postgres>,
+        held_id: Uuid,
+        dto: CreateContentDto,
+        sent_at: DateTime<Utc>,
+    ) -> Result<Content, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let content = Self::insert(&mut *tx, dto, sent_at).await?;
+        sqlx::query!("UPDATE held_contents SET content_id = $2 WHERE id = $1", held_id, content.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(content)
+    }
+
+    async fn insert<'e>(executor: impl PgExecutor<'e>, dto: CreateContentDto, now: DateTime<Utc>) -> Result<Content, sqlx::Error> {
         let id = Uuid::new_v4(); // 새 UUID 생성
 
         // Default sender IDs if not provided
         let default_uuid = Uuid::parse_str("00000000-0000-0000-0000-000000000000").unwrap();
         let sender_id = dto.sender_id.unwrap_or(default_uuid);
         let sender_xid = dto.sender_xid.unwrap_or_else(|| "default-user".to_string());
-        let wallet_address = dto.wallet_address.unwrap_or_else(|| "".to_string());
+        let wallet_address = dto.wallet_address.unwrap_or_default();
 
         let content = sqlx::query_as!(
         Content,
@@ -78,39 +355,248 @@ impl ContentRepository {
             content, 
             sender_id, 
             sender_xid, 
-            image_url, 
+            image_asset_id, 
             community_id, 
             wallet_address,
-            created_at
+            created_at,
+            reply_to_id
         )
         VALUES (
-            $1, $2, $3, $4, $5, $6, $7, $8
+            $1, $2, $3, $4, $5, $6, $7, $8, $9
         )
-        RETURNING id, content, sender_id, sender_xid, image_url, community_id, wallet_address, created_at
+        RETURNING id, content, sender_id, sender_xid, image_asset_id, community_id, wallet_address, created_at,
+            reply_to_id, edited_at, deleted_at, 0::bigint as "reply_count!", '{}'::jsonb as "reactions!: Json<ReactionCounts>",
+            '[]'::jsonb as "previews!: Json<Vec<LinkPreview>>"
         "#,
         id,              
         dto.content,
         sender_id,
         sender_xid,
-        dto.image_url,
+        dto.image_asset_id,
         dto.community_id,
         wallet_address,
-        now
+        now,
+        dto.reply_to_id
     )
-            .fetch_one(pool)
+            .fetch_one(executor)
             .await?;
 
         Ok(content)
     }
 
-    /// Delete content
-    pub async fn delete(pool: &Pool<Postgres>, id: Uuid) -> Result<bool, sqlx::Error> {
+    /// Replace a message's text, keeping what it said before in the edit history.
+    ///
+    /// Returns false if the message does not exist or has been deleted.
+    pub async fn edit(pool: &Pool<Postgres>, id: Uuid, editor_id: Uuid, text: &str, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let previous = sqlx::query_scalar!(
+            "SELECT content FROM content WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+            id
+        )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(previous) = previous else {
+            return Ok(false);
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO content_edits (id, content_id, previous_content, edited_by, edited_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            Uuid::new_v4(),
+            id,
+            previous,
+            editor_id,
+            now
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            "UPDATE content SET content = $1, edited_at = $2 WHERE id = $3",
+            text,
+            now,
+            id
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Turn a message into a tombstone, recording who deleted it and why.
+    ///
+    /// The row stays so the round it was part of is unaffected, but the text
+    /// is also scrubbed from logged events and mention notifications so it
+    /// doesn't come back through them.
+    /// Returns false if the message does not exist or was already deleted.
+    pub async fn tombstone(
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        deleted_by: Uuid,
+        reason: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
         let result = sqlx::query!(
             r#"
-            DELETE FROM content WHERE id = $1
+            UPDATE content
+            SET deleted_at = $1, deleted_by = $2, delete_reason = $3
+            WHERE id = $4 AND deleted_at IS NULL
+            "#,
+            now,
+            deleted_by,
+            reason,
+            id
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        // Nothing left to show at the top
+        sqlx::query!("DELETE FROM pinned_contents WHERE content_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE community_events
+            SET payload = payload || jsonb_build_object('content', '', 'imageAssetId', NULL, 'deletedAt', $1::timestamptz)
+            WHERE event_type IN ('content_created', 'content_edited')
+                AND payload->>'id' = $2
+            "#,
+            now,
+            id.to_string()
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE notifications
+            SET payload = payload || '{"excerpt": ""}'
+            WHERE content_id = $1 AND kind = 'mention'
             "#,
             id
         )
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Find the earlier versions of a message, oldest first
+    pub async fn find_edits(pool: &Pool<Postgres>, id: Uuid) -> Result<Vec<ContentEdit>, sqlx::Error> {
+        let edits = sqlx::query_as!(
+            ContentEdit,
+            r#"
+            SELECT id, content_id, previous_content, edited_by, edited_at
+            FROM content_edits
+            WHERE content_id = $1
+            ORDER BY edited_at, id
+            "#,
+            id
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(edits)
+    }
+
+    /// Find a community's pinned messages, most recently pinned first
+    pub async fn find_pinned(pool: &Pool<Postgres>, community_id: Uuid) -> Result<Vec<Content>, sqlx::Error> {
+        let contents = sqlx::query_as!(
+            Content,
+            r#"
+            SELECT
+                c.id as "id!", c.content as "content!", c.created_at as "created_at!",
+                c.sender_id as "sender_id!", c.sender_xid as "sender_xid!", c.image_asset_id,
+                c.community_id as "community_id!", c.wallet_address as "wallet_address!",
+                c.reply_to_id, c.edited_at, c.deleted_at, c.reply_count as "reply_count!",
+                c.reactions as "reactions!: Json<ReactionCounts>", c.previews as "previews!: Json<Vec<LinkPreview>>"
+            FROM pinned_contents pc
+            JOIN content_view c ON c.id = pc.content_id
+            WHERE pc.community_id = $1
+            ORDER BY pc.pinned_at DESC, pc.content_id DESC
+            "#,
+            community_id
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(contents)
+    }
+
+    pub async fn is_pinned(pool: &Pool<Postgres>, id: Uuid) -> Result<bool, sqlx::Error> {
+        let pinned = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM pinned_contents WHERE content_id = $1) as "pinned!""#,
+            id
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(pinned)
+    }
+
+    /// Pin a message to the top of its community.
+    /// Returns false if the community already has `max` pinned messages.
+    pub async fn pin(
+        pool: &Pool<Postgres>,
+        content: &Content,
+        pinned_by: Uuid,
+        max: i64,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        // Serialize pins per community so two at once can't both squeeze under the cap
+        sqlx::query!("SELECT id FROM communities WHERE id = $1 FOR UPDATE", content.community_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let pinned = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM pinned_contents WHERE community_id = $1"#,
+            content.community_id
+        )
+            .fetch_one(&mut *tx)
+            .await?;
+        if pinned >= max {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO pinned_contents (content_id, community_id, pinned_by, pinned_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (content_id) DO NOTHING
+            "#,
+            content.id,
+            content.community_id,
+            pinned_by,
+            now
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Take a message off the top of its community.
+    /// Returns false if it wasn't pinned.
+    pub async fn unpin(pool: &Pool<Postgres>, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM pinned_contents WHERE content_id = $1", id)
             .execute(pool)
             .await?;
 
@@ -0,0 +1,86 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::Utc;
+
+use crate::model::depositor::{CreateDepositDto, Depositor};
+use crate::model::reward::RewardShare;
+use crate::repository::RewardRepository;
+
+pub struct DepositorRepository;
+
+impl DepositorRepository {
+    /// Find deposits by community ID
+    pub async fn find_by_community_id(pool: &Pool<Postgres>, community_id: Uuid) -> Result<Vec<Depositor>, sqlx::Error> {
+        let deposits = sqlx::query_as!(
+            Depositor,
+            r#"
+            SELECT 
+                id, user_id, user_xid, community_id, amount, wallet_address, deposited_at
+            FROM depositor WHERE community_id = $1
+            ORDER BY deposited_at DESC
+            "#,
+            community_id
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(deposits)
+    }
+
+    /// Record a deposit and add it to the community's bounty, crediting the
+    /// depositor's referrer with their share of the fee if there is one
+    pub async fn create(
+        pool: &Pool<Postgres>,
+        community_id: Uuid,
+        dto: CreateDepositDto,
+        reward: Option<RewardShare>,
+    ) -> Result<Depositor, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+
+        // Default user IDs if not provided
+        let default_uuid = Uuid::parse_str("00000000-0000-0000-0000-000000000000").unwrap();
+        let user_id = dto.user_id.unwrap_or(default_uuid);
+        let user_xid = dto.user_xid.unwrap_or_else(|| "default-user".to_string());
+
+        let mut tx = pool.begin().await?;
+
+        let deposit = sqlx::query_as!(
+            Depositor,
+            r#"
+            INSERT INTO depositor (id, user_id, user_xid, community_id, amount, wallet_address, deposited_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, user_id, user_xid, community_id, amount, wallet_address, deposited_at
+            "#,
+            id,
+            user_id,
+            user_xid,
+            community_id,
+            dto.amount,
+            dto.wallet_address,
+            now
+        )
+            .fetch_one(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE communities
+            SET bounty_amount = bounty_amount + $1
+            WHERE id = $2
+            "#,
+            dto.amount,
+            community_id
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        if let Some(reward) = reward {
+            RewardRepository::accrue(&mut tx, &deposit, &reward, now).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(deposit)
+    }
+}
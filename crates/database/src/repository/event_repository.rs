@@ -0,0 +1,115 @@
+use sqlx::{postgres::PgListener, Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::event::{CommunityEvent, EventType, NewCommunityEvent};
+
+/// Advisory lock held while appending, so event ids become visible in order
+const APPEND_LOCK_KEY: i64 = 0x7075_6c73_6501;
+
+/// Channel notified with the id of every appended event
+pub const EVENTS_CHANNEL: &str = "community_events";
+
+pub struct EventRepository;
+
+impl EventRepository {
+    /// Append an event to the log and notify listeners on every instance.
+    ///
+    /// Appends are serialized with a transaction-scoped advisory lock: a
+    /// sequence alone hands out ids in call order but commits can land out of
+    /// order, and a reader that saw id N must never later find an N-1.
+    /// The notification carries only the event id and is delivered on commit.
+    pub async fn append(pool: &Pool<Postgres>, event: NewCommunityEvent) -> Result<CommunityEvent, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", APPEND_LOCK_KEY)
+            .execute(&mut *tx)
+            .await?;
+
+        let event = sqlx::query_as!(
+            CommunityEvent,
+            r#"
+            INSERT INTO community_events (community_id, event_type, payload, created_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, community_id, event_type as "event_type: EventType", payload, created_at
+            "#,
+            event.community_id,
+            event.event_type.as_str(),
+            event.payload,
+            Utc::now()
+        )
+            .fetch_one(&mut *tx)
+            .await?;
+
+        sqlx::query!("SELECT pg_notify($1, $2)", EVENTS_CHANNEL, event.id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(event)
+    }
+
+    /// Open a dedicated connection listening for appended events
+    pub async fn listen(pool: &Pool<Postgres>) -> Result<PgListener, sqlx::Error> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(EVENTS_CHANNEL).await?;
+        Ok(listener)
+    }
+
+    /// Find events after `after_id`, oldest first, optionally for one community
+    pub async fn find_after(
+        pool: &Pool<Postgres>,
+        community_id: Option<Uuid>,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<CommunityEvent>, sqlx::Error> {
+        let events = sqlx::query_as!(
+            CommunityEvent,
+            r#"
+            SELECT id, community_id, event_type as "event_type: EventType", payload, created_at
+            FROM community_events
+            WHERE id > $1 AND ($2::uuid IS NULL OR community_id = $2)
+            ORDER BY id
+            LIMIT $3
+            "#,
+            after_id,
+            community_id,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(events)
+    }
+
+    /// Id of the newest event, optionally for one community
+    pub async fn latest_id(pool: &Pool<Postgres>, community_id: Option<Uuid>) -> Result<i64, sqlx::Error> {
+        let id = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(MAX(id), 0) as "id!"
+            FROM community_events
+            WHERE ($1::uuid IS NULL OR community_id = $1)
+            "#,
+            community_id
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Delete events older than `before`; they are too old to resume from
+    pub async fn delete_older_than(pool: &Pool<Postgres>, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM community_events WHERE created_at < $1
+            "#,
+            before
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
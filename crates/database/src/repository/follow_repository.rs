@@ -0,0 +1,114 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::follow::Following;
+
+pub struct FollowRepository;
+
+impl FollowRepository {
+    /// Follow a user; returns false if already following them
+    pub async fn follow_user(pool: &Pool<Postgres>, follower_id: Uuid, followed_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO user_follows (follower_id, followed_id, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (follower_id, followed_id) DO NOTHING
+            "#,
+            follower_id,
+            followed_id,
+            now
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stop following a user; returns false if not following them
+    pub async fn unfollow_user(pool: &Pool<Postgres>, follower_id: Uuid, followed_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM user_follows WHERE follower_id = $1 AND followed_id = $2",
+            follower_id,
+            followed_id
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Follow a community; returns false if already following it
+    pub async fn follow_community(pool: &Pool<Postgres>, user_id: Uuid, community_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO community_follows (user_id, community_id, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, community_id) DO NOTHING
+            "#,
+            user_id,
+            community_id,
+            now
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stop following a community; returns false if not following it
+    pub async fn unfollow_community(pool: &Pool<Postgres>, user_id: Uuid, community_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM community_follows WHERE user_id = $1 AND community_id = $2",
+            user_id,
+            community_id
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// How many users and how many communities a user follows
+    pub async fn count(pool: &Pool<Postgres>, user_id: Uuid) -> Result<(i64, i64), sqlx::Error> {
+        let counts = sqlx::query!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM user_follows WHERE follower_id = $1) as "users!",
+                (SELECT COUNT(*) FROM community_follows WHERE user_id = $1) as "communities!"
+            "#,
+            user_id
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok((counts.users, counts.communities))
+    }
+
+    /// Everyone and everything a user follows, most recently followed first
+    pub async fn find_following(pool: &Pool<Postgres>, user_id: Uuid) -> Result<Following, sqlx::Error> {
+        let users = sqlx::query_scalar!(
+            r#"
+            SELECT followed_id FROM user_follows
+            WHERE follower_id = $1
+            ORDER BY created_at DESC, followed_id
+            "#,
+            user_id
+        )
+            .fetch_all(pool)
+            .await?;
+
+        let communities = sqlx::query_scalar!(
+            r#"
+            SELECT community_id FROM community_follows
+            WHERE user_id = $1
+            ORDER BY created_at DESC, community_id
+            "#,
+            user_id
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(Following { users, communities })
+    }
+}
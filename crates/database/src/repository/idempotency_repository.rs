@@ -0,0 +1,121 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::idempotency::{IdempotencyRecord, IdempotencyStatus, StoredResponse};
+
+pub struct IdempotencyRepository;
+
+impl IdempotencyRepository {
+    /// Take a key for a request, returning false if another request has it.
+    ///
+    /// Keys created before `expired_before` are free again, as are keys for
+    /// the same request still in progress since before `abandoned_before`.
+    pub async fn claim(
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+        key: &str,
+        fingerprint: &str,
+        now: DateTime<Utc>,
+        expired_before: DateTime<Utc>,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let claimed = sqlx::query_scalar!(
+            r#"
+            INSERT INTO idempotency_keys (user_id, key, fingerprint, status, created_at, updated_at)
+            VALUES ($1, $2, $3, 'in_progress', $4, $4)
+            ON CONFLICT (user_id, key) DO UPDATE
+                SET fingerprint = EXCLUDED.fingerprint,
+                    status = EXCLUDED.status,
+                    response_status = NULL,
+                    response_content_type = NULL,
+                    response_body = NULL,
+                    created_at = EXCLUDED.created_at,
+                    updated_at = EXCLUDED.updated_at
+                WHERE idempotency_keys.created_at < $5
+                    OR (idempotency_keys.status = 'in_progress'
+                        AND idempotency_keys.updated_at < $6
+                        AND idempotency_keys.fingerprint = EXCLUDED.fingerprint)
+            RETURNING key
+            "#,
+            user_id,
+            key,
+            fingerprint,
+            now,
+            expired_before,
+            abandoned_before
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(claimed.is_some())
+    }
+
+    pub async fn find(pool: &Pool<Postgres>, user_id: Uuid, key: &str) -> Result<Option<IdempotencyRecord>, sqlx::Error> {
+        let record = sqlx::query_as!(
+            IdempotencyRecord,
+            r#"
+            SELECT
+                fingerprint, status as "status: IdempotencyStatus",
+                response_status, response_content_type, response_body
+            FROM idempotency_keys WHERE user_id = $1 AND key = $2
+            "#,
+            user_id,
+            key
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Store the response to the request holding a key
+    pub async fn complete(
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+        key: &str,
+        response: &StoredResponse,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET status = $3, response_status = $4, response_content_type = $5, response_body = $6, updated_at = $7
+            WHERE user_id = $1 AND key = $2
+            "#,
+            user_id,
+            key,
+            IdempotencyStatus::Completed.as_str(),
+            response.status as i16,
+            response.content_type,
+            response.body,
+            now
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Give up a key without storing a response, so a retry runs again
+    pub async fn release(pool: &Pool<Postgres>, user_id: Uuid, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE user_id = $1 AND key = $2 AND status = 'in_progress'",
+            user_id,
+            key
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Forget keys created before `before`, returning how many were removed
+    pub async fn delete_expired(pool: &Pool<Postgres>, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM idempotency_keys WHERE created_at < $1", before)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
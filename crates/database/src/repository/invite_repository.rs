@@ -0,0 +1,125 @@
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::invite::{CommunityInvite, CreateInviteDto};
+
+pub struct InviteRepository;
+
+impl InviteRepository {
+    pub async fn create(
+        pool: &Pool<Postgres>,
+        code: &str,
+        community_id: Uuid,
+        created_by: Uuid,
+        dto: &CreateInviteDto,
+        now: DateTime<Utc>,
+    ) -> Result<CommunityInvite, sqlx::Error> {
+        let invite = sqlx::query_as!(
+            CommunityInvite,
+            r#"
+            INSERT INTO community_invites (code, community_id, created_by, created_at, max_uses, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING code, community_id, created_by, created_at, revoked_at, max_uses, use_count, expires_at
+            "#,
+            code,
+            community_id,
+            created_by,
+            now,
+            dto.max_uses,
+            dto.expires_at
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(invite)
+    }
+
+    pub async fn find_by_code(pool: &Pool<Postgres>, code: &str) -> Result<Option<CommunityInvite>, sqlx::Error> {
+        let invite = sqlx::query_as!(
+            CommunityInvite,
+            r#"
+            SELECT code, community_id, created_by, created_at, revoked_at, max_uses, use_count, expires_at
+            FROM community_invites WHERE code = $1
+            "#,
+            code
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(invite)
+    }
+
+    /// Find a community's invites that haven't been revoked, newest first,
+    /// optionally only those made by one user
+    pub async fn find_by_community(
+        pool: &Pool<Postgres>,
+        community_id: Uuid,
+        created_by: Option<Uuid>,
+    ) -> Result<Vec<CommunityInvite>, sqlx::Error> {
+        let invites = sqlx::query_as!(
+            CommunityInvite,
+            r#"
+            SELECT code, community_id, created_by, created_at, revoked_at, max_uses, use_count, expires_at
+            FROM community_invites
+            WHERE community_id = $1
+                AND revoked_at IS NULL
+                AND ($2::uuid IS NULL OR created_by = $2)
+            ORDER BY created_at DESC, code
+            "#,
+            community_id,
+            created_by
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(invites)
+    }
+
+    /// Stop an invite letting anyone else in.
+    /// Returns `None` if it had already been revoked.
+    pub async fn revoke(pool: &Pool<Postgres>, code: &str, now: DateTime<Utc>) -> Result<Option<CommunityInvite>, sqlx::Error> {
+        let invite = sqlx::query_as!(
+            CommunityInvite,
+            r#"
+            UPDATE community_invites
+            SET revoked_at = $2
+            WHERE code = $1 AND revoked_at IS NULL
+            RETURNING code, community_id, created_by, created_at, revoked_at, max_uses, use_count, expires_at
+            "#,
+            code,
+            now
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(invite)
+    }
+
+    /// Count a use of an invite, in the transaction that lets its user in.
+    /// Returns `None` if it has been revoked, expired or used up.
+    pub(crate) async fn redeem(
+        tx: &mut Transaction<'_, Postgres>,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<CommunityInvite>, sqlx::Error> {
+        let invite = sqlx::query_as!(
+            CommunityInvite,
+            r#"
+            UPDATE community_invites
+            SET use_count = use_count + 1
+            WHERE code = $1
+                AND revoked_at IS NULL
+                AND (max_uses IS NULL OR use_count < max_uses)
+                AND (expires_at IS NULL OR expires_at > $2)
+            RETURNING code, community_id, created_by, created_at, revoked_at, max_uses, use_count, expires_at
+            "#,
+            code,
+            now
+        )
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(invite)
+    }
+}
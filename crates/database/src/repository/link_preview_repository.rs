@@ -0,0 +1,119 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::link_preview::LinkPreview;
+
+pub struct LinkPreviewRepository;
+
+impl LinkPreviewRepository {
+    /// Record the links in a message, replacing any it had before an edit,
+    /// and queue a fetch for each link without a fresh preview.
+    ///
+    /// Previews fetched before `stale_before` are fetched again.
+    pub async fn link_content(
+        pool: &Pool<Postgres>,
+        content_id: Uuid,
+        urls: &[String],
+        now: DateTime<Utc>,
+        stale_before: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO link_previews (url, status, updated_at)
+            SELECT url, 'pending', $2 FROM unnest($1::varchar[]) AS url
+            ON CONFLICT (url) DO UPDATE
+                SET status = 'pending', updated_at = EXCLUDED.updated_at
+                WHERE link_previews.status IN ('ready', 'failed')
+                    AND link_previews.fetched_at < $3
+            "#,
+            urls,
+            now,
+            stale_before
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM content_links WHERE content_id = $1", content_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO content_links (content_id, url, position)
+            SELECT $1, link.url, link.position::smallint
+            FROM unnest($2::varchar[]) WITH ORDINALITY AS link(url, position)
+            "#,
+            content_id,
+            urls
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Claim up to `limit` links to fetch, oldest first. Claims older than
+    /// `abandoned_before` belong to an unfurler that died and are taken over.
+    pub async fn claim_pending(
+        pool: &Pool<Postgres>,
+        now: DateTime<Utc>,
+        abandoned_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let urls = sqlx::query_scalar!(
+            r#"
+            UPDATE link_previews
+            SET status = 'fetching', updated_at = $1
+            WHERE url IN (
+                SELECT url FROM link_previews
+                WHERE status = 'pending'
+                    OR (status = 'fetching' AND updated_at < $2)
+                ORDER BY updated_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING url
+            "#,
+            now,
+            abandoned_before,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(urls)
+    }
+
+    /// Store a fetched preview, or mark the link as having none
+    pub async fn complete(
+        pool: &Pool<Postgres>,
+        url: &str,
+        preview: Option<&LinkPreview>,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE link_previews
+            SET status = CASE WHEN $2 THEN 'ready' ELSE 'failed' END,
+                title = $3, description = $4, image_url = $5, site_name = $6,
+                updated_at = $7, fetched_at = $7
+            WHERE url = $1
+            "#,
+            url,
+            preview.is_some(),
+            preview.and_then(|preview| preview.title.as_deref()),
+            preview.and_then(|preview| preview.description.as_deref()),
+            preview.and_then(|preview| preview.image_url.as_deref()),
+            preview.and_then(|preview| preview.site_name.as_deref()),
+            now
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
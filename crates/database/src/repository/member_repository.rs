@@ -0,0 +1,266 @@
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::member::{CommunityMember, MemberStatus};
+use crate::model::pagination::{Cursor, Page};
+use crate::repository::{InviteRepository, ReferralRepository};
+
+pub struct MemberRepository;
+
+impl MemberRepository {
+    pub async fn find(pool: &Pool<Postgres>, community_id: Uuid, user_id: Uuid) -> Result<Option<CommunityMember>, sqlx::Error> {
+        let member = sqlx::query_as!(
+            CommunityMember,
+            r#"
+            SELECT community_id, user_id, status as "status: MemberStatus", created_at, joined_at
+            FROM community_members
+            WHERE community_id = $1 AND user_id = $2
+            "#,
+            community_id,
+            user_id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(member)
+    }
+
+    pub async fn is_active_member(pool: &Pool<Postgres>, community_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        let is_member = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM community_members
+                WHERE community_id = $1 AND user_id = $2 AND status = 'active'
+            ) as "is_member!"
+            "#,
+            community_id,
+            user_id
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(is_member)
+    }
+
+    /// Whether a user, or an anonymous caller as `None`, can read a
+    /// community's messages: anyone can read a public community, only its
+    /// active members a private one. False if there is no such community.
+    pub async fn can_read(pool: &Pool<Postgres>, community_id: Uuid, user_id: Option<Uuid>) -> Result<bool, sqlx::Error> {
+        let can_read = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM communities c
+                WHERE c.id = $1
+                    AND (
+                        c.visibility = 'public'
+                        OR EXISTS (
+                            SELECT 1 FROM community_members m
+                            WHERE m.community_id = c.id AND m.user_id = $2 AND m.status = 'active'
+                        )
+                    )
+            ) as "can_read!"
+            "#,
+            community_id,
+            user_id
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(can_read)
+    }
+
+    /// Those of `user_ids` who can read a community's messages
+    pub async fn find_readers(pool: &Pool<Postgres>, community_id: Uuid, user_ids: &[Uuid]) -> Result<Vec<Uuid>, sqlx::Error> {
+        let readers = sqlx::query_scalar!(
+            r#"
+            SELECT u.id as "id!"
+            FROM UNNEST($2::uuid[]) AS u(id)
+            JOIN communities c ON c.id = $1
+            WHERE c.visibility = 'public'
+                OR EXISTS (
+                    SELECT 1 FROM community_members m
+                    WHERE m.community_id = c.id AND m.user_id = u.id AND m.status = 'active'
+                )
+            "#,
+            community_id,
+            user_ids
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(readers)
+    }
+
+    /// Add a user to a community, or record their request to join. An
+    /// invite code is redeemed and its maker credited with the referral.
+    /// Returns `None` if they already had a row or the invite can no longer be used.
+    pub async fn add(
+        pool: &Pool<Postgres>,
+        community_id: Uuid,
+        user_id: Uuid,
+        status: MemberStatus,
+        invite_code: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<CommunityMember>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let member = sqlx::query_as!(
+            CommunityMember,
+            r#"
+            INSERT INTO community_members (community_id, user_id, status, created_at, joined_at, invite_code)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (community_id, user_id) DO NOTHING
+            RETURNING community_id, user_id, status as "status: MemberStatus", created_at, joined_at
+            "#,
+            community_id,
+            user_id,
+            status.as_str(),
+            now,
+            (status == MemberStatus::Active).then_some(now),
+            invite_code
+        )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(member) = member else {
+            return Ok(None);
+        };
+        if !Self::redeem(&mut tx, user_id, invite_code, now).await? {
+            return Ok(None);
+        }
+        if member.status == MemberStatus::Active {
+            Self::adjust_count(&mut tx, community_id, 1).await?;
+        }
+
+        tx.commit().await?;
+        Ok(Some(member))
+    }
+
+    /// Let a pending user in, redeeming the invite code they used if any.
+    /// Returns `None` if they had no pending request or the invite can no longer be used.
+    pub async fn activate(
+        pool: &Pool<Postgres>,
+        community_id: Uuid,
+        user_id: Uuid,
+        invite_code: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<CommunityMember>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let member = sqlx::query_as!(
+            CommunityMember,
+            r#"
+            UPDATE community_members
+            SET status = 'active', joined_at = $3, invite_code = COALESCE($4, invite_code)
+            WHERE community_id = $1 AND user_id = $2 AND status = 'pending'
+            RETURNING community_id, user_id, status as "status: MemberStatus", created_at, joined_at
+            "#,
+            community_id,
+            user_id,
+            now,
+            invite_code
+        )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if member.is_none() || !Self::redeem(&mut tx, user_id, invite_code, now).await? {
+            return Ok(None);
+        }
+        Self::adjust_count(&mut tx, community_id, 1).await?;
+
+        tx.commit().await?;
+        Ok(member)
+    }
+
+    /// Take a user out of a community, or drop their request to join.
+    /// Returns the status they had, or `None` if they had none.
+    pub async fn remove(pool: &Pool<Postgres>, community_id: Uuid, user_id: Uuid) -> Result<Option<MemberStatus>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let status = sqlx::query_scalar!(
+            r#"
+            DELETE FROM community_members
+            WHERE community_id = $1 AND user_id = $2
+            RETURNING status as "status: MemberStatus"
+            "#,
+            community_id,
+            user_id
+        )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if status == Some(MemberStatus::Active) {
+            Self::adjust_count(&mut tx, community_id, -1).await?;
+        }
+
+        tx.commit().await?;
+        Ok(status)
+    }
+
+    /// Find a page of a community's members with the given status, in the
+    /// order they joined or asked to.
+    ///
+    /// `after` is the position of the last member on the previous page.
+    pub async fn find_page(
+        pool: &Pool<Postgres>,
+        community_id: Uuid,
+        status: MemberStatus,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Page<CommunityMember>, sqlx::Error> {
+        let members = sqlx::query_as!(
+            CommunityMember,
+            r#"
+            SELECT community_id, user_id, status as "status: MemberStatus", created_at, joined_at
+            FROM community_members
+            WHERE community_id = $1
+                AND status = $2
+                AND ($3::timestamptz IS NULL OR (created_at, user_id) > ($3, $4::uuid))
+            ORDER BY created_at, user_id
+            LIMIT $5
+            "#,
+            community_id,
+            status.as_str(),
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(Page::from_rows(members, limit, |member| Cursor::new(member.created_at, member.user_id)))
+    }
+
+    /// Use up one go of an invite and credit its maker. Returns false if it
+    /// can no longer be used; nothing to redeem counts as success.
+    async fn redeem(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        invite_code: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let Some(code) = invite_code else {
+            return Ok(true);
+        };
+        let Some(invite) = InviteRepository::redeem(tx, code, now).await? else {
+            return Ok(false);
+        };
+
+        ReferralRepository::record(tx, &invite, user_id, now).await?;
+        Ok(true)
+    }
+
+    /// Keep `communities.member_count` in step with the active members
+    async fn adjust_count(tx: &mut Transaction<'_, Postgres>, community_id: Uuid, delta: i32) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE communities SET member_count = member_count + $2 WHERE id = $1",
+            community_id,
+            delta
+        )
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+}
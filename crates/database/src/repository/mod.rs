@@ -1,9 +1,45 @@
 // Re-export repositories
 pub mod user_repository;
+pub mod asset_repository;
 pub mod community_repository;
 pub mod content_repository;
+pub mod depositor_repository;
+pub mod event_repository;
+pub mod follow_repository;
+pub mod idempotency_repository;
+pub mod invite_repository;
+pub mod link_preview_repository;
+pub mod member_repository;
+pub mod moderation_repository;
+pub mod notification_repository;
+pub mod payout_repository;
+pub mod presence_repository;
+pub mod rate_limit_repository;
+pub mod reaction_repository;
+pub mod referral_repository;
+pub mod reward_repository;
+pub mod sanction_repository;
+pub mod search_repository;
 
 pub use user_repository::UserRepository;
+pub use asset_repository::AssetRepository;
 pub use community_repository::CommunityRepository;
 pub use content_repository::ContentRepository;
+pub use depositor_repository::DepositorRepository;
+pub use event_repository::EventRepository;
+pub use follow_repository::FollowRepository;
+pub use idempotency_repository::IdempotencyRepository;
+pub use invite_repository::InviteRepository;
+pub use link_preview_repository::LinkPreviewRepository;
+pub use member_repository::MemberRepository;
+pub use moderation_repository::ModerationRepository;
+pub use notification_repository::NotificationRepository;
+pub use payout_repository::PayoutRepository;
+pub use presence_repository::PresenceRepository;
+pub use rate_limit_repository::RateLimitRepository;
+pub use reaction_repository::ReactionRepository;
+pub use referral_repository::ReferralRepository;
+pub use reward_repository::RewardRepository;
+pub use sanction_repository::SanctionRepository;
+pub use search_repository::SearchRepository;
 
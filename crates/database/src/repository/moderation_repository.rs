@@ -0,0 +1,174 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::content::CreateContentDto;
+use crate::model::moderation::{CommunityModeration, HeldContent, HeldContentStatus};
+use crate::model::pagination::{Cursor, Page};
+
+pub struct ModerationRepository;
+
+impl ModerationRepository {
+    /// Find a community's moderation rules, if it has any
+    pub async fn find_rules(pool: &Pool<Postgres>, community_id: Uuid) -> Result<Option<CommunityModeration>, sqlx::Error> {
+        let rules = sqlx::query_as!(
+            CommunityModeration,
+            r#"
+            SELECT blocked_words, held_words, allowed_domains
+            FROM community_moderation WHERE community_id = $1
+            "#,
+            community_id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(rules)
+    }
+
+    /// Replace a community's moderation rules
+    pub async fn save_rules(pool: &Pool<Postgres>, community_id: Uuid, rules: &CommunityModeration) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO community_moderation (community_id, blocked_words, held_words, allowed_domains, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (community_id) DO UPDATE
+                SET blocked_words = EXCLUDED.blocked_words,
+                    held_words = EXCLUDED.held_words,
+                    allowed_domains = EXCLUDED.allowed_domains,
+                    updated_at = EXCLUDED.updated_at
+            "#,
+            community_id,
+            &rules.blocked_words,
+            &rules.held_words,
+            &rules.allowed_domains,
+            Utc::now()
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Hold a message for review instead of posting it.
+    /// The sender has to be set on `dto`.
+    pub async fn hold(pool: &Pool<Postgres>, dto: &CreateContentDto, reason: &str) -> Result<HeldContent, sqlx::Error> {
+        let held = sqlx::query_as!(
+            HeldContent,
+            r#"
+            INSERT INTO held_contents (
+                id, community_id, sender_id, sender_xid, content, image_asset_id,
+                reply_to_id, wallet_address, reason, status, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING
+                id, community_id, sender_id, sender_xid, content, image_asset_id,
+                reply_to_id, wallet_address, reason, status as "status: HeldContentStatus",
+                created_at, reviewed_by, reviewed_at, content_id
+            "#,
+            Uuid::new_v4(),
+            dto.community_id,
+            dto.sender_id.unwrap_or_default(),
+            dto.sender_xid.clone().unwrap_or_default(),
+            dto.content,
+            dto.image_asset_id,
+            dto.reply_to_id,
+            dto.wallet_address.clone().unwrap_or_default(),
+            reason,
+            HeldContentStatus::Pending.as_str(),
+            Utc::now()
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(held)
+    }
+
+    pub async fn find_held(pool: &Pool<Postgres>, id: Uuid) -> Result<Option<HeldContent>, sqlx::Error> {
+        let held = sqlx::query_as!(
+            HeldContent,
+            r#"
+            SELECT
+                id, community_id, sender_id, sender_xid, content, image_asset_id,
+                reply_to_id, wallet_address, reason, status as "status: HeldContentStatus",
+                created_at, reviewed_by, reviewed_at, content_id
+            FROM held_contents WHERE id = $1
+            "#,
+            id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(held)
+    }
+
+    /// Find a page of a community's messages waiting for review, oldest first.
+    ///
+    /// `after` is the position of the last message on the previous page.
+    pub async fn find_queue(pool: &Pool<Postgres>, community_id: Uuid, after: Option<Cursor>, limit: i64) -> Result<Page<HeldContent>, sqlx::Error> {
+        let held = sqlx::query_as!(
+            HeldContent,
+            r#"
+            SELECT
+                id, community_id, sender_id, sender_xid, content, image_asset_id,
+                reply_to_id, wallet_address, reason, status as "status: HeldContentStatus",
+                created_at, reviewed_by, reviewed_at, content_id
+            FROM held_contents
+            WHERE community_id = $1
+                AND status = 'pending'
+                AND ($2::timestamptz IS NULL OR (created_at, id) > ($2, $3::uuid))
+            ORDER BY created_at, id
+            LIMIT $4
+            "#,
+            community_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(Page::from_rows(held, limit, |held| Cursor::new(held.created_at, held.id)))
+    }
+
+    /// Record a moderator's decision on a pending message.
+    /// Returns false if it had already been reviewed.
+    pub async fn review(
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        status: HeldContentStatus,
+        reviewer_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE held_contents
+            SET status = $2, reviewed_by = $3, reviewed_at = $4
+            WHERE id = $1 AND status = 'pending'
+            "#,
+            id,
+            status.as_str(),
+            reviewer_id,
+            now
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put an approved message back in the queue, for when posting it failed
+    pub async fn reopen(pool: &Pool<Postgres>, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE held_contents
+            SET status = 'pending', reviewed_by = NULL, reviewed_at = NULL
+            WHERE id = $1 AND status = 'approved' AND content_id IS NULL
+            "#,
+            id
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::Utc;
+
+use crate::model::notification::{NewNotification, Notification, NotificationKind};
+use crate::model::pagination::{Cursor, Page};
+
+pub struct NotificationRepository;
+
+impl NotificationRepository {
+    /// Store a batch of notifications in one transaction
+    pub async fn create_many(pool: &Pool<Postgres>, notifications: &[NewNotification]) -> Result<(), sqlx::Error> {
+        if notifications.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let mut tx = pool.begin().await?;
+
+        for notification in notifications {
+            sqlx::query!(
+                r#"
+                INSERT INTO notifications (id, user_id, kind, community_id, content_id, actor_id, payload, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+                Uuid::new_v4(),
+                notification.user_id,
+                notification.kind.as_str(),
+                notification.community_id,
+                notification.content_id,
+                notification.actor_id,
+                notification.payload,
+                now
+            )
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Find a page of a user's notifications, newest first.
+    ///
+    /// `after` is the position of the last notification on the previous page.
+    pub async fn find_page(
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+        unread_only: bool,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Page<Notification>, sqlx::Error> {
+        let notifications = sqlx::query_as!(
+            Notification,
+            r#"
+            SELECT
+                id, user_id, kind as "kind: NotificationKind", community_id, content_id, actor_id,
+                payload, created_at, read_at
+            FROM notifications
+            WHERE user_id = $1
+                AND (NOT $2 OR read_at IS NULL)
+                AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4::uuid))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $5
+            "#,
+            user_id,
+            unread_only,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(Page::from_rows(notifications, limit, |notification| {
+            Cursor::new(notification.created_at, notification.id)
+        }))
+    }
+
+    pub async fn count_unread(pool: &Pool<Postgres>, user_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM notifications WHERE user_id = $1 AND read_at IS NULL"#,
+            user_id
+        )
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Mark one of a user's notifications as read; false if the user has no such notification
+    pub async fn mark_read(pool: &Pool<Postgres>, user_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE notifications
+            SET read_at = COALESCE(read_at, $1)
+            WHERE id = $2 AND user_id = $3
+            "#,
+            Utc::now(),
+            id,
+            user_id
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark everything in a user's inbox as read and return how many were unread
+    pub async fn mark_all_read(pool: &Pool<Postgres>, user_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE notifications SET read_at = $1 WHERE user_id = $2 AND read_at IS NULL",
+            Utc::now(),
+            user_id
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,118 @@
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::payout::{NewPayout, Payout, PayoutKind, PayoutStatus};
+
+pub struct PayoutRepository;
+
+impl PayoutRepository {
+    /// Record a payout to the wallet on the user's account, for the payout
+    /// processor to send
+    pub(crate) async fn create(
+        tx: &mut Transaction<'_, Postgres>,
+        payout: &NewPayout,
+        now: DateTime<Utc>,
+    ) -> Result<Payout, sqlx::Error> {
+        let payout = sqlx::query_as!(
+            Payout,
+            r#"
+            INSERT INTO payouts (id, user_id, kind, amount, wallet_address, community_id, content_id, created_at, next_attempt_at)
+            SELECT $1, u.id, $3, $4, NULLIF(u.wallet_address, ''), $5, $6, $7, $7
+            FROM users u
+            WHERE u.id = $2
+            RETURNING
+                id, user_id, kind as "kind: PayoutKind", amount, wallet_address, community_id, content_id,
+                status as "status: PayoutStatus", created_at, sent_at
+            "#,
+            payout.id,
+            payout.user_id,
+            payout.kind.as_str(),
+            payout.amount,
+            payout.community_id,
+            payout.content_id,
+            now
+        )
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(payout)
+    }
+
+    /// Take payouts that are due to be sent, including ones whose last
+    /// attempt was abandoned, so no other processor picks them up before
+    /// `lease_until`. A payout recorded before its user had a wallet picks up
+    /// the one on their account; until there is one it waits.
+    pub async fn claim_due(
+        pool: &Pool<Postgres>,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Payout>, sqlx::Error> {
+        let payouts = sqlx::query_as!(
+            Payout,
+            r#"
+            UPDATE payouts p
+            SET status = 'processing', attempts = p.attempts + 1, next_attempt_at = $2,
+                wallet_address = COALESCE(p.wallet_address, NULLIF(u.wallet_address, ''))
+            FROM users u
+            WHERE u.id = p.user_id AND p.id IN (
+                SELECT d.id FROM payouts d
+                JOIN users du ON du.id = d.user_id
+                WHERE d.status IN ('pending', 'processing') AND d.next_attempt_at <= $1
+                    AND (d.wallet_address IS NOT NULL OR du.wallet_address <> '')
+                ORDER BY d.next_attempt_at
+                LIMIT $3
+                FOR UPDATE OF d SKIP LOCKED
+            )
+            RETURNING
+                p.id, p.user_id, p.kind as "kind: PayoutKind", p.amount, p.wallet_address, p.community_id, p.content_id,
+                p.status as "status: PayoutStatus", p.created_at, p.sent_at
+            "#,
+            now,
+            lease_until,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(payouts)
+    }
+
+    /// Record that the payment service accepted a payout
+    pub async fn mark_sent(pool: &Pool<Postgres>, id: Uuid, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE payouts
+            SET status = 'sent', sent_at = $2
+            WHERE id = $1 AND status = 'processing'
+            "#,
+            id,
+            now
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Put a payout back to be tried again at `retry_at`, or give up on it
+    /// once it has had `max_attempts`
+    pub async fn release(pool: &Pool<Postgres>, id: Uuid, retry_at: DateTime<Utc>, max_attempts: i32) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE payouts
+            SET status = CASE WHEN attempts >= $3 THEN 'failed' ELSE 'pending' END,
+                next_attempt_at = $2
+            WHERE id = $1 AND status = 'processing'
+            "#,
+            id,
+            retry_at,
+            max_attempts
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::presence::{OnlineUser, PresenceCounts};
+
+pub struct PresenceRepository;
+
+impl PresenceRepository {
+    /// Record a new connection to a community
+    pub async fn join(
+        pool: &Pool<Postgres>,
+        connection_id: Uuid,
+        community_id: Uuid,
+        user_id: Option<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO community_presence (connection_id, community_id, user_id, connected_at, last_seen_at)
+            VALUES ($1, $2, $3, $4, $4)
+            "#,
+            connection_id,
+            community_id,
+            user_id,
+            now
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove a connection and return the community it was watching, or
+    /// `None` if it was already swept away
+    pub async fn leave(pool: &Pool<Postgres>, connection_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        let community_id = sqlx::query_scalar!(
+            "DELETE FROM community_presence WHERE connection_id = $1 RETURNING community_id",
+            connection_id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(community_id)
+    }
+
+    /// Mark a connection as still open; false if it has already been swept away
+    pub async fn touch(pool: &Pool<Postgres>, connection_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE community_presence SET last_seen_at = $1 WHERE connection_id = $2",
+            now,
+            connection_id
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count the open connections and online users of a community
+    pub async fn counts(pool: &Pool<Postgres>, community_id: Uuid, cutoff: DateTime<Utc>) -> Result<PresenceCounts, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) as "viewers!",
+                COUNT(DISTINCT user_id) as "online_users!"
+            FROM community_presence
+            WHERE community_id = $1 AND last_seen_at > $2
+            "#,
+            community_id,
+            cutoff
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(PresenceCounts {
+            viewers: row.viewers,
+            online_users: row.online_users,
+        })
+    }
+
+    /// Find the signed-in users watching a community, longest online first
+    pub async fn find_online_users(pool: &Pool<Postgres>, community_id: Uuid, cutoff: DateTime<Utc>) -> Result<Vec<OnlineUser>, sqlx::Error> {
+        let users = sqlx::query_as!(
+            OnlineUser,
+            r#"
+            SELECT u.id as user_id, u.xid as user_xid, u.username, MIN(p.connected_at) as "online_since!"
+            FROM community_presence p
+            JOIN users u ON u.id = p.user_id
+            WHERE p.community_id = $1 AND p.last_seen_at > $2
+            GROUP BY u.id, u.xid, u.username
+            ORDER BY MIN(p.connected_at), u.id
+            "#,
+            community_id,
+            cutoff
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(users)
+    }
+
+    /// Delete connections not seen since `cutoff` and return the community
+    /// each was watching
+    pub async fn delete_stale(pool: &Pool<Postgres>, cutoff: DateTime<Utc>) -> Result<Vec<Uuid>, sqlx::Error> {
+        let community_ids = sqlx::query_scalar!(
+            "DELETE FROM community_presence WHERE last_seen_at <= $1 RETURNING community_id",
+            cutoff
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(community_ids)
+    }
+}
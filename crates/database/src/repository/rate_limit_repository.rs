@@ -0,0 +1,53 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::Utc;
+
+use crate::model::rate_limit::CommunityRateLimits;
+
+pub struct RateLimitRepository;
+
+impl RateLimitRepository {
+    /// Find a community's posting limits, if it has set any
+    pub async fn find_by_community(pool: &Pool<Postgres>, community_id: Uuid) -> Result<Option<CommunityRateLimits>, sqlx::Error> {
+        let limits = sqlx::query_as!(
+            CommunityRateLimits,
+            r#"
+            SELECT posts_per_minute, post_burst, member_posts_per_minute, member_post_burst
+            FROM community_rate_limits WHERE community_id = $1
+            "#,
+            community_id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(limits)
+    }
+
+    /// Replace a community's posting limits
+    pub async fn save(pool: &Pool<Postgres>, community_id: Uuid, limits: &CommunityRateLimits) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO community_rate_limits (
+                community_id, posts_per_minute, post_burst, member_posts_per_minute, member_post_burst, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (community_id) DO UPDATE
+                SET posts_per_minute = EXCLUDED.posts_per_minute,
+                    post_burst = EXCLUDED.post_burst,
+                    member_posts_per_minute = EXCLUDED.member_posts_per_minute,
+                    member_post_burst = EXCLUDED.member_post_burst,
+                    updated_at = EXCLUDED.updated_at
+            "#,
+            community_id,
+            limits.posts_per_minute,
+            limits.post_burst,
+            limits.member_posts_per_minute,
+            limits.member_post_burst,
+            Utc::now()
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::Utc;
+
+use crate::model::reaction::ReactionCounts;
+
+pub struct ReactionRepository;
+
+impl ReactionRepository {
+    /// Set a user's reaction to a message, replacing any earlier one.
+    /// Returns false if the user had already reacted the same way.
+    pub async fn upsert(pool: &Pool<Postgres>, content_id: Uuid, user_id: Uuid, reaction: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO content_reactions (content_id, user_id, reaction, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (content_id, user_id) DO UPDATE
+                SET reaction = EXCLUDED.reaction, created_at = EXCLUDED.created_at
+                WHERE content_reactions.reaction <> EXCLUDED.reaction
+            "#,
+            content_id,
+            user_id,
+            reaction,
+            Utc::now()
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove a user's reaction; false if there was none
+    pub async fn delete(pool: &Pool<Postgres>, content_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM content_reactions WHERE content_id = $1 AND user_id = $2",
+            content_id,
+            user_id
+        )
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count the reactions on a message by kind
+    pub async fn counts(pool: &Pool<Postgres>, content_id: Uuid) -> Result<ReactionCounts, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT reaction, COUNT(*) as "count!"
+            FROM content_reactions
+            WHERE content_id = $1
+            GROUP BY reaction
+            "#,
+            content_id
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| (row.reaction, row.count)).collect())
+    }
+}
@@ -0,0 +1,83 @@
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::invite::CommunityInvite;
+use crate::model::referral::ReferralStats;
+
+pub struct ReferralRepository;
+
+impl ReferralRepository {
+    /// Credit an invite's maker with a user joining through it, unless the
+    /// user was already credited to someone or made the invite themselves
+    pub(crate) async fn record(
+        tx: &mut Transaction<'_, Postgres>,
+        invite: &CommunityInvite,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        if invite.created_by == user_id {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO community_referrals (community_id, user_id, invite_code, referrer_id, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (community_id, user_id) DO NOTHING
+            "#,
+            invite.community_id,
+            user_id,
+            invite.code,
+            invite.created_by,
+            now
+        )
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Who brought a user into a community, if anyone
+    pub async fn find_referrer(pool: &Pool<Postgres>, community_id: Uuid, user_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        let referrer_id = sqlx::query_scalar!(
+            "SELECT referrer_id FROM community_referrals WHERE community_id = $1 AND user_id = $2",
+            community_id,
+            user_id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(referrer_id)
+    }
+
+    /// Referral counts per referrer and community, most joins first,
+    /// optionally limited to one community or one referrer
+    pub async fn find_stats(
+        pool: &Pool<Postgres>,
+        community_id: Option<Uuid>,
+        referrer_id: Option<Uuid>,
+    ) -> Result<Vec<ReferralStats>, sqlx::Error> {
+        let stats = sqlx::query_as!(
+            ReferralStats,
+            r#"
+            SELECT
+                r.community_id, r.referrer_id,
+                COUNT(*) as "joins!",
+                COUNT(m.user_id) FILTER (WHERE m.status = 'active') as "active_members!"
+            FROM community_referrals r
+            LEFT JOIN community_members m ON m.community_id = r.community_id AND m.user_id = r.user_id
+            WHERE ($1::uuid IS NULL OR r.community_id = $1)
+                AND ($2::uuid IS NULL OR r.referrer_id = $2)
+            GROUP BY r.community_id, r.referrer_id
+            ORDER BY COUNT(*) DESC, r.community_id, r.referrer_id
+            "#,
+            community_id,
+            referrer_id
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,113 @@
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::depositor::Depositor;
+use crate::model::payout::{NewPayout, Payout};
+use crate::model::reward::{ReferralReward, RewardShare};
+use crate::repository::PayoutRepository;
+
+pub struct RewardRepository;
+
+impl RewardRepository {
+    /// Credit a referrer with their share of the fee on a deposit
+    pub(crate) async fn accrue(
+        tx: &mut Transaction<'_, Postgres>,
+        deposit: &Depositor,
+        share: &RewardShare,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO referral_rewards (id, user_id, community_id, deposit_id, referred_user_id, platform_fee, amount, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            Uuid::new_v4(),
+            share.referrer_id,
+            deposit.community_id,
+            deposit.id,
+            deposit.user_id,
+            share.platform_fee,
+            share.amount,
+            now
+        )
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Everything a user has earned, and how much of it has been paid out.
+    /// Rewards on a failed payout count as unclaimed again.
+    pub async fn find_totals(pool: &Pool<Postgres>, user_id: Uuid) -> Result<(Decimal, Decimal), sqlx::Error> {
+        let totals = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(r.amount), 0) as "accrued!",
+                COALESCE(SUM(r.amount) FILTER (WHERE p.status <> 'failed'), 0) as "claimed!"
+            FROM referral_rewards r
+            LEFT JOIN payouts p ON p.id = r.payout_id
+            WHERE r.user_id = $1
+            "#,
+            user_id
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok((totals.accrued, totals.claimed))
+    }
+
+    /// A user's latest rewards, newest first
+    pub async fn find_recent(pool: &Pool<Postgres>, user_id: Uuid, limit: i64) -> Result<Vec<ReferralReward>, sqlx::Error> {
+        let rewards = sqlx::query_as!(
+            ReferralReward,
+            r#"
+            SELECT id, user_id, community_id, deposit_id, referred_user_id, platform_fee, amount, created_at, payout_id
+            FROM referral_rewards
+            WHERE user_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+            user_id,
+            limit
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rewards)
+    }
+
+    /// Pay out every unclaimed reward a user has as one payout, or `None` if
+    /// there is nothing to claim. Rewards whose payout failed are claimed again.
+    pub async fn claim(pool: &Pool<Postgres>, user_id: Uuid, now: DateTime<Utc>) -> Result<Option<Payout>, sqlx::Error> {
+        let payout_id = Uuid::new_v4();
+        let mut tx = pool.begin().await?;
+
+        // Marking the rewards first means a concurrent claim can't take them too
+        let amounts = sqlx::query_scalar!(
+            r#"
+            UPDATE referral_rewards
+            SET payout_id = $1
+            WHERE user_id = $2
+                AND (payout_id IS NULL OR payout_id IN (SELECT id FROM payouts WHERE status = 'failed'))
+            RETURNING amount
+            "#,
+            payout_id,
+            user_id
+        )
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let amount: Decimal = amounts.into_iter().sum();
+        if amount <= Decimal::ZERO {
+            return Ok(None);
+        }
+
+        let payout = NewPayout { id: payout_id, ..NewPayout::referral_reward(user_id, amount) };
+        let payout = PayoutRepository::create(&mut tx, &payout, now).await?;
+        tx.commit().await?;
+
+        Ok(Some(payout))
+    }
+}
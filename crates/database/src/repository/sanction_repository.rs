@@ -0,0 +1,228 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::model::pagination::{Cursor, Page};
+use crate::model::sanction::{AuditAction, AuditEntry, CreateSanctionDto, Sanction, SanctionKind};
+
+pub struct SanctionRepository;
+
+impl SanctionRepository {
+    pub async fn is_platform_admin(pool: &Pool<Postgres>, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        let is_admin = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM platform_admins WHERE user_id = $1) as "is_admin!""#,
+            user_id
+        )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(is_admin)
+    }
+
+    /// Issue a sanction and record who issued it
+    pub async fn create(
+        pool: &Pool<Postgres>,
+        community_id: Option<Uuid>,
+        dto: &CreateSanctionDto,
+        issued_by: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Sanction, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let sanction = sqlx::query_as!(
+            Sanction,
+            r#"
+            INSERT INTO sanctions (id, user_id, community_id, kind, reason, issued_by, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING
+                id, user_id, community_id, kind as "kind: SanctionKind", reason,
+                issued_by, created_at, expires_at, revoked_at, revoked_by
+            "#,
+            Uuid::new_v4(),
+            dto.user_id,
+            community_id,
+            dto.kind.as_str(),
+            dto.reason,
+            issued_by,
+            now,
+            dto.expires_at
+        )
+            .fetch_one(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO moderation_audit_log (id, community_id, actor_id, action, target_user_id, sanction_id, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            Uuid::new_v4(),
+            community_id,
+            issued_by,
+            AuditAction::issued(dto.kind).as_str(),
+            dto.user_id,
+            sanction.id,
+            dto.reason,
+            now
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(sanction)
+    }
+
+    pub async fn find_by_id(pool: &Pool<Postgres>, id: Uuid) -> Result<Option<Sanction>, sqlx::Error> {
+        let sanction = sqlx::query_as!(
+            Sanction,
+            r#"
+            SELECT
+                id, user_id, community_id, kind as "kind: SanctionKind", reason,
+                issued_by, created_at, expires_at, revoked_at, revoked_by
+            FROM sanctions WHERE id = $1
+            "#,
+            id
+        )
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(sanction)
+    }
+
+    /// Sanctions in force on a user in a community, platform-wide ones included
+    pub async fn find_active_for(
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+        community_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Sanction>, sqlx::Error> {
+        let sanctions = sqlx::query_as!(
+            Sanction,
+            r#"
+            SELECT
+                id, user_id, community_id, kind as "kind: SanctionKind", reason,
+                issued_by, created_at, expires_at, revoked_at, revoked_by
+            FROM sanctions
+            WHERE user_id = $1
+                AND (community_id IS NULL OR community_id = $2)
+                AND revoked_at IS NULL
+                AND (expires_at IS NULL OR expires_at > $3)
+            "#,
+            user_id,
+            community_id,
+            now
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(sanctions)
+    }
+
+    /// Find a page of a community's sanctions, or the platform's for `None`, newest first
+    pub async fn find_page(
+        pool: &Pool<Postgres>,
+        community_id: Option<Uuid>,
+        include_inactive: bool,
+        now: DateTime<Utc>,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Page<Sanction>, sqlx::Error> {
+        let sanctions = sqlx::query_as!(
+            Sanction,
+            r#"
+            SELECT
+                id, user_id, community_id, kind as "kind: SanctionKind", reason,
+                issued_by, created_at, expires_at, revoked_at, revoked_by
+            FROM sanctions
+            WHERE community_id IS NOT DISTINCT FROM $1
+                AND ($2 OR (revoked_at IS NULL AND (expires_at IS NULL OR expires_at > $3)))
+                AND ($4::timestamptz IS NULL OR (created_at, id) < ($4, $5::uuid))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $6
+            "#,
+            community_id,
+            include_inactive,
+            now,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(Page::from_rows(sanctions, limit, |sanction| Cursor::new(sanction.created_at, sanction.id)))
+    }
+
+    /// Lift a sanction and record who lifted it.
+    /// Returns `None` if it had already been revoked.
+    pub async fn revoke(pool: &Pool<Postgres>, id: Uuid, revoked_by: Uuid, now: DateTime<Utc>) -> Result<Option<Sanction>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let sanction = sqlx::query_as!(
+            Sanction,
+            r#"
+            UPDATE sanctions
+            SET revoked_at = $3, revoked_by = $2
+            WHERE id = $1 AND revoked_at IS NULL
+            RETURNING
+                id, user_id, community_id, kind as "kind: SanctionKind", reason,
+                issued_by, created_at, expires_at, revoked_at, revoked_by
+            "#,
+            id,
+            revoked_by,
+            now
+        )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if let Some(sanction) = &sanction {
+            sqlx::query!(
+                r#"
+                INSERT INTO moderation_audit_log (id, community_id, actor_id, action, target_user_id, sanction_id, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+                Uuid::new_v4(),
+                sanction.community_id,
+                revoked_by,
+                AuditAction::SanctionRevoked.as_str(),
+                sanction.user_id,
+                sanction.id,
+                now
+            )
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(sanction)
+    }
+
+    /// Find a page of a community's audit log, or the platform's for `None`, newest first
+    pub async fn find_audit_page(
+        pool: &Pool<Postgres>,
+        community_id: Option<Uuid>,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Page<AuditEntry>, sqlx::Error> {
+        let entries = sqlx::query_as!(
+            AuditEntry,
+            r#"
+            SELECT
+                id, community_id, actor_id, action as "action: AuditAction",
+                target_user_id, sanction_id, reason, created_at
+            FROM moderation_audit_log
+            WHERE community_id IS NOT DISTINCT FROM $1
+                AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3::uuid))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $4
+            "#,
+            community_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(Page::from_rows(entries, limit, |entry| Cursor::new(entry.created_at, entry.id)))
+    }
+}
@@ -0,0 +1,88 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::model::search::{headline_options, render_snippet, SearchResult, SearchResultKind};
+
+pub struct SearchRepository;
+
+impl SearchRepository {
+    /// Search communities and messages, best matches first.
+    ///
+    /// `text` is the raw user query, matched with English stemming;
+    /// `prefix_query` is a `simple` tsquery from `prefix_tsquery` that
+    /// catches words the English configuration does not know about.
+    /// Snippets are only generated for the rows that are returned, and are
+    /// HTML-escaped with hits wrapped in `<mark>` tags. Messages in private
+    /// communities only match for their members.
+    pub async fn search(
+        pool: &Pool<Postgres>,
+        text: &str,
+        prefix_query: &str,
+        kind: Option<SearchResultKind>,
+        viewer: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<SearchResult>, sqlx::Error> {
+        let results = sqlx::query_as!(
+            SearchResult,
+            r#"
+            WITH q AS (
+                SELECT websearch_to_tsquery('english', $1) || to_tsquery('simple', $2) AS query
+            )
+            SELECT
+                r.kind as "kind!: SearchResultKind",
+                r.id as "id!",
+                r.community_id as "community_id!",
+                r.title as "title!",
+                ts_headline('english', r.body, q.query, $5) as "snippet!",
+                r.rank as "rank!",
+                r.created_at as "created_at!"
+            FROM (
+                SELECT * FROM (
+                    SELECT
+                        'community'::varchar AS kind, c.id, c.id AS community_id, c.name AS title,
+                        COALESCE(c.description, c.name) AS body,
+                        ts_rank_cd(c.search_vector, q.query) AS rank, c.created_at
+                    FROM communities c, q
+                    WHERE ($3::varchar IS NULL OR $3 = 'community')
+                        AND c.status <> 'draft'
+                        AND c.search_vector @@ q.query
+                    UNION ALL
+                    SELECT
+                        'content'::varchar, ct.id, ct.community_id, c.name,
+                        ct.content::text,
+                        ts_rank_cd(ct.search_vector, q.query), ct.created_at
+                    FROM content ct
+                    JOIN communities c ON c.id = ct.community_id, q
+                    WHERE ($3::varchar IS NULL OR $3 = 'content')
+                        AND c.status <> 'draft'
+                        AND ct.deleted_at IS NULL
+                        AND ct.search_vector @@ q.query
+                        AND (
+                            c.visibility = 'public'
+                            OR EXISTS (
+                                SELECT 1 FROM community_members m
+                                WHERE m.community_id = c.id AND m.user_id = $6 AND m.status = 'active'
+                            )
+                        )
+                ) matches
+                ORDER BY rank DESC, created_at DESC
+                LIMIT $4
+            ) r, q
+            ORDER BY r.rank DESC, r.created_at DESC
+            "#,
+            text,
+            prefix_query,
+            kind.map(|kind| kind.as_str()),
+            limit,
+            headline_options(),
+            viewer
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|result| SearchResult { snippet: render_snippet(&result.snippet), ..result })
+            .collect())
+    }
+}
@@ -3,7 +3,8 @@ use uuid::Uuid;
 use chrono::Utc;
 
 
-use crate::model::user::{User, CreateUserDto, UpdateUserDto};
+use crate::model::user::{User, CreateUserDto};
+use crate::model::pagination::{Cursor, Page};
 
 
 pub struct UserRepository;
@@ -11,20 +12,29 @@ pub struct UserRepository;
 impl UserRepository {
     
     
-    pub async fn find_all(pool: &Pool<Postgres>) -> Result<Vec<User>, sqlx::Error> {
+    /// Find a page of users, newest first.
+    ///
+    /// `after` is the position of the last user on the previous page.
+    pub async fn find_page(pool: &Pool<Postgres>, after: Option<Cursor>, limit: i64) -> Result<Page<User>, sqlx::Error> {
         let users = sqlx::query_as!(
             User,
             r#"
             SELECT 
-                id, xid, username, profile_image_url,  
+                id, xid, username, profile_image_asset_id,  
                 email, created_at, updated_at, wallet_address
             FROM users
-            "#
+            WHERE ($1::timestamptz IS NULL OR (created_at, id) < ($1, $2::uuid))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3
+            "#,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit + 1
         )
             .fetch_all(pool)
             .await?;
 
-        Ok(users)
+        Ok(Page::from_rows(users, limit, |user| Cursor::new(user.created_at, user.id)))
     }
 
     pub async fn find_by_id(pool: &Pool<Postgres>, id: Uuid) -> Result<Option<User>, sqlx::Error> {
@@ -32,7 +42,7 @@ impl UserRepository {
             User,
             r#"
             SELECT 
-                id, xid, username, profile_image_url, wallet_address, 
+                id, xid, username, profile_image_asset_id, wallet_address, 
                 email, created_at, updated_at
             FROM users WHERE id = $1
             "#,
@@ -44,12 +54,25 @@ impl UserRepository {
         Ok(user)
     }
 
+    /// Find the ids of users by username, ignoring case
+    pub async fn find_ids_by_usernames(pool: &Pool<Postgres>, usernames: &[String]) -> Result<Vec<Uuid>, sqlx::Error> {
+        let lowered: Vec<String> = usernames.iter().map(|name| name.to_lowercase()).collect();
+        let ids = sqlx::query_scalar!(
+            "SELECT id FROM users WHERE lower(username) = ANY($1)",
+            &lowered
+        )
+            .fetch_all(pool)
+            .await?;
+
+        Ok(ids)
+    }
+
     pub async fn find_by_email(pool: &Pool<Postgres>, email: &str) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as!(
             User,
             r#"
             SELECT 
-                id, xid, username, profile_image_url, wallet_address, 
+                id, xid, username, profile_image_asset_id, wallet_address, 
                 email, created_at, updated_at
             FROM users WHERE email = $1
             "#,
@@ -71,14 +94,14 @@ impl UserRepository {
         let user = sqlx::query_as!(
             User,
             r#"
-            INSERT INTO users (id, xid, username, profile_image_url, wallet_address, email, password_hash, created_at, updated_at, is_active)
+            INSERT INTO users (id, xid, username, profile_image_asset_id, wallet_address, email, password_hash, created_at, updated_at, is_active)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, xid, username, profile_image_url, wallet_address, email, created_at, updated_at
+            RETURNING id, xid, username, profile_image_asset_id, wallet_address, email, created_at, updated_at
             "#,
             id,
             xid,
             dto.username,
-            dto.profile_image_url.as_deref(),
+            dto.profile_image_asset_id,
             dto.wallet_address,
             dto.email,
             dto.password,
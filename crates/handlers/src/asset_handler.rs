@@ -0,0 +1,152 @@
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use pulse_database::model::asset::{Asset, AssetVariant};
+use pulse_service::asset_service::{AssetServiceError, MAX_UPLOAD_BYTES};
+use pulse_service::{AssetService, BlobStore};
+use std::sync::Arc;
+use pulse_database::connection::Database;
+use crate::auth::CurrentUser;
+
+/// Multipart field holding the uploaded file
+const FILE_FIELD: &str = "file";
+
+/// Asset files never change once stored, so clients can keep them forever
+const IMMUTABLE_CACHE: &str = "public, max-age=31536000, immutable";
+
+// Error handling for asset handlers
+pub enum AssetHandlerError {
+    Service(AssetServiceError),
+    NotFound,
+    BadRequest(String),
+    Multipart(MultipartError),
+}
+
+// Convert AssetHandlerError to StatusCode and message
+impl IntoResponse for AssetHandlerError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            AssetHandlerError::Service(err) => {
+                let status = match &err {
+                    AssetServiceError::InvalidUuid
+                    | AssetServiceError::Image(_) => StatusCode::BAD_REQUEST,
+                    AssetServiceError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+                    AssetServiceError::BlobStore(_)
+                    | AssetServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            AssetHandlerError::NotFound => {
+                (StatusCode::NOT_FOUND, "Asset not found".to_string())
+            },
+            AssetHandlerError::BadRequest(err) => {
+                (StatusCode::BAD_REQUEST, err)
+            },
+            AssetHandlerError::Multipart(err) => {
+                (err.status(), err.body_text())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to AssetHandlerError
+impl From<AssetServiceError> for AssetHandlerError {
+    fn from(err: AssetServiceError) -> Self {
+        AssetHandlerError::Service(err)
+    }
+}
+
+impl From<MultipartError> for AssetHandlerError {
+    fn from(err: MultipartError) -> Self {
+        // Hitting the body limit should read the same as an oversized file
+        if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            return AssetHandlerError::Service(AssetServiceError::TooLarge);
+        }
+        AssetHandlerError::Multipart(err)
+    }
+}
+
+// Upload an image as the `file` field of a multipart form. The caller becomes
+// the owner and the only one who can use it. Uploads without a caller are for
+// the profile image on signup, before there is a user, and can't be attached
+// to anything else.
+pub async fn upload_asset(
+    State(db): State<Arc<Database>>,
+    State(blobs): State<Arc<dyn BlobStore>>,
+    user: Option<CurrentUser>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<Asset>), AssetHandlerError> {
+    let mut bytes = None;
+    while let Some(mut field) = multipart.next_field().await? {
+        if field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+
+        // Read in chunks so an oversized file is turned away early
+        let mut buffer = Vec::new();
+        while let Some(chunk) = field.chunk().await? {
+            if buffer.len() + chunk.len() > MAX_UPLOAD_BYTES {
+                return Err(AssetServiceError::TooLarge.into());
+            }
+            buffer.extend_from_slice(&chunk);
+        }
+        bytes = Some(buffer);
+        break;
+    }
+    let bytes = bytes.ok_or_else(|| AssetHandlerError::BadRequest(format!("Missing {} field", FILE_FIELD)))?;
+
+    let service = AssetService::new(db, blobs);
+    let asset = service.upload(user.map(|CurrentUser(id)| id), bytes).await?;
+
+    Ok((StatusCode::CREATED, Json(asset)))
+}
+
+// Get what is known about an asset
+pub async fn get_asset(
+    State(db): State<Arc<Database>>,
+    State(blobs): State<Arc<dyn BlobStore>>,
+    Path(id): Path<String>,
+) -> Result<Json<Asset>, AssetHandlerError> {
+    let service = AssetService::new(db, blobs);
+    let asset = service.get_asset(id).await?.ok_or(AssetHandlerError::NotFound)?;
+
+    Ok(Json(asset))
+}
+
+// Serve an asset's image
+pub async fn get_asset_file(
+    State(db): State<Arc<Database>>,
+    State(blobs): State<Arc<dyn BlobStore>>,
+    Path(id): Path<String>,
+) -> Result<Response, AssetHandlerError> {
+    serve_blob(AssetService::new(db, blobs), id, AssetVariant::Original).await
+}
+
+// Serve an asset's thumbnail
+pub async fn get_asset_thumbnail(
+    State(db): State<Arc<Database>>,
+    State(blobs): State<Arc<dyn BlobStore>>,
+    Path(id): Path<String>,
+) -> Result<Response, AssetHandlerError> {
+    serve_blob(AssetService::new(db, blobs), id, AssetVariant::Thumbnail).await
+}
+
+async fn serve_blob(service: AssetService, id: String, variant: AssetVariant) -> Result<Response, AssetHandlerError> {
+    let blob = service.get_blob(id, variant).await?.ok_or(AssetHandlerError::NotFound)?;
+
+    let headers = [
+        (header::CONTENT_TYPE, blob.mime.as_str()),
+        (header::CACHE_CONTROL, IMMUTABLE_CACHE),
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+    ];
+    Ok((headers, blob.bytes).into_response())
+}
@@ -0,0 +1,65 @@
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use uuid::Uuid;
+
+/// Header carrying the id of the user making the request
+pub const USER_ID_HEADER: &str = "x-user-id";
+
+/// The user making the request.
+///
+/// Until token authentication lands this is read from the `X-User-Id` header,
+/// so handlers that need to know who is calling already have a single place
+/// to get it from.
+#[derive(Debug, Clone, Copy)]
+pub struct CurrentUser(pub Uuid);
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let unauthorized = |message: &str| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({ "error": message })),
+            )
+        };
+
+        let header = parts
+            .headers
+            .get(USER_ID_HEADER)
+            .ok_or_else(|| unauthorized("Missing X-User-Id header"))?;
+
+        let user_id = header
+            .to_str()
+            .ok()
+            .and_then(|value| Uuid::parse_str(value.trim()).ok())
+            .ok_or_else(|| unauthorized("Invalid X-User-Id header"))?;
+
+        Ok(CurrentUser(user_id))
+    }
+}
+
+/// Lets handlers that also serve anonymous callers take `Option<CurrentUser>`.
+/// A missing header means anonymous; a malformed one is still rejected.
+impl<S> OptionalFromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(USER_ID_HEADER) {
+            return Ok(None);
+        }
+
+        <CurrentUser as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
@@ -0,0 +1,64 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+
+/// Header proxies append the address they got the request from to
+pub const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// The address the request came from, if it is known.
+///
+/// Behind proxies, set `TRUSTED_PROXY_HOPS` to how many of them there are.
+/// Each one appends the address it was reached from to `X-Forwarded-For`, so
+/// the client is that many entries from the end; anything earlier in the
+/// header was written by the client and can't be trusted.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+fn trusted_proxy_hops() -> usize {
+    static HOPS: OnceLock<usize> = OnceLock::new();
+    *HOPS.get_or_init(|| {
+        std::env::var("TRUSTED_PROXY_HOPS")
+            .ok()
+            .and_then(|hops| hops.trim().parse().ok())
+            .unwrap_or(0)
+    })
+}
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip());
+
+        let hops = trusted_proxy_hops();
+        if hops == 0 {
+            return Ok(ClientIp(peer));
+        }
+
+        let forwarded: Vec<&str> = parts
+            .headers
+            .get_all(FORWARDED_FOR_HEADER)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+
+        let client = forwarded
+            .len()
+            .checked_sub(hops)
+            .and_then(|index| forwarded[index].parse().ok());
+        Ok(ClientIp(client.or(peer)))
+    }
+}
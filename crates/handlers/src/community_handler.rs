@@ -1,16 +1,27 @@
 use axum::{
-    extract::{State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
-use pulse_database::model::community::{Community, CreateCommunityDto};
+use pulse_database::model::community::{
+    Community, CommunityListQuery, CommunityStatusTransition, CreateCommunityDto, DiscoverQuery,
+    UpdateCommunityDto, UpdateCommunityStatusDto,
+};
+use pulse_database::model::pagination::Page;
+use pulse_database::model::depositor::{CreateDepositDto, Depositor};
+use pulse_service::community_service::CommunityServiceError;
 use pulse_service::CommunityService;
 use std::sync::Arc;
+use uuid::Uuid;
 use pulse_database::connection::Database;
 
+use crate::auth::CurrentUser;
+
 // Error handling for community handlers
 pub enum CommunityHandlerError {
-    Service(String),
+    Service(CommunityServiceError),
+    InvalidUuid,
+    BadRequest(String),
 }
 
 // Convert CommunityHandlerError to StatusCode and message
@@ -18,7 +29,27 @@ impl axum::response::IntoResponse for CommunityHandlerError {
     fn into_response(self) -> axum::response::Response {
         let (status, message) = match self {
             CommunityHandlerError::Service(err) => {
-                (StatusCode::INTERNAL_SERVER_ERROR, err)
+                let status = match err {
+                    CommunityServiceError::NotFound => StatusCode::NOT_FOUND,
+                    CommunityServiceError::InvalidUuid
+                    | CommunityServiceError::InvalidCursor
+                    | CommunityServiceError::InvalidAmount
+                    | CommunityServiceError::InvalidImageAsset => StatusCode::BAD_REQUEST,
+                    CommunityServiceError::Forbidden | CommunityServiceError::Banned => StatusCode::FORBIDDEN,
+                    CommunityServiceError::InvalidTransition { .. }
+                    | CommunityServiceError::SetByRoundEngine(_)
+                    | CommunityServiceError::DepositsClosed(_)
+                    | CommunityServiceError::FieldNotEditable { .. }
+                    | CommunityServiceError::CannotDelete(_) => StatusCode::CONFLICT,
+                    CommunityServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            CommunityHandlerError::InvalidUuid => {
+                (StatusCode::BAD_REQUEST, "Invalid UUID format".to_string())
+            },
+            CommunityHandlerError::BadRequest(err) => {
+                (StatusCode::BAD_REQUEST, err)
             },
         };
 
@@ -31,12 +62,18 @@ impl axum::response::IntoResponse for CommunityHandlerError {
 }
 
 // Convert service errors to CommunityHandlerError
-impl From<String> for CommunityHandlerError {
-    fn from(err: String) -> Self {
+impl From<CommunityServiceError> for CommunityHandlerError {
+    fn from(err: CommunityServiceError) -> Self {
         CommunityHandlerError::Service(err)
     }
 }
 
+impl From<uuid::Error> for CommunityHandlerError {
+    fn from(_: uuid::Error) -> Self {
+        CommunityHandlerError::InvalidUuid
+    }
+}
+
 // Create new community
 pub async fn create_community(
     State(db): State<Arc<Database>>,
@@ -45,19 +82,139 @@ pub async fn create_community(
     // In a real application, you would extract the user_id from the authentication token
     // For now, we'll use the first user ID from our database
     let user_id = "5374209b-2abd-4138-88f5-75839c9db9c9".to_string();
-    
+
     let service = CommunityService::new(db);
     let community = service.create_community(user_id, dto).await?;
-    
+
     Ok(Json(community))
 }
 
-// Get all communities
+// Get a page of communities
 pub async fn get_all_communities(
     State(db): State<Arc<Database>>,
+    Query(query): Query<CommunityListQuery>,
+) -> Result<Json<Page<Community>>, CommunityHandlerError> {
+    let service = CommunityService::new(db);
+    let communities = service.get_communities(&query).await?;
+
+    Ok(Json(communities))
+}
+
+// Get ranked communities for the home screen
+pub async fn discover_communities(
+    State(db): State<Arc<Database>>,
+    Query(query): Query<DiscoverQuery>,
+) -> Result<Json<Page<Community>>, CommunityHandlerError> {
+    let service = CommunityService::new(db);
+    let communities = service.discover(&query).await?;
+
+    Ok(Json(communities))
+}
+
+// Get community by ID
+pub async fn get_community(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+) -> Result<Json<Community>, CommunityHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = CommunityService::new(db);
+    let community = service.get_community(uuid).await?;
+
+    Ok(Json(community))
+}
+
+// Update a community (creator only)
+pub async fn update_community(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateCommunityDto>,
+) -> Result<Json<Community>, CommunityHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = CommunityService::new(db);
+    let community = service.update_community(uuid, user_id, dto).await?;
+
+    Ok(Json(community))
+}
+
+// Delete a community (creator only)
+pub async fn delete_community(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, CommunityHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = CommunityService::new(db);
+    service.delete_community(uuid, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Get communities created by a user
+pub async fn get_user_communities(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
 ) -> Result<Json<Vec<Community>>, CommunityHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
     let service = CommunityService::new(db);
-    let communities = service.get_all_communities().await?;
-    
+    let communities = service.get_communities_by_creator(uuid).await?;
+
     Ok(Json(communities))
-}
\ No newline at end of file
+}
+
+// Move a community to a new lifecycle status (creator only)
+pub async fn update_community_status(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateCommunityStatusDto>,
+) -> Result<Json<Community>, CommunityHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = CommunityService::new(db);
+    let community = service.transition(uuid, dto.status, user_id).await?;
+
+    Ok(Json(community))
+}
+
+// Get the status history of a community
+pub async fn get_community_status_history(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<CommunityStatusTransition>>, CommunityHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = CommunityService::new(db);
+    let transitions = service.get_status_history(uuid).await?;
+
+    Ok(Json(transitions))
+}
+
+// Deposit into a community's bounty
+pub async fn create_deposit(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    Json(dto): Json<CreateDepositDto>,
+) -> Result<Json<Depositor>, CommunityHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    // Get the user_id from the request DTO
+    let user_id = match dto.user_id {
+        Some(user_id) => user_id,
+        None => return Err(CommunityHandlerError::BadRequest("userId is required".to_string())),
+    };
+
+    let service = CommunityService::new(db);
+    let deposit = service.create_deposit(uuid, user_id, dto).await?;
+
+    Ok(Json(deposit))
+}
+
+// Get deposits for a community
+pub async fn get_community_deposits(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Depositor>>, CommunityHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = CommunityService::new(db);
+    let deposits = service.get_deposits(uuid).await?;
+
+    Ok(Json(deposits))
+}
@@ -1,16 +1,29 @@
 use axum::{
-    extract::{State, Path},
+    extract::{State, Path, Query},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
-use pulse_database::model::content::{Content, CreateContentDto};
-use pulse_service::ContentService;
+use pulse_database::model::content::{
+    CommunityContents, Content, ContentEdit, ContentListQuery, CreateContentDto, DeleteContentQuery, ReplyListQuery, UpdateContentDto,
+};
+use pulse_database::model::pagination::Page;
+use pulse_database::model::reaction::{CreateReactionDto, ReactionSummary};
+use pulse_service::community_service::CommunityServiceError;
+use pulse_service::content_service::ContentServiceError;
+use pulse_service::content_service::PostOutcome;
+use pulse_service::rate_limit_service::RateLimitServiceError;
+use pulse_service::{ContentService, ModerationPipeline, RateLimitService, RateLimitStore};
 use std::sync::Arc;
 use pulse_database::connection::Database;
+use crate::auth::CurrentUser;
+use crate::client_ip::ClientIp;
+use crate::rate_limit_handler::RateLimitHandlerError;
 
 // Error handling for content handlers
 pub enum ContentHandlerError {
-    Service(String),
+    Service(ContentServiceError),
+    RateLimit(RateLimitServiceError),
     NotFound,
     BadRequest(String), // Add a proper BadRequest variant
 }
@@ -20,7 +33,35 @@ impl axum::response::IntoResponse for ContentHandlerError {
     fn into_response(self) -> axum::response::Response {
         let (status, message) = match self {
             ContentHandlerError::Service(err) => {
-                (StatusCode::INTERNAL_SERVER_ERROR, err)
+                let status = match &err {
+                    ContentServiceError::InvalidUuid
+                    | ContentServiceError::InvalidCursor
+                    | ContentServiceError::InvalidReplyTarget
+                    | ContentServiceError::InvalidReaction
+                    | ContentServiceError::InvalidImageAsset
+                    | ContentServiceError::EmptyContent
+                    | ContentServiceError::ContentTooLong
+                    | ContentServiceError::ReasonRequired => StatusCode::BAD_REQUEST,
+                    ContentServiceError::Forbidden
+                    | ContentServiceError::CreatorOnly
+                    | ContentServiceError::NotMember
+                    | ContentServiceError::MembersOnly
+                    | ContentServiceError::Banned
+                    | ContentServiceError::Muted(_) => StatusCode::FORBIDDEN,
+                    ContentServiceError::Rejected(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                    ContentServiceError::EditWindowClosed
+                    | ContentServiceError::ContentDeleted
+                    | ContentServiceError::TooManyPinned => StatusCode::CONFLICT,
+                    ContentServiceError::CommunityNotLive(_)
+                    | ContentServiceError::RoundEnded => StatusCode::CONFLICT,
+                    ContentServiceError::Community(CommunityServiceError::NotFound) => StatusCode::NOT_FOUND,
+                    ContentServiceError::Community(_)
+                    | ContentServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            ContentHandlerError::RateLimit(err) => {
+                return RateLimitHandlerError::Service(err).into_response();
             },
             ContentHandlerError::NotFound => {
                 (StatusCode::NOT_FOUND, "Content not found".to_string())
@@ -39,60 +80,180 @@ impl axum::response::IntoResponse for ContentHandlerError {
 }
 
 // Convert service errors to ContentHandlerError
-impl From<String> for ContentHandlerError {
-    fn from(err: String) -> Self {
+impl From<ContentServiceError> for ContentHandlerError {
+    fn from(err: ContentServiceError) -> Self {
         ContentHandlerError::Service(err)
     }
 }
 
-// Create new content
+// Create new content. Held messages come back as 202 with their place in the moderation queue.
 pub async fn create_content(
     State(db): State<Arc<Database>>,
-    Json(mut dto): Json<CreateContentDto>,
-) -> Result<Json<Content>, ContentHandlerError> {
-    
-    println!("recieved");
-    // Get the user_id from the request DTO
-    let user_id = match &dto.sender_id {
-        Some(id) => id.to_string(),
-        None => return Err(ContentHandlerError::BadRequest("sender_id is required".to_string())),
-    };
+    State(moderation): State<Arc<ModerationPipeline>>,
+    State(rate_limits): State<Arc<dyn RateLimitStore>>,
+    ClientIp(ip): ClientIp,
+    CurrentUser(user_id): CurrentUser,
+    Json(dto): Json<CreateContentDto>,
+) -> Result<Response, ContentHandlerError> {
+    // The limits follow the authenticated caller, whatever the body claims as sender
+    RateLimitService::new(db.clone(), rate_limits)
+        .check_post(ip, user_id, dto.community_id)
+        .await
+        .map_err(ContentHandlerError::RateLimit)?;
 
     let service = ContentService::new(db);
-    let content = service.create_content(user_id, dto).await?;
+    let response = match service.create_content(user_id.to_string(), dto, &moderation).await? {
+        PostOutcome::Published(content) => Json(content).into_response(),
+        PostOutcome::Held(held) => (StatusCode::ACCEPTED, Json(held)).into_response(),
+    };
 
-    Ok(Json(content))
+    Ok(response)
 }
 
 // Get content by ID
 pub async fn get_content(
     State(db): State<Arc<Database>>,
     Path(id): Path<String>,
+    user: Option<CurrentUser>,
 ) -> Result<Json<Content>, ContentHandlerError> {
     let service = ContentService::new(db);
-    let content = service.get_content_by_id(id).await?
+    let content = service.get_content_by_id(id, user.map(|CurrentUser(user_id)| user_id)).await?
         .ok_or(ContentHandlerError::NotFound)?;
 
     Ok(Json(content))
 }
 
-// Get all content
+// Edit the caller's own content
+pub async fn update_content(
+    State(db): State<Arc<Database>>,
+    State(moderation): State<Arc<ModerationPipeline>>,
+    Path(id): Path<String>,
+    CurrentUser(user_id): CurrentUser,
+    Json(dto): Json<UpdateContentDto>,
+) -> Result<Json<Content>, ContentHandlerError> {
+    let service = ContentService::new(db);
+    let content = service.edit_content(id, user_id, dto, &moderation).await?
+        .ok_or(ContentHandlerError::NotFound)?;
+
+    Ok(Json(content))
+}
+
+// Delete a content, as its sender or as the community's moderator
+pub async fn delete_content(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    CurrentUser(user_id): CurrentUser,
+    Query(query): Query<DeleteContentQuery>,
+) -> Result<StatusCode, ContentHandlerError> {
+    let service = ContentService::new(db);
+    if !service.delete_content(id, user_id, query.reason).await? {
+        return Err(ContentHandlerError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Get the earlier versions of an edited content
+pub async fn get_content_history(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    user: Option<CurrentUser>,
+) -> Result<Json<Vec<ContentEdit>>, ContentHandlerError> {
+    let service = ContentService::new(db);
+    let edits = service.get_edit_history(id, user.map(|CurrentUser(user_id)| user_id)).await?
+        .ok_or(ContentHandlerError::NotFound)?;
+
+    Ok(Json(edits))
+}
+
+// Get the replies to a content
+pub async fn get_content_replies(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    Query(query): Query<ReplyListQuery>,
+    user: Option<CurrentUser>,
+) -> Result<Json<Page<Content>>, ContentHandlerError> {
+    let service = ContentService::new(db);
+    let replies = service.get_replies(id, &query, user.map(|CurrentUser(user_id)| user_id)).await?
+        .ok_or(ContentHandlerError::NotFound)?;
+
+    Ok(Json(replies))
+}
+
+// Get a page of content from the communities the caller can read
 pub async fn get_all_contents(
     State(db): State<Arc<Database>>,
-) -> Result<Json<Vec<Content>>, ContentHandlerError> {
+    Query(query): Query<ContentListQuery>,
+    user: Option<CurrentUser>,
+) -> Result<Json<Page<Content>>, ContentHandlerError> {
     let service = ContentService::new(db);
-    let contents = service.get_all_contents().await?;
+    let contents = service.get_contents(&query, user.map(|CurrentUser(user_id)| user_id)).await?;
 
     Ok(Json(contents))
 }
 
-// Get content by community ID
+// Get a page of content by community ID
 pub async fn get_community_contents(
     State(db): State<Arc<Database>>,
     Path(community_id): Path<String>,
-) -> Result<Json<Vec<Content>>, ContentHandlerError> {
+    Query(query): Query<ContentListQuery>,
+    user: Option<CurrentUser>,
+) -> Result<Json<CommunityContents>, ContentHandlerError> {
     let service = ContentService::new(db);
-    let contents = service.get_contents_by_community(community_id).await?;
+    let contents = service.get_contents_by_community(community_id, &query, user.map(|CurrentUser(user_id)| user_id)).await?;
 
     Ok(Json(contents))
 }
+
+// Pin a content to the top of its community (creator only)
+pub async fn pin_content(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<Vec<Content>>, ContentHandlerError> {
+    let service = ContentService::new(db);
+    let pinned = service.pin_content(id, user_id).await?
+        .ok_or(ContentHandlerError::NotFound)?;
+
+    Ok(Json(pinned))
+}
+
+// Unpin a content (creator only)
+pub async fn unpin_content(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<Vec<Content>>, ContentHandlerError> {
+    let service = ContentService::new(db);
+    let pinned = service.unpin_content(id, user_id).await?
+        .ok_or(ContentHandlerError::NotFound)?;
+
+    Ok(Json(pinned))
+}
+
+// React to a content, replacing the caller's previous reaction
+pub async fn add_content_reaction(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    CurrentUser(user_id): CurrentUser,
+    Json(dto): Json<CreateReactionDto>,
+) -> Result<Json<ReactionSummary>, ContentHandlerError> {
+    let service = ContentService::new(db);
+    let summary = service.add_reaction(id, user_id, &dto.reaction).await?
+        .ok_or(ContentHandlerError::NotFound)?;
+
+    Ok(Json(summary))
+}
+
+// Remove the caller's reaction to a content
+pub async fn remove_content_reaction(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<ReactionSummary>, ContentHandlerError> {
+    let service = ContentService::new(db);
+    let summary = service.remove_reaction(id, user_id).await?
+        .ok_or(ContentHandlerError::NotFound)?;
+
+    Ok(Json(summary))
+}
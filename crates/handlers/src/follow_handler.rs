@@ -0,0 +1,143 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use pulse_database::model::feed::{FeedItem, FeedQuery};
+use pulse_database::model::follow::Following;
+use pulse_database::model::pagination::Page;
+use pulse_service::follow_service::FollowServiceError;
+use pulse_service::FollowService;
+use std::sync::Arc;
+use uuid::Uuid;
+use pulse_database::connection::Database;
+
+use crate::auth::CurrentUser;
+use crate::community_handler::CommunityHandlerError;
+
+// Error handling for follow handlers
+pub enum FollowHandlerError {
+    Service(FollowServiceError),
+    InvalidUuid,
+}
+
+// Convert FollowHandlerError to StatusCode and message
+impl axum::response::IntoResponse for FollowHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            FollowHandlerError::Service(FollowServiceError::Community(err)) => {
+                return CommunityHandlerError::Service(err).into_response();
+            },
+            FollowHandlerError::Service(err) => {
+                let status = match &err {
+                    FollowServiceError::UserNotFound
+                    | FollowServiceError::NotFollowing => StatusCode::NOT_FOUND,
+                    FollowServiceError::CannotFollowSelf
+                    | FollowServiceError::InvalidCursor => StatusCode::BAD_REQUEST,
+                    FollowServiceError::MembersOnly => StatusCode::FORBIDDEN,
+                    FollowServiceError::TooManyFollows => StatusCode::CONFLICT,
+                    FollowServiceError::Community(_)
+                    | FollowServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            FollowHandlerError::InvalidUuid => {
+                (StatusCode::BAD_REQUEST, "Invalid UUID format".to_string())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to FollowHandlerError
+impl From<FollowServiceError> for FollowHandlerError {
+    fn from(err: FollowServiceError) -> Self {
+        FollowHandlerError::Service(err)
+    }
+}
+
+impl From<uuid::Error> for FollowHandlerError {
+    fn from(_: uuid::Error) -> Self {
+        FollowHandlerError::InvalidUuid
+    }
+}
+
+// Follow a user
+pub async fn follow_user(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, FollowHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = FollowService::new(db);
+    service.follow_user(user_id, uuid).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Stop following a user
+pub async fn unfollow_user(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, FollowHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = FollowService::new(db);
+    service.unfollow_user(user_id, uuid).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Follow a community
+pub async fn follow_community(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, FollowHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = FollowService::new(db);
+    service.follow_community(user_id, uuid).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Stop following a community
+pub async fn unfollow_community(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, FollowHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = FollowService::new(db);
+    service.unfollow_community(user_id, uuid).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Get the users and communities the caller follows
+pub async fn get_my_following(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<Following>, FollowHandlerError> {
+    let service = FollowService::new(db);
+    let following = service.get_following(user_id).await?;
+
+    Ok(Json(following))
+}
+
+// Get the caller's home feed, newest first
+pub async fn get_my_feed(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Query(query): Query<FeedQuery>,
+) -> Result<Json<Page<FeedItem>>, FollowHandlerError> {
+    let service = FollowService::new(db);
+    let feed = service.get_feed(user_id, &query).await?;
+
+    Ok(Json(feed))
+}
@@ -0,0 +1,131 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use pulse_database::connection::Database;
+use pulse_database::model::idempotency::{is_valid_idempotency_key, StoredResponse};
+use pulse_service::idempotency_service::{request_fingerprint, IdempotencyClaim};
+use pulse_service::IdempotencyService;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::auth::USER_ID_HEADER;
+
+/// Header carrying the client's key for a request it may retry
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header set on responses replayed for a retry
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Most of a request body read to fingerprint it; axum's own default for JSON
+const MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+
+/// Most of a response body kept for replay
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored.content_type.and_then(|content_type| HeaderValue::from_str(&content_type).ok()) {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// Middleware for create endpoints that clients retry.
+///
+/// A request with an `Idempotency-Key` header is handled once; retries with
+/// the same key and body get the first response back instead of, say, a
+/// second charge. Reusing a key for a different request is a 409. Requests
+/// without the header, or from anonymous callers, are passed straight through.
+pub async fn idempotent(State(db): State<Arc<Database>>, request: Request, next: Next) -> Response {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let Some(key) = key.to_str().ok().filter(|key| is_valid_idempotency_key(key)).map(str::to_string) else {
+        return error(StatusCode::BAD_REQUEST, "Invalid Idempotency-Key header");
+    };
+
+    // Keys belong to the caller. Anonymous callers have no namespace of their
+    // own, so their keys are neither kept nor replayed to anyone else
+    let Some(user_id) = request
+        .headers()
+        .get(USER_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value.trim()).ok())
+    else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_REQUEST_BYTES).await else {
+        return error(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large");
+    };
+    let fingerprint = request_fingerprint(parts.method.as_str(), parts.uri.path(), &body);
+
+    let service = IdempotencyService::new(db);
+    match service.begin(user_id, &key, &fingerprint).await {
+        Ok(IdempotencyClaim::New) => {},
+        Ok(IdempotencyClaim::Replay(stored)) => return replay(stored),
+        Ok(IdempotencyClaim::Mismatch) => {
+            return error(StatusCode::CONFLICT, "Idempotency-Key was already used for a different request");
+        },
+        Ok(IdempotencyClaim::InProgress) => {
+            return error(StatusCode::CONFLICT, "A request with this Idempotency-Key is still being processed");
+        },
+        // Without the key we can't promise the request runs once, so it doesn't run
+        Err(e) => {
+            eprintln!("❌ Failed to claim idempotency key: {}", e);
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to check Idempotency-Key");
+        },
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    // Server errors and rate limiting are worth retrying, so they aren't kept
+    let status = response.status();
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        if let Err(e) = service.release(user_id, &key).await {
+            eprintln!("❌ Failed to release idempotency key: {}", e);
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_RESPONSE_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("❌ Failed to read response for idempotency key: {}", e);
+            if let Err(e) = service.release(user_id, &key).await {
+                eprintln!("❌ Failed to release idempotency key: {}", e);
+            }
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response");
+        },
+    };
+
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    };
+    // The request has been handled either way; a retry will wait out the claim
+    if let Err(e) = service.complete(user_id, &key, &stored).await {
+        eprintln!("❌ Failed to store idempotent response: {}", e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
@@ -1,7 +1,32 @@
+pub mod auth;
+pub mod client_ip;
+pub mod idempotency;
+pub mod state;
 pub mod user_handler;
+pub mod asset_handler;
 pub mod community_handler;
 pub mod content_handler;
+pub mod search_handler;
+pub mod live_handler;
+pub mod notification_handler;
+pub mod member_handler;
+pub mod moderation_handler;
+pub mod rate_limit_handler;
+pub mod reward_handler;
+pub mod sanction_handler;
+pub mod follow_handler;
 
+pub use state::AppState;
 pub use user_handler::*;
+pub use asset_handler::*;
 pub use community_handler::*;
-pub use content_handler::*;
\ No newline at end of file
+pub use content_handler::*;
+pub use search_handler::*;
+pub use live_handler::*;
+pub use notification_handler::*;
+pub use member_handler::*;
+pub use moderation_handler::*;
+pub use rate_limit_handler::*;
+pub use reward_handler::*;
+pub use sanction_handler::*;
+pub use follow_handler::*;
@@ -0,0 +1,587 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+    Json,
+};
+use futures_util::{
+    stream::{self, SplitSink, Stream, StreamExt},
+    SinkExt,
+};
+use chrono::{DateTime, Utc};
+use pulse_database::model::community::{Community, CommunityStatus};
+use pulse_database::model::event::{CommunityEvent, EventFilter, EventStreamQuery};
+use pulse_service::community_service::CommunityServiceError;
+use pulse_service::event_service::global_deposit_threshold;
+use pulse_service::{CommunityService, EventHub, EventService, MemberService, PresenceService};
+use pulse_database::model::presence::Presence;
+use crate::auth::CurrentUser;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+use pulse_database::connection::Database;
+
+/// Most events replayed on resume before the client is told to resync
+const MAX_REPLAY: i64 = 500;
+
+/// A client that cannot take a frame within this long is disconnected
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often idle connections are pinged
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How close to the deadline countdown ticks start
+const COUNTDOWN_WINDOW: chrono::Duration = chrono::Duration::seconds(60);
+
+/// How often countdown ticks are sent
+const COUNTDOWN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often a heartbeat comment is sent on idle event streams
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long a stream goes on trusting a check that its viewer can read a community
+const READ_ACCESS_TTL: Duration = Duration::from_secs(30);
+
+// Error handling for live handlers
+pub enum LiveHandlerError {
+    Service(CommunityServiceError),
+    InvalidUuid,
+    InvalidFilter,
+    MembersOnly,
+    Database(sqlx::Error),
+}
+
+// Convert LiveHandlerError to StatusCode and message
+impl axum::response::IntoResponse for LiveHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            LiveHandlerError::Service(err) => {
+                let status = match err {
+                    CommunityServiceError::NotFound => StatusCode::NOT_FOUND,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            LiveHandlerError::InvalidUuid => {
+                (StatusCode::BAD_REQUEST, "Invalid UUID format".to_string())
+            },
+            LiveHandlerError::InvalidFilter => {
+                (StatusCode::BAD_REQUEST, "Unknown event type".to_string())
+            },
+            LiveHandlerError::MembersOnly => {
+                (StatusCode::FORBIDDEN, "Only members can read this community".to_string())
+            },
+            LiveHandlerError::Database(err) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", err))
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+impl From<CommunityServiceError> for LiveHandlerError {
+    fn from(err: CommunityServiceError) -> Self {
+        LiveHandlerError::Service(err)
+    }
+}
+
+impl From<uuid::Error> for LiveHandlerError {
+    fn from(_: uuid::Error) -> Self {
+        LiveHandlerError::InvalidUuid
+    }
+}
+
+impl From<sqlx::Error> for LiveHandlerError {
+    fn from(err: sqlx::Error) -> Self {
+        LiveHandlerError::Database(err)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LiveQuery {
+    /// Id of the last event the client saw, to resume after a reconnect
+    #[serde(rename = "lastEventId")]
+    pub last_event_id: Option<i64>,
+}
+
+// Upgrade to a WebSocket streaming a community's live events
+pub async fn community_ws(
+    ws: WebSocketUpgrade,
+    State(db): State<Arc<Database>>,
+    State(hub): State<EventHub>,
+    Path(id): Path<String>,
+    Query(query): Query<LiveQuery>,
+    user: Option<CurrentUser>,
+) -> Result<Response, LiveHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+
+    // Fail with a proper status before upgrading if the community doesn't exist
+    CommunityService::new(db.clone()).get_community(uuid).await?;
+
+    let user_id = user.map(|CurrentUser(user_id)| user_id);
+    let mut access = ReadAccess::new(db.clone(), user_id);
+    if !access.allows(uuid).await {
+        return Err(LiveHandlerError::MembersOnly);
+    }
+
+    Ok(ws.on_upgrade(move |socket| watch_community(socket, db, hub, uuid, user_id, query.last_event_id)))
+}
+
+// Get how many people are watching a community; its members also see who
+pub async fn get_community_presence(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    user: Option<CurrentUser>,
+) -> Result<Json<Presence>, LiveHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    CommunityService::new(db.clone()).get_community(uuid).await?;
+
+    let include_users = match user {
+        Some(CurrentUser(user_id)) => MemberService::new(db.clone()).is_active_member(uuid, user_id).await?,
+        None => false,
+    };
+    let presence = PresenceService::new(db)
+        .get_presence(uuid, include_users)
+        .await?;
+
+    Ok(Json(presence))
+}
+
+type Sink = SplitSink<WebSocket, Message>;
+
+/// Stream a community to one client, counting it as present while connected
+async fn watch_community(
+    socket: WebSocket,
+    db: Arc<Database>,
+    hub: EventHub,
+    community_id: Uuid,
+    user_id: Option<Uuid>,
+    last_event_id: Option<i64>,
+) {
+    // Subscribe before reading the database so nothing slips through the gap;
+    // anything seen twice is skipped by id below
+    let rx = hub.subscribe();
+
+    let service = PresenceService::new(db.clone());
+    let connection_id = match service.join(community_id, user_id).await {
+        Ok(connection_id) => connection_id,
+        Err(e) => {
+            eprintln!("❌ Failed to record presence: {}", e);
+            return;
+        }
+    };
+    let mut presence = Watcher { service, community_id, user_id, connection_id };
+
+    stream_community(socket, db, rx, community_id, last_event_id, &mut presence).await;
+
+    if let Err(e) = presence.service.leave(presence.connection_id).await {
+        eprintln!("❌ Failed to clear presence: {}", e);
+    }
+}
+
+async fn stream_community(
+    socket: WebSocket,
+    db: Arc<Database>,
+    mut rx: broadcast::Receiver<Arc<CommunityEvent>>,
+    community_id: Uuid,
+    last_event_id: Option<i64>,
+    presence: &mut Watcher,
+) {
+    let (sink, mut stream) = socket.split();
+    let mut access = ReadAccess::new(db.clone(), presence.user_id);
+    let mut conn = Connection {
+        sink,
+        events: EventService::new(db.clone()),
+        db,
+        community_id,
+        last_sent: 0,
+        deadline: None,
+    };
+
+    let started = match last_event_id {
+        Some(after) => conn.resume(after).await,
+        None => conn.send_snapshot().await,
+    };
+    if started.is_err() {
+        return;
+    }
+
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    ping.tick().await;
+    let mut countdown = tokio::time::interval(COUNTDOWN_INTERVAL);
+
+    loop {
+        tokio::select! {
+            received = rx.recv() => match received {
+                Ok(event) => {
+                    if event.community_id != community_id || event.id <= conn.last_sent {
+                        continue;
+                    }
+                    if conn.send_event(&event).await.is_err() {
+                        break;
+                    }
+                }
+                // The client fell behind the hub; catch up from the log instead
+                Err(RecvError::Lagged(_)) => {
+                    if conn.resume(conn.last_sent).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Closed) => break,
+            },
+            incoming = stream.next() => match incoming {
+                // The feed is one-way; anything but a close is ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = countdown.tick() => {
+                if conn.send_countdown().await.is_err() {
+                    break;
+                }
+            }
+            _ = ping.tick() => {
+                // Whoever leaves or is removed from a private community stops hearing from it
+                if !access.allows(community_id).await {
+                    let _ = conn.send(Message::Close(None)).await;
+                    break;
+                }
+                if conn.send(Message::Ping(Vec::new().into())).await.is_err() {
+                    break;
+                }
+                presence.heartbeat().await;
+            }
+        }
+    }
+}
+
+/// A connection's entry in the community's presence
+struct Watcher {
+    service: PresenceService,
+    community_id: Uuid,
+    user_id: Option<Uuid>,
+    connection_id: Uuid,
+}
+
+impl Watcher {
+    /// Keep the connection counted, joining again if it was swept as stale
+    /// (say the database was unreachable for a while)
+    async fn heartbeat(&mut self) {
+        match self.service.heartbeat(self.connection_id).await {
+            Ok(true) => {}
+            Ok(false) => match self.service.join(self.community_id, self.user_id).await {
+                Ok(connection_id) => self.connection_id = connection_id,
+                Err(e) => eprintln!("❌ Failed to record presence: {}", e),
+            },
+            Err(e) => eprintln!("❌ Failed to refresh presence: {}", e),
+        }
+    }
+}
+
+/// One client's WebSocket feed for a community
+struct Connection {
+    sink: Sink,
+    db: Arc<Database>,
+    events: EventService,
+    community_id: Uuid,
+    /// Id of the last event the client has been sent
+    last_sent: i64,
+    /// When the current round ends, as far as the events sent so far say
+    deadline: Option<DateTime<Utc>>,
+}
+
+impl Connection {
+    /// Send events after `after` from the log, or a fresh snapshot if the
+    /// client is too far behind to replay
+    async fn resume(&mut self, after: i64) -> Result<(), ()> {
+        let backlog = self
+            .events
+            .events_after(Some(self.community_id), after, MAX_REPLAY + 1)
+            .await
+            .map_err(|_| ())?;
+
+        if backlog.len() as i64 > MAX_REPLAY {
+            let frame = serde_json::json!({ "type": "resync_required" });
+            self.send(Message::Text(frame_text(frame).into())).await?;
+            return self.send_snapshot().await;
+        }
+
+        // The events being replayed may not touch the timer, so start from
+        // the round as it stands
+        let community = self.community().await?;
+        self.deadline = live_deadline(&community);
+        self.last_sent = after;
+
+        for event in &backlog {
+            self.send_event(event).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send the current state of the community
+    async fn send_snapshot(&mut self) -> Result<(), ()> {
+        // Read the event id first: the snapshot may already include later events,
+        // which is harmless, but must not miss any earlier ones
+        let last_event_id = self.events.latest_event_id(Some(self.community_id)).await.map_err(|_| ())?;
+        let community = self.community().await?;
+        let presence = PresenceService::new(self.db.clone())
+            .get_presence(self.community_id, false)
+            .await
+            .map_err(|_| ())?;
+
+        let frame = serde_json::json!({
+            "type": "snapshot",
+            "lastEventId": last_event_id,
+            "deadline": live_deadline(&community),
+            "presence": presence.counts,
+            "data": community,
+        });
+        self.send(Message::Text(frame_text(frame).into())).await?;
+
+        self.last_sent = last_event_id;
+        self.deadline = live_deadline(&community);
+        Ok(())
+    }
+
+    async fn send_event(&mut self, event: &CommunityEvent) -> Result<(), ()> {
+        let frame = serde_json::to_value(event).map_err(|_| ())?;
+        self.send(Message::Text(frame_text(frame).into())).await?;
+
+        self.last_sent = event.id;
+        if let Some(deadline) = event.deadline_change() {
+            self.deadline = deadline;
+        }
+        Ok(())
+    }
+
+    /// Tell the client how long is left once the round enters its final stretch.
+    ///
+    /// Ticks only report the server's view; whether a message made it in is
+    /// decided when it is stored, never from what a client last saw.
+    async fn send_countdown(&mut self) -> Result<(), ()> {
+        let Some(deadline) = self.deadline else {
+            return Ok(());
+        };
+
+        let remaining = deadline - Utc::now();
+        if remaining <= chrono::Duration::zero() || remaining > COUNTDOWN_WINDOW {
+            return Ok(());
+        }
+
+        let frame = serde_json::json!({
+            "type": "countdown",
+            "communityId": self.community_id,
+            "deadline": deadline,
+            "remainingMs": remaining.num_milliseconds(),
+        });
+        self.send(Message::Text(frame_text(frame).into())).await
+    }
+
+    async fn community(&self) -> Result<Community, ()> {
+        CommunityService::new(self.db.clone())
+            .get_community(self.community_id)
+            .await
+            .map_err(|_| ())
+    }
+
+    /// Send a frame, giving up on clients that stop reading
+    async fn send(&mut self, message: Message) -> Result<(), ()> {
+        match tokio::time::timeout(SEND_TIMEOUT, self.sink.send(message)).await {
+            Ok(Ok(())) => Ok(()),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The deadline of the running round, if there is one
+fn live_deadline(community: &Community) -> Option<DateTime<Utc>> {
+    if community.status == CommunityStatus::Live {
+        community.round_deadline()
+    } else {
+        None
+    }
+}
+
+/// Serialize a frame, stamped with the server's clock so clients can correct for drift
+fn frame_text(mut frame: serde_json::Value) -> String {
+    if let Some(fields) = frame.as_object_mut() {
+        fields.insert("serverTime".to_string(), serde_json::json!(Utc::now()));
+    }
+    frame.to_string()
+}
+
+// Report the server's clock so clients can estimate their offset from it
+pub async fn server_time() -> Json<serde_json::Value> {
+    let now = Utc::now();
+    Json(serde_json::json!({
+        "serverTime": now,
+        "epochMillis": now.timestamp_millis(),
+    }))
+}
+
+// Stream platform-wide events as Server-Sent Events, resuming from Last-Event-ID
+pub async fn events_sse(
+    State(db): State<Arc<Database>>,
+    State(hub): State<EventHub>,
+    Query(query): Query<EventStreamQuery>,
+    user: Option<CurrentUser>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, LiveHandlerError> {
+    let filter = EventFilter::from_query(&query, global_deposit_threshold()).ok_or(LiveHandlerError::InvalidFilter)?;
+
+    // Events from communities the caller can't read are left out; asking
+    // for one of them by name is refused outright
+    let mut access = ReadAccess::new(db.clone(), user.map(|CurrentUser(user_id)| user_id));
+    if let Some(community_id) = filter.community_id {
+        if !access.allows(community_id).await {
+            return Err(LiveHandlerError::MembersOnly);
+        }
+    }
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i64>().ok());
+
+    // Subscribe before reading the database so nothing slips through the gap
+    let rx = hub.subscribe();
+    let mut feed = SseFeed {
+        rx,
+        events: EventService::new(db),
+        filter,
+        access,
+        last_sent: 0,
+        pending: VecDeque::new(),
+    };
+
+    match last_event_id {
+        Some(after) => {
+            feed.last_sent = after;
+            feed.catch_up().await?;
+        }
+        None => feed.last_sent = feed.events.latest_event_id(feed.filter.community_id).await?,
+    }
+
+    let stream = stream::unfold(feed, |mut feed| async move {
+        feed.next_event().await.map(|event| (Ok(event), feed))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL).text("heartbeat")))
+}
+
+/// State behind one SSE connection
+struct SseFeed {
+    rx: broadcast::Receiver<Arc<CommunityEvent>>,
+    events: EventService,
+    filter: EventFilter,
+    access: ReadAccess,
+    /// Id of the newest event handled, whether or not it passed the filter
+    last_sent: i64,
+    pending: VecDeque<Event>,
+}
+
+impl SseFeed {
+    /// Wait for the next event to send, or `None` to end the stream
+    async fn next_event(&mut self) -> Option<Event> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+
+            match self.rx.recv().await {
+                Ok(event) => {
+                    if event.id <= self.last_sent {
+                        continue;
+                    }
+                    self.last_sent = event.id;
+                    if self.filter.matches(&event) && self.access.allows(event.community_id).await {
+                        return Some(sse_event(&event));
+                    }
+                }
+                // Fell behind the hub; catch up from the log, or end the
+                // stream and let the client reconnect with Last-Event-ID
+                Err(RecvError::Lagged(_)) => self.catch_up().await.ok()?,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Queue logged events after `last_sent`, or a resync notice if there are too many
+    async fn catch_up(&mut self) -> Result<(), sqlx::Error> {
+        let backlog = self
+            .events
+            .events_after(self.filter.community_id, self.last_sent, MAX_REPLAY + 1)
+            .await?;
+
+        if backlog.len() as i64 > MAX_REPLAY {
+            self.last_sent = self.events.latest_event_id(self.filter.community_id).await?;
+            let frame = serde_json::json!({ "type": "resync_required" });
+            self.pending.push_back(Event::default().event("resync_required").data(frame_text(frame)));
+            return Ok(());
+        }
+
+        for event in &backlog {
+            self.last_sent = event.id;
+            if self.filter.matches(event) && self.access.allows(event.community_id).await {
+                self.pending.push_back(sse_event(event));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Which communities a stream's viewer can read, checked again once a check
+/// is `READ_ACCESS_TTL` old
+struct ReadAccess {
+    members: MemberService,
+    viewer: Option<Uuid>,
+    checked: HashMap<Uuid, (bool, Instant)>,
+}
+
+impl ReadAccess {
+    fn new(db: Arc<Database>, viewer: Option<Uuid>) -> Self {
+        Self { members: MemberService::new(db), viewer, checked: HashMap::new() }
+    }
+
+    /// Whether the viewer can read a community. If that can't be checked
+    /// the answer is no; the client can always reconnect.
+    async fn allows(&mut self, community_id: Uuid) -> bool {
+        if let Some((allowed, checked_at)) = self.checked.get(&community_id) {
+            if checked_at.elapsed() < READ_ACCESS_TTL {
+                return *allowed;
+            }
+        }
+
+        let allowed = match self.members.can_read(community_id, self.viewer).await {
+            Ok(allowed) => allowed,
+            Err(e) => {
+                eprintln!("❌ Failed to check read access: {}", e);
+                false
+            }
+        };
+        self.checked.insert(community_id, (allowed, Instant::now()));
+        allowed
+    }
+}
+
+fn sse_event(event: &CommunityEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.event_type.as_str())
+        .data(frame_text(serde_json::to_value(event).unwrap_or_default()))
+}
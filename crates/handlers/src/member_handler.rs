@@ -0,0 +1,207 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use pulse_database::model::invite::{CommunityInvite, CreateInviteDto};
+use pulse_database::model::member::{CommunityMember, JoinCommunityDto, MemberListQuery};
+use pulse_database::model::pagination::Page;
+use pulse_database::model::referral::ReferralStats;
+use pulse_service::member_service::MemberServiceError;
+use pulse_service::MemberService;
+use std::sync::Arc;
+use uuid::Uuid;
+use pulse_database::connection::Database;
+
+use crate::auth::CurrentUser;
+use crate::community_handler::CommunityHandlerError;
+
+// Error handling for member handlers
+pub enum MemberHandlerError {
+    Service(MemberServiceError),
+    InvalidUuid,
+}
+
+// Convert MemberHandlerError to StatusCode and message
+impl axum::response::IntoResponse for MemberHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            MemberHandlerError::Service(MemberServiceError::Community(err)) => {
+                return CommunityHandlerError::Service(err).into_response();
+            },
+            MemberHandlerError::Service(err) => {
+                let status = match &err {
+                    MemberServiceError::NotMember
+                    | MemberServiceError::NoRequest
+                    | MemberServiceError::InviteNotFound => StatusCode::NOT_FOUND,
+                    MemberServiceError::InvalidInvite
+                    | MemberServiceError::InvalidMaxUses
+                    | MemberServiceError::InvalidExpiry
+                    | MemberServiceError::InvalidCursor => StatusCode::BAD_REQUEST,
+                    MemberServiceError::Forbidden
+                    | MemberServiceError::MembersOnly
+                    | MemberServiceError::Banned => StatusCode::FORBIDDEN,
+                    MemberServiceError::CreatorCannotLeave => StatusCode::CONFLICT,
+                    MemberServiceError::Community(_)
+                    | MemberServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            MemberHandlerError::InvalidUuid => {
+                (StatusCode::BAD_REQUEST, "Invalid UUID format".to_string())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to MemberHandlerError
+impl From<MemberServiceError> for MemberHandlerError {
+    fn from(err: MemberServiceError) -> Self {
+        MemberHandlerError::Service(err)
+    }
+}
+
+impl From<uuid::Error> for MemberHandlerError {
+    fn from(_: uuid::Error) -> Self {
+        MemberHandlerError::InvalidUuid
+    }
+}
+
+// Join a community, or ask to join a private one
+pub async fn join_community(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    dto: Option<Json<JoinCommunityDto>>,
+) -> Result<Json<CommunityMember>, MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = MemberService::new(db);
+    let member = service.join(uuid, user_id, dto.map(|Json(dto)| dto).unwrap_or_default()).await?;
+
+    Ok(Json(member))
+}
+
+// Leave a community, or withdraw a request to join it
+pub async fn leave_community(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = MemberService::new(db);
+    service.leave(uuid, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Get a page of a community's members, or its pending requests (creator only)
+pub async fn get_community_members(
+    State(db): State<Arc<Database>>,
+    user: Option<CurrentUser>,
+    Path(id): Path<String>,
+    Query(query): Query<MemberListQuery>,
+) -> Result<Json<Page<CommunityMember>>, MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = MemberService::new(db);
+    let members = service.get_members(uuid, user.map(|CurrentUser(user_id)| user_id), &query).await?;
+
+    Ok(Json(members))
+}
+
+// Approve a request to join (creator only)
+pub async fn approve_community_member(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, member_id)): Path<(String, String)>,
+) -> Result<Json<CommunityMember>, MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let member_uuid = Uuid::parse_str(&member_id)?;
+    let service = MemberService::new(db);
+    let member = service.approve(uuid, user_id, member_uuid).await?;
+
+    Ok(Json(member))
+}
+
+// Remove a member or turn down a request to join (creator only)
+pub async fn remove_community_member(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, member_id)): Path<(String, String)>,
+) -> Result<StatusCode, MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let member_uuid = Uuid::parse_str(&member_id)?;
+    let service = MemberService::new(db);
+    service.remove(uuid, user_id, member_uuid).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Create an invite code for a community (members only)
+pub async fn create_community_invite(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    dto: Option<Json<CreateInviteDto>>,
+) -> Result<(StatusCode, Json<CommunityInvite>), MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = MemberService::new(db);
+    let invite = service.create_invite(uuid, user_id, dto.map(|Json(dto)| dto).unwrap_or_default()).await?;
+
+    Ok((StatusCode::CREATED, Json(invite)))
+}
+
+// Get a community's invite codes: all of them for the creator, their own for members
+pub async fn get_community_invites(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<CommunityInvite>>, MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = MemberService::new(db);
+    let invites = service.get_invites(uuid, user_id).await?;
+
+    Ok(Json(invites))
+}
+
+// Revoke an invite code (its maker or the creator)
+pub async fn revoke_community_invite(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, code)): Path<(String, String)>,
+) -> Result<Json<CommunityInvite>, MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = MemberService::new(db);
+    let invite = service.revoke_invite(uuid, user_id, &code).await?;
+
+    Ok(Json(invite))
+}
+
+// Get how many people each member brought into a community (creator only)
+pub async fn get_community_referrals(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<ReferralStats>>, MemberHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = MemberService::new(db);
+    let stats = service.get_referral_stats(uuid, user_id).await?;
+
+    Ok(Json(stats))
+}
+
+// Get how many people the current user brought into each community
+pub async fn get_my_referrals(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<Vec<ReferralStats>>, MemberHandlerError> {
+    let service = MemberService::new(db);
+    let stats = service.get_my_referral_stats(user_id).await?;
+
+    Ok(Json(stats))
+}
@@ -0,0 +1,140 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use pulse_database::model::content::Content;
+use pulse_database::model::moderation::{CommunityModeration, HeldContent, ModerationQueueQuery, UpdateModerationDto};
+use pulse_database::model::pagination::Page;
+use pulse_service::moderation_service::ModerationServiceError;
+use pulse_service::ModerationService;
+use std::sync::Arc;
+use uuid::Uuid;
+use pulse_database::connection::Database;
+
+use crate::auth::CurrentUser;
+use crate::community_handler::CommunityHandlerError;
+use crate::content_handler::ContentHandlerError;
+
+// Error handling for moderation handlers
+pub enum ModerationHandlerError {
+    Service(ModerationServiceError),
+    InvalidUuid,
+}
+
+// Convert ModerationHandlerError to StatusCode and message
+impl axum::response::IntoResponse for ModerationHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            // Errors from other services answer the way their own handlers do
+            ModerationHandlerError::Service(ModerationServiceError::Community(err)) => {
+                return CommunityHandlerError::Service(err).into_response();
+            },
+            ModerationHandlerError::Service(ModerationServiceError::Content(err)) => {
+                return ContentHandlerError::Service(err).into_response();
+            },
+            ModerationHandlerError::Service(err) => {
+                let status = match &err {
+                    ModerationServiceError::NotFound => StatusCode::NOT_FOUND,
+                    ModerationServiceError::InvalidCursor
+                    | ModerationServiceError::TooManyEntries(_) => StatusCode::BAD_REQUEST,
+                    ModerationServiceError::Forbidden => StatusCode::FORBIDDEN,
+                    ModerationServiceError::AlreadyReviewed => StatusCode::CONFLICT,
+                    ModerationServiceError::Community(_)
+                    | ModerationServiceError::Content(_)
+                    | ModerationServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            ModerationHandlerError::InvalidUuid => {
+                (StatusCode::BAD_REQUEST, "Invalid UUID format".to_string())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to ModerationHandlerError
+impl From<ModerationServiceError> for ModerationHandlerError {
+    fn from(err: ModerationServiceError) -> Self {
+        ModerationHandlerError::Service(err)
+    }
+}
+
+impl From<uuid::Error> for ModerationHandlerError {
+    fn from(_: uuid::Error) -> Self {
+        ModerationHandlerError::InvalidUuid
+    }
+}
+
+// Get a community's moderation rules (creator only)
+pub async fn get_community_moderation(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<Json<CommunityModeration>, ModerationHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = ModerationService::new(db);
+    let rules = service.get_rules(uuid, user_id).await?;
+
+    Ok(Json(rules))
+}
+
+// Replace some of a community's moderation lists (creator only)
+pub async fn update_community_moderation(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateModerationDto>,
+) -> Result<Json<CommunityModeration>, ModerationHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = ModerationService::new(db);
+    let rules = service.update_rules(uuid, user_id, dto).await?;
+
+    Ok(Json(rules))
+}
+
+// Get the messages waiting for review in a community (creator only)
+pub async fn get_moderation_queue(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    Query(query): Query<ModerationQueueQuery>,
+) -> Result<Json<Page<HeldContent>>, ModerationHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = ModerationService::new(db);
+    let queue = service.get_queue(uuid, user_id, &query).await?;
+
+    Ok(Json(queue))
+}
+
+// Approve a held message, posting it to the community
+pub async fn approve_held_content(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<Content>), ModerationHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = ModerationService::new(db);
+    let content = service.approve(uuid, user_id).await?;
+
+    Ok((StatusCode::CREATED, Json(content)))
+}
+
+// Reject a held message
+pub async fn reject_held_content(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<Json<HeldContent>, ModerationHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = ModerationService::new(db);
+    let held = service.reject(uuid, user_id).await?;
+
+    Ok(Json(held))
+}
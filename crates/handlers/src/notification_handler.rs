@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use pulse_database::model::notification::{NotificationInbox, NotificationListQuery};
+use pulse_service::notification_service::NotificationServiceError;
+use pulse_service::NotificationService;
+use std::sync::Arc;
+use pulse_database::connection::Database;
+use crate::auth::CurrentUser;
+
+// Error handling for notification handlers
+pub enum NotificationHandlerError {
+    Service(NotificationServiceError),
+    NotFound,
+}
+
+// Convert NotificationHandlerError to StatusCode and message
+impl axum::response::IntoResponse for NotificationHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            NotificationHandlerError::Service(err) => {
+                let status = match &err {
+                    NotificationServiceError::InvalidUuid
+                    | NotificationServiceError::InvalidCursor => StatusCode::BAD_REQUEST,
+                    NotificationServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            NotificationHandlerError::NotFound => {
+                (StatusCode::NOT_FOUND, "Notification not found".to_string())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to NotificationHandlerError
+impl From<NotificationServiceError> for NotificationHandlerError {
+    fn from(err: NotificationServiceError) -> Self {
+        NotificationHandlerError::Service(err)
+    }
+}
+
+// Get the caller's notifications, newest first
+pub async fn get_my_notifications(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Query(query): Query<NotificationListQuery>,
+) -> Result<Json<NotificationInbox>, NotificationHandlerError> {
+    let service = NotificationService::new(db);
+    let inbox = service.get_inbox(user_id, &query).await?;
+
+    Ok(Json(inbox))
+}
+
+// Mark one of the caller's notifications as read
+pub async fn mark_notification_read(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, NotificationHandlerError> {
+    let service = NotificationService::new(db);
+    if !service.mark_read(user_id, id).await? {
+        return Err(NotificationHandlerError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Mark all of the caller's notifications as read
+pub async fn mark_all_notifications_read(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<serde_json::Value>, NotificationHandlerError> {
+    let service = NotificationService::new(db);
+    let marked = service.mark_all_read(user_id).await?;
+
+    Ok(Json(serde_json::json!({ "marked": marked })))
+}
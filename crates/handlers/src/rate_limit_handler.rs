@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    Json,
+};
+use pulse_database::model::rate_limit::CommunityRateLimits;
+use pulse_service::rate_limit_service::{retry_after_secs, RateLimitServiceError};
+use pulse_service::{RateLimitService, RateLimitStore};
+use std::sync::Arc;
+use uuid::Uuid;
+use pulse_database::connection::Database;
+
+use crate::auth::CurrentUser;
+use crate::community_handler::CommunityHandlerError;
+
+// Error handling for rate limit handlers
+pub enum RateLimitHandlerError {
+    Service(RateLimitServiceError),
+    InvalidUuid,
+}
+
+// Convert RateLimitHandlerError to StatusCode and message
+impl axum::response::IntoResponse for RateLimitHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            RateLimitHandlerError::Service(RateLimitServiceError::Community(err)) => {
+                return CommunityHandlerError::Service(err).into_response();
+            },
+            // Clients are told when to come back as well as why
+            RateLimitHandlerError::Service(err @ RateLimitServiceError::Limited(retry_after)) => {
+                let seconds = retry_after_secs(retry_after);
+                let body = Json(serde_json::json!({
+                    "error": err.to_string(),
+                    "retryAfter": seconds
+                }));
+                return (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, seconds.to_string())], body).into_response();
+            },
+            RateLimitHandlerError::Service(err) => {
+                let status = match &err {
+                    RateLimitServiceError::InvalidLimits => StatusCode::BAD_REQUEST,
+                    RateLimitServiceError::Forbidden => StatusCode::FORBIDDEN,
+                    RateLimitServiceError::Limited(_) => StatusCode::TOO_MANY_REQUESTS,
+                    RateLimitServiceError::Community(_)
+                    | RateLimitServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            RateLimitHandlerError::InvalidUuid => {
+                (StatusCode::BAD_REQUEST, "Invalid UUID format".to_string())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to RateLimitHandlerError
+impl From<RateLimitServiceError> for RateLimitHandlerError {
+    fn from(err: RateLimitServiceError) -> Self {
+        RateLimitHandlerError::Service(err)
+    }
+}
+
+impl From<uuid::Error> for RateLimitHandlerError {
+    fn from(_: uuid::Error) -> Self {
+        RateLimitHandlerError::InvalidUuid
+    }
+}
+
+// Get a community's posting limits
+pub async fn get_community_rate_limits(
+    State(db): State<Arc<Database>>,
+    State(store): State<Arc<dyn RateLimitStore>>,
+    Path(id): Path<String>,
+) -> Result<Json<CommunityRateLimits>, RateLimitHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = RateLimitService::new(db, store);
+    let limits = service.get_limits(uuid).await?;
+
+    Ok(Json(limits))
+}
+
+// Replace a community's posting limits (creator only)
+pub async fn update_community_rate_limits(
+    State(db): State<Arc<Database>>,
+    State(store): State<Arc<dyn RateLimitStore>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    Json(limits): Json<CommunityRateLimits>,
+) -> Result<Json<CommunityRateLimits>, RateLimitHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = RateLimitService::new(db, store);
+    let limits = service.update_limits(uuid, user_id, limits).await?;
+
+    Ok(Json(limits))
+}
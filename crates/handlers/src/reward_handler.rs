@@ -0,0 +1,68 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    Json,
+};
+use pulse_database::model::payout::Payout;
+use pulse_database::model::reward::RewardBalance;
+use pulse_service::reward_service::RewardServiceError;
+use pulse_service::RewardService;
+use std::sync::Arc;
+use pulse_database::connection::Database;
+use crate::auth::CurrentUser;
+
+// Error handling for reward handlers
+pub enum RewardHandlerError {
+    Service(RewardServiceError),
+}
+
+// Convert RewardHandlerError to StatusCode and message
+impl axum::response::IntoResponse for RewardHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            RewardHandlerError::Service(err) => {
+                let status = match &err {
+                    RewardServiceError::NothingToClaim => StatusCode::CONFLICT,
+                    RewardServiceError::NoWallet => StatusCode::BAD_REQUEST,
+                    RewardServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to RewardHandlerError
+impl From<RewardServiceError> for RewardHandlerError {
+    fn from(err: RewardServiceError) -> Self {
+        RewardHandlerError::Service(err)
+    }
+}
+
+// Get the caller's referral rewards and what they can claim
+pub async fn get_my_rewards(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<RewardBalance>, RewardHandlerError> {
+    let service = RewardService::new(db);
+    let balance = service.get_balance(user_id).await?;
+
+    Ok(Json(balance))
+}
+
+// Pay out the caller's whole reward balance to the wallet on their account
+pub async fn claim_my_rewards(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<(StatusCode, Json<Payout>), RewardHandlerError> {
+    let service = RewardService::new(db);
+    let payout = service.claim(user_id).await?;
+
+    Ok((StatusCode::CREATED, Json(payout)))
+}
@@ -0,0 +1,175 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use pulse_database::model::pagination::Page;
+use pulse_database::model::sanction::{AuditEntry, AuditLogQuery, CreateSanctionDto, Sanction, SanctionListQuery};
+use pulse_service::sanction_service::SanctionServiceError;
+use pulse_service::SanctionService;
+use std::sync::Arc;
+use uuid::Uuid;
+use pulse_database::connection::Database;
+
+use crate::auth::CurrentUser;
+use crate::community_handler::CommunityHandlerError;
+
+// Error handling for sanction handlers
+pub enum SanctionHandlerError {
+    Service(SanctionServiceError),
+    InvalidUuid,
+}
+
+// Convert SanctionHandlerError to StatusCode and message
+impl axum::response::IntoResponse for SanctionHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            SanctionHandlerError::Service(SanctionServiceError::Community(err)) => {
+                return CommunityHandlerError::Service(err).into_response();
+            },
+            SanctionHandlerError::Service(err) => {
+                let status = match &err {
+                    SanctionServiceError::NotFound
+                    | SanctionServiceError::UserNotFound => StatusCode::NOT_FOUND,
+                    SanctionServiceError::InvalidCursor
+                    | SanctionServiceError::ReasonRequired
+                    | SanctionServiceError::InvalidExpiry => StatusCode::BAD_REQUEST,
+                    SanctionServiceError::Forbidden
+                    | SanctionServiceError::AdminOnly
+                    | SanctionServiceError::CannotSanction => StatusCode::FORBIDDEN,
+                    SanctionServiceError::AlreadyRevoked => StatusCode::CONFLICT,
+                    SanctionServiceError::Community(_)
+                    | SanctionServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+            SanctionHandlerError::InvalidUuid => {
+                (StatusCode::BAD_REQUEST, "Invalid UUID format".to_string())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to SanctionHandlerError
+impl From<SanctionServiceError> for SanctionHandlerError {
+    fn from(err: SanctionServiceError) -> Self {
+        SanctionHandlerError::Service(err)
+    }
+}
+
+impl From<uuid::Error> for SanctionHandlerError {
+    fn from(_: uuid::Error) -> Self {
+        SanctionHandlerError::InvalidUuid
+    }
+}
+
+// Ban or mute a user in a community (creator or platform admin)
+pub async fn create_community_sanction(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    Json(dto): Json<CreateSanctionDto>,
+) -> Result<(StatusCode, Json<Sanction>), SanctionHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = SanctionService::new(db);
+    let sanction = service.issue(Some(uuid), user_id, dto).await?;
+
+    Ok((StatusCode::CREATED, Json(sanction)))
+}
+
+// Get a community's sanctions (creator or platform admin)
+pub async fn get_community_sanctions(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    Query(query): Query<SanctionListQuery>,
+) -> Result<Json<Page<Sanction>>, SanctionHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = SanctionService::new(db);
+    let sanctions = service.list(Some(uuid), user_id, &query).await?;
+
+    Ok(Json(sanctions))
+}
+
+// Lift a sanction in a community (creator or platform admin)
+pub async fn revoke_community_sanction(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, sanction_id)): Path<(String, String)>,
+) -> Result<Json<Sanction>, SanctionHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let sanction_uuid = Uuid::parse_str(&sanction_id)?;
+    let service = SanctionService::new(db);
+    let sanction = service.revoke(Some(uuid), sanction_uuid, user_id).await?;
+
+    Ok(Json(sanction))
+}
+
+// Get a community's moderation audit log (creator or platform admin)
+pub async fn get_community_audit_log(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<Page<AuditEntry>>, SanctionHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = SanctionService::new(db);
+    let entries = service.audit_log(Some(uuid), user_id, &query).await?;
+
+    Ok(Json(entries))
+}
+
+// Ban or mute a user across the platform (admin only)
+pub async fn create_platform_sanction(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Json(dto): Json<CreateSanctionDto>,
+) -> Result<(StatusCode, Json<Sanction>), SanctionHandlerError> {
+    let service = SanctionService::new(db);
+    let sanction = service.issue(None, user_id, dto).await?;
+
+    Ok((StatusCode::CREATED, Json(sanction)))
+}
+
+// Get platform-wide sanctions (admin only)
+pub async fn get_platform_sanctions(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Query(query): Query<SanctionListQuery>,
+) -> Result<Json<Page<Sanction>>, SanctionHandlerError> {
+    let service = SanctionService::new(db);
+    let sanctions = service.list(None, user_id, &query).await?;
+
+    Ok(Json(sanctions))
+}
+
+// Lift a platform-wide sanction (admin only)
+pub async fn revoke_platform_sanction(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<String>,
+) -> Result<Json<Sanction>, SanctionHandlerError> {
+    let uuid = Uuid::parse_str(&id)?;
+    let service = SanctionService::new(db);
+    let sanction = service.revoke(None, uuid, user_id).await?;
+
+    Ok(Json(sanction))
+}
+
+// Get the platform-wide moderation audit log (admin only)
+pub async fn get_platform_audit_log(
+    State(db): State<Arc<Database>>,
+    CurrentUser(user_id): CurrentUser,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<Page<AuditEntry>>, SanctionHandlerError> {
+    let service = SanctionService::new(db);
+    let entries = service.audit_log(None, user_id, &query).await?;
+
+    Ok(Json(entries))
+}
@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use pulse_database::model::search::{SearchQuery, SearchResult};
+use pulse_service::search_service::SearchServiceError;
+use pulse_service::SearchService;
+use std::sync::Arc;
+use pulse_database::connection::Database;
+use crate::auth::CurrentUser;
+
+// Error handling for search handlers
+pub enum SearchHandlerError {
+    Service(SearchServiceError),
+}
+
+// Convert SearchHandlerError to StatusCode and message
+impl axum::response::IntoResponse for SearchHandlerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            SearchHandlerError::Service(err) => {
+                let status = match err {
+                    SearchServiceError::EmptyQuery
+                    | SearchServiceError::QueryTooLong => StatusCode::BAD_REQUEST,
+                    SearchServiceError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            },
+        };
+
+        let body = Json(serde_json::json!({
+            "error": message
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Convert service errors to SearchHandlerError
+impl From<SearchServiceError> for SearchHandlerError {
+    fn from(err: SearchServiceError) -> Self {
+        SearchHandlerError::Service(err)
+    }
+}
+
+// Search communities, and messages in the communities the caller can read
+pub async fn search(
+    State(db): State<Arc<Database>>,
+    Query(query): Query<SearchQuery>,
+    user: Option<CurrentUser>,
+) -> Result<Json<Vec<SearchResult>>, SearchHandlerError> {
+    let service = SearchService::new(db);
+    let results = service.search(&query, user.map(|CurrentUser(user_id)| user_id)).await?;
+
+    Ok(Json(results))
+}
@@ -0,0 +1,58 @@
+use axum::extract::FromRef;
+use pulse_database::connection::Database;
+use pulse_service::{BlobStore, EventHub, ModerationPipeline, RateLimitStore};
+use std::sync::Arc;
+
+/// Shared state for every route.
+///
+/// Handlers extract only the part they need, e.g. `State<Arc<Database>>`.
+#[derive(Clone)]
+pub struct AppState {
+    pub db: Arc<Database>,
+    pub events: EventHub,
+    pub blobs: Arc<dyn BlobStore>,
+    pub moderation: Arc<ModerationPipeline>,
+    pub rate_limits: Arc<dyn RateLimitStore>,
+}
+
+impl AppState {
+    pub fn new(
+        db: Arc<Database>,
+        events: EventHub,
+        blobs: Arc<dyn BlobStore>,
+        moderation: Arc<ModerationPipeline>,
+        rate_limits: Arc<dyn RateLimitStore>,
+    ) -> Self {
+        Self { db, events, blobs, moderation, rate_limits }
+    }
+}
+
+impl FromRef<AppState> for Arc<Database> {
+    fn from_ref(state: &AppState) -> Self {
+        state.db.clone()
+    }
+}
+
+impl FromRef<AppState> for EventHub {
+    fn from_ref(state: &AppState) -> Self {
+        state.events.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn BlobStore> {
+    fn from_ref(state: &AppState) -> Self {
+        state.blobs.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<ModerationPipeline> {
+    fn from_ref(state: &AppState) -> Self {
+        state.moderation.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn RateLimitStore> {
+    fn from_ref(state: &AppState) -> Self {
+        state.rate_limits.clone()
+    }
+}
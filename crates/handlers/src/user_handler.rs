@@ -1,9 +1,10 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
-use pulse_database::model::user::{CreateUserDto, UpdateUserDto, User};
+use pulse_database::model::pagination::Page;
+use pulse_database::model::user::{CreateUserDto, User, UserListQuery};
 use pulse_service::UserService;
 // No need for these imports
 use std::sync::Arc;
@@ -27,6 +28,12 @@ impl axum::response::IntoResponse for UserHandlerError {
                 pulse_service::user_service::UserServiceError::EmailExists => {
                     (StatusCode::CONFLICT, "Email already exists".to_string())
                 }
+                pulse_service::user_service::UserServiceError::InvalidCursor => {
+                    (StatusCode::BAD_REQUEST, "Invalid cursor".to_string())
+                }
+                pulse_service::user_service::UserServiceError::InvalidImageAsset => {
+                    (StatusCode::BAD_REQUEST, "Image asset not found".to_string())
+                }
                 pulse_service::user_service::UserServiceError::Database(e) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     format!("Database error: {}", e),
@@ -59,11 +66,12 @@ impl From<uuid::Error> for UserHandlerError {
     }
 }
 
-// Get all users
+// Get a page of users
 pub async fn get_users(
     State(db): State<Arc<Database>>,
-) -> Result<Json<Vec<User>>, UserHandlerError> {
-    let users = UserService::get_all_users(db.pool()).await?;
+    Query(query): Query<UserListQuery>,
+) -> Result<Json<Page<User>>, UserHandlerError> {
+    let users = UserService::get_users(db.pool(), &query).await?;
     Ok(Json(users))
 }
 
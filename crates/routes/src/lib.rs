@@ -1,11 +1,14 @@
 use axum::{
-    routing::{get, post, delete},
+    routing::{get, post, put, patch, delete},
     Router,
-    extract::State,
+    extract::{DefaultBodyLimit, State},
+    middleware,
 };
 use std::sync::Arc;
 use pulse_database::connection::Database;
-use pulse_handlers::{user_handler, community_handler, content_handler};
+use pulse_service::asset_service::MAX_UPLOAD_BYTES;
+use pulse_handlers::idempotency::idempotent;
+use pulse_handlers::{user_handler, asset_handler, community_handler, content_handler, search_handler, live_handler, notification_handler, follow_handler, member_handler, moderation_handler, rate_limit_handler, reward_handler, sanction_handler, AppState};
 
 // Define a simple handler function
 async fn hello_world() -> &'static str {
@@ -21,24 +24,95 @@ async fn health_check(State(db): State<Arc<Database>>) -> &'static str {
 }
 
 // Create and configure the application router
-pub fn create_router(db: Arc<Database>) -> Router {
+pub fn create_router(state: AppState) -> Router {
+    // Create endpoints that clients retry honor Idempotency-Key
+    let idempotent = middleware::from_fn_with_state(state.clone(), idempotent);
+
     Router::new()
         .route("/", get(hello_world))
         .route("/api/health", get(health_check))
+        .route("/api/time", get(live_handler::server_time))
         // User routes
         .route("/api/users", get(user_handler::get_users))
-        .route("/api/users", post(user_handler::create_user))
+        .route("/api/users", post(user_handler::create_user).layer(idempotent.clone()))
         .route("/api/users/{id}", get(user_handler::get_user))
         .route("/api/users/{id}", delete(user_handler::delete_user))
+        .route("/api/users/{id}/communities", get(community_handler::get_user_communities))
+        .route("/api/users/{id}/follow", post(follow_handler::follow_user))
+        .route("/api/users/{id}/follow", delete(follow_handler::unfollow_user))
+        // Current user routes
+        .route("/api/me/notifications", get(notification_handler::get_my_notifications))
+        .route("/api/me/notifications/read-all", post(notification_handler::mark_all_notifications_read))
+        .route("/api/me/notifications/{id}/read", post(notification_handler::mark_notification_read))
+        .route("/api/me/referrals", get(member_handler::get_my_referrals))
+        .route("/api/me/following", get(follow_handler::get_my_following))
+        .route("/api/me/feed", get(follow_handler::get_my_feed))
+        .route("/api/me/rewards", get(reward_handler::get_my_rewards))
+        .route("/api/me/rewards/claim", post(reward_handler::claim_my_rewards).layer(idempotent.clone()))
+        // Asset routes. Uploads get room for the multipart framing around the file.
+        .route("/api/assets", post(asset_handler::upload_asset).layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES + 64 * 1024)))
+        .route("/api/assets/{id}", get(asset_handler::get_asset))
+        .route("/api/assets/{id}/file", get(asset_handler::get_asset_file))
+        .route("/api/assets/{id}/thumbnail", get(asset_handler::get_asset_thumbnail))
         // Community routes
-        .route("/api/communities", post(community_handler::create_community))
+        .route("/api/communities", post(community_handler::create_community).layer(idempotent.clone()))
         .route("/api/communities", get(community_handler::get_all_communities))
+        .route("/api/communities/discover", get(community_handler::discover_communities))
+        .route("/api/communities/{id}", get(community_handler::get_community))
+        .route("/api/communities/{id}", patch(community_handler::update_community))
+        .route("/api/communities/{id}", delete(community_handler::delete_community))
+        .route("/api/communities/{id}/status", put(community_handler::update_community_status))
+        .route("/api/communities/{id}/status/history", get(community_handler::get_community_status_history))
+        .route("/api/communities/{id}/deposits", post(community_handler::create_deposit).layer(idempotent.clone()))
+        .route("/api/communities/{id}/deposits", get(community_handler::get_community_deposits))
+        .route("/api/communities/{id}/ws", get(live_handler::community_ws))
+        .route("/api/communities/{id}/presence", get(live_handler::get_community_presence))
+        .route("/api/communities/{id}/join", post(member_handler::join_community))
+        .route("/api/communities/{id}/leave", post(member_handler::leave_community))
+        .route("/api/communities/{id}/members", get(member_handler::get_community_members))
+        .route("/api/communities/{id}/members/{user_id}/approve", post(member_handler::approve_community_member))
+        .route("/api/communities/{id}/members/{user_id}", delete(member_handler::remove_community_member))
+        .route("/api/communities/{id}/invites", post(member_handler::create_community_invite))
+        .route("/api/communities/{id}/invites", get(member_handler::get_community_invites))
+        .route("/api/communities/{id}/invites/{code}", delete(member_handler::revoke_community_invite))
+        .route("/api/communities/{id}/referrals", get(member_handler::get_community_referrals))
+        .route("/api/communities/{id}/follow", post(follow_handler::follow_community))
+        .route("/api/communities/{id}/follow", delete(follow_handler::unfollow_community))
+        .route("/api/communities/{id}/moderation", get(moderation_handler::get_community_moderation))
+        .route("/api/communities/{id}/moderation", put(moderation_handler::update_community_moderation))
+        .route("/api/communities/{id}/moderation/queue", get(moderation_handler::get_moderation_queue))
+        .route("/api/communities/{id}/rate-limits", get(rate_limit_handler::get_community_rate_limits))
+        .route("/api/communities/{id}/rate-limits", put(rate_limit_handler::update_community_rate_limits))
+        .route("/api/communities/{id}/sanctions", post(sanction_handler::create_community_sanction))
+        .route("/api/communities/{id}/sanctions", get(sanction_handler::get_community_sanctions))
+        .route("/api/communities/{id}/sanctions/{sanction_id}", delete(sanction_handler::revoke_community_sanction))
+        .route("/api/communities/{id}/audit-log", get(sanction_handler::get_community_audit_log))
         // Content routes
-        .route("/api/contents", post(content_handler::create_content))
+        .route("/api/contents", post(content_handler::create_content).layer(idempotent))
         .route("/api/contents", get(content_handler::get_all_contents))
         .route("/api/contents/{id}", get(content_handler::get_content))
+        .route("/api/contents/{id}", patch(content_handler::update_content))
+        .route("/api/contents/{id}", delete(content_handler::delete_content))
+        .route("/api/contents/{id}/history", get(content_handler::get_content_history))
+        .route("/api/contents/{id}/replies", get(content_handler::get_content_replies))
+        .route("/api/contents/{id}/reactions", post(content_handler::add_content_reaction))
+        .route("/api/contents/{id}/reactions", delete(content_handler::remove_content_reaction))
+        .route("/api/contents/{id}/pin", post(content_handler::pin_content))
+        .route("/api/contents/{id}/pin", delete(content_handler::unpin_content))
         .route("/api/communities/{community_id}/contents", get(content_handler::get_community_contents))
-        .with_state(db)
+        // Moderation routes
+        .route("/api/moderation/queue/{id}/approve", post(moderation_handler::approve_held_content))
+        .route("/api/moderation/queue/{id}/reject", post(moderation_handler::reject_held_content))
+        // Platform admin routes
+        .route("/api/admin/sanctions", post(sanction_handler::create_platform_sanction))
+        .route("/api/admin/sanctions", get(sanction_handler::get_platform_sanctions))
+        .route("/api/admin/sanctions/{id}", delete(sanction_handler::revoke_platform_sanction))
+        .route("/api/admin/audit-log", get(sanction_handler::get_platform_audit_log))
+        // Event routes
+        .route("/api/events/stream", get(live_handler::events_sse))
+        // Search routes
+        .route("/api/search", get(search_handler::search))
+        .with_state(state)
 }
 
 #[cfg(test)]
@@ -47,7 +121,6 @@ mod tests {
     use axum::body::Body;
     use axum::http::{Request, StatusCode};
     use tower::ServiceExt;
-    use std::sync::Arc;
 
 
     #[tokio::test]
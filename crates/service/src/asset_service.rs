@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use pulse_database::{
+    connection::Database,
+    model::asset::{Asset, AssetVariant, ImageMime},
+    repository::AssetRepository,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::blob_store::{BlobStore, BlobStoreError};
+use crate::image_processing::{process_image, ImageProcessingError};
+
+/// Largest file accepted for upload, in bytes
+pub const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Error, Debug)]
+pub enum AssetServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    BlobStore(#[from] BlobStoreError),
+
+    #[error(transparent)]
+    Image(#[from] ImageProcessingError),
+
+    #[error("Invalid UUID format")]
+    InvalidUuid,
+
+    #[error("Files can be at most {} MB", MAX_UPLOAD_BYTES / 1024 / 1024)]
+    TooLarge,
+}
+
+/// A stored file ready to be served
+pub struct AssetBlob {
+    pub mime: ImageMime,
+    pub bytes: Vec<u8>,
+}
+
+pub struct AssetService {
+    db: Arc<Database>,
+    blobs: Arc<dyn BlobStore>,
+}
+
+impl AssetService {
+    pub fn new(db: Arc<Database>, blobs: Arc<dyn BlobStore>) -> Self {
+        Self { db, blobs }
+    }
+
+    /// Validate, clean and store an uploaded image along with its thumbnail
+    pub async fn upload(&self, owner_id: Option<Uuid>, bytes: Vec<u8>) -> Result<Asset, AssetServiceError> {
+        if bytes.len() > MAX_UPLOAD_BYTES {
+            return Err(AssetServiceError::TooLarge);
+        }
+
+        let processed = tokio::task::spawn_blocking(move || process_image(&bytes))
+            .await
+            .map_err(|e| ImageProcessingError::Invalid(e.to_string()))??;
+
+        let id = Uuid::new_v4();
+        let asset = Asset {
+            id,
+            owner_id,
+            mime_type: processed.original.mime,
+            byte_size: processed.original.bytes.len() as i64,
+            width: processed.original.width as i32,
+            height: processed.original.height as i32,
+            blob_key: Asset::blob_key_for(id),
+            thumbnail_mime_type: processed.thumbnail.mime,
+            thumbnail_key: Asset::thumbnail_key_for(id),
+            created_at: Utc::now(),
+        };
+
+        self.blobs
+            .put(&asset.blob_key, processed.original.bytes, asset.mime_type.as_str())
+            .await?;
+        self.blobs
+            .put(&asset.thumbnail_key, processed.thumbnail.bytes, asset.thumbnail_mime_type.as_str())
+            .await?;
+
+        // Without a row nothing can reach the blobs, so don't leave them behind
+        if let Err(e) = AssetRepository::create(self.db.pool(), &asset).await {
+            self.delete_blobs_best_effort(&asset).await;
+            return Err(e.into());
+        }
+
+        Ok(asset)
+    }
+
+    pub async fn get_asset(&self, id: String) -> Result<Option<Asset>, AssetServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+
+        let asset = AssetRepository::find_by_id(self.db.pool(), uuid_id).await?;
+        Ok(asset)
+    }
+
+    /// One of an asset's stored files, or `None` if the asset doesn't exist
+    pub async fn get_blob(&self, id: String, variant: AssetVariant) -> Result<Option<AssetBlob>, AssetServiceError> {
+        let Some(asset) = self.get_asset(id).await? else {
+            return Ok(None);
+        };
+
+        let (key, mime) = match variant {
+            AssetVariant::Original => (&asset.blob_key, asset.mime_type),
+            AssetVariant::Thumbnail => (&asset.thumbnail_key, asset.thumbnail_mime_type),
+        };
+        let blob = self.blobs.get(key).await?;
+
+        Ok(blob.map(|bytes| AssetBlob { mime, bytes }))
+    }
+
+    async fn delete_blobs_best_effort(&self, asset: &Asset) {
+        for key in [&asset.blob_key, &asset.thumbnail_key] {
+            if let Err(e) = self.blobs.delete(key).await {
+                eprintln!("❌ Failed to delete blob {}: {}", key, e);
+            }
+        }
+    }
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, AssetServiceError> {
+    Uuid::parse_str(id).map_err(|_| AssetServiceError::InvalidUuid)
+}
@@ -1,10 +1,76 @@
 use std::sync::Arc;
 
+use chrono::Utc;
 use pulse_database::{
     connection::Database,
-    model::community::{Community, CreateCommunityDto},
-    repository::CommunityRepository,
+    model::community::{
+        Community, CommunityListQuery, CommunityStatus, CommunityStatusTransition, CreateCommunityDto,
+        DiscoverQuery, DiscoverSort, UpdateCommunityDto,
+    },
+    model::pagination::{page_limit, Cursor, Page},
+    model::depositor::{CreateDepositDto, Depositor},
+    model::event::NewCommunityEvent,
+    model::reward::RewardShare,
+    model::sanction::SanctionKind,
+    repository::{
+        AssetRepository, CommunityRepository, ContentRepository, DepositorRepository, ReferralRepository,
+        SanctionRepository,
+    },
 };
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::event_service::EventService;
+use crate::notification_service::NotificationService;
+use crate::reward_service::referral_reward_percent;
+
+#[derive(Error, Debug)]
+pub enum CommunityServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Community not found")]
+    NotFound,
+
+    #[error("Invalid UUID format")]
+    InvalidUuid,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    #[error("Cannot move community from {from} to {to}")]
+    InvalidTransition {
+        from: CommunityStatus,
+        to: CommunityStatus,
+    },
+
+    #[error("Communities are only moved to {0} by the round engine")]
+    SetByRoundEngine(CommunityStatus),
+
+    #[error("Community is {0} and not accepting deposits")]
+    DepositsClosed(CommunityStatus),
+
+    #[error("Image asset not found")]
+    InvalidImageAsset,
+
+    #[error("Deposit amount must be positive")]
+    InvalidAmount,
+
+    #[error("Only the community creator can do this")]
+    Forbidden,
+
+    #[error("You are banned from depositing here")]
+    Banned,
+
+    #[error("{field} cannot be edited while the community is {status}")]
+    FieldNotEditable {
+        field: &'static str,
+        status: CommunityStatus,
+    },
+
+    #[error("Community is {0} and still holds funds; it cannot be deleted")]
+    CannotDelete(CommunityStatus),
+}
 
 pub struct CommunityService {
     db: Arc<Database>,
@@ -15,12 +81,16 @@ impl CommunityService {
         Self { db }
     }
 
-    pub async fn create_community(&self, user_id: String, mut dto: CreateCommunityDto) -> Result<Community, String> {
+    pub async fn create_community(&self, user_id: String, mut dto: CreateCommunityDto) -> Result<Community, CommunityServiceError> {
         // UUID 문자열을 UUID 타입으로 변환
-        let uuid_id = match uuid::Uuid::parse_str(&user_id) {
-            Ok(uuid) => uuid,
-            Err(_) => return Err("Invalid UUID format".to_string()),
-        };
+        let uuid_id = parse_uuid(&user_id)?;
+
+        // Images have to be uploaded first, by the creator
+        if let Some(asset_id) = dto.image_asset_id {
+            if !AssetRepository::is_usable_by(self.db.pool(), asset_id, Some(uuid_id)).await? {
+                return Err(CommunityServiceError::InvalidImageAsset);
+            }
+        }
 
         // 사용자의 xid 조회
         let user_xid = sqlx::query_scalar!(
@@ -28,21 +98,293 @@ impl CommunityService {
             uuid_id
         )
             .fetch_one(self.db.pool())
-            .await
-            .map_err(|e| format!("Failed to get user xid: {}", e))?;
+            .await?;
 
         // uuid_id와 xid를 각각 설정
         dto.creator_id = Some(uuid_id);
         dto.creator_xid = Some(user_xid);
 
-        CommunityRepository::create(self.db.pool(), dto)
-            .await
-            .map_err(|e| format!("Failed to create community: {}", e))
+        let community = CommunityRepository::create(self.db.pool(), dto).await?;
+        Ok(community)
+    }
+
+    pub async fn get_communities(&self, query: &CommunityListQuery) -> Result<Page<Community>, CommunityServiceError> {
+        let after = parse_cursor(query.cursor.as_deref())?;
+
+        let communities = CommunityRepository::find_page(self.db.pool(), query, after, page_limit(query.limit)).await?;
+        Ok(communities)
+    }
+
+    /// Ranked lists for the home screen
+    pub async fn discover(&self, query: &DiscoverQuery) -> Result<Page<Community>, CommunityServiceError> {
+        let pool = self.db.pool();
+        let limit = page_limit(query.limit);
+
+        let communities = match query.sort {
+            DiscoverSort::Trending => CommunityRepository::find_trending(pool, limit).await?,
+            DiscoverSort::EndingSoon => CommunityRepository::find_ending_soon(pool, limit).await?,
+            DiscoverSort::LargestBounty => CommunityRepository::find_largest_bounty(pool, limit).await?,
+            DiscoverSort::Newest => CommunityRepository::find_newest(pool, limit).await?,
+        };
+
+        // Rankings are a single top-N list, there is no next page
+        Ok(Page { items: communities, next_cursor: None })
+    }
+
+    /// Recompute trending scores; called periodically by the web server
+    pub async fn refresh_trending(&self) -> Result<(), CommunityServiceError> {
+        CommunityRepository::refresh_trending(self.db.pool()).await?;
+        Ok(())
+    }
+
+    pub async fn get_community(&self, id: Uuid) -> Result<Community, CommunityServiceError> {
+        let community = CommunityRepository::find_by_id(self.db.pool(), id).await?;
+        let community = community.ok_or(CommunityServiceError::NotFound)?;
+        self.expire_if_timed_out(community).await
+    }
+
+    pub async fn get_communities_by_creator(&self, creator_id: Uuid) -> Result<Vec<Community>, CommunityServiceError> {
+        let communities = CommunityRepository::find_by_creator_id(self.db.pool(), creator_id).await?;
+        Ok(communities)
+    }
+
+    /// Apply a creator's edits, rejecting fields that are frozen in the current status
+    pub async fn update_community(&self, id: Uuid, user_id: Uuid, dto: UpdateCommunityDto) -> Result<Community, CommunityServiceError> {
+        let community = self.get_community(id).await?;
+        if community.creator_id != user_id {
+            return Err(CommunityServiceError::Forbidden);
+        }
+
+        let editable = community.status.editable_fields();
+        if let Some(field) = dto.set_fields().into_iter().find(|field| !editable.contains(field)) {
+            return Err(CommunityServiceError::FieldNotEditable { field, status: community.status });
+        }
+
+        // Same rule for a new image as when the community was created
+        if let Some(asset_id) = dto.image_asset_id {
+            if !AssetRepository::is_usable_by(self.db.pool(), asset_id, Some(user_id)).await? {
+                return Err(CommunityServiceError::InvalidImageAsset);
+            }
+        }
+
+        CommunityRepository::update(self.db.pool(), id, dto)
+            .await?
+            .ok_or(CommunityServiceError::NotFound)
     }
 
-    pub async fn get_all_communities(&self) -> Result<Vec<Community>, String> {
-        CommunityRepository::find_all(self.db.pool())
-            .await
-            .map_err(|e| format!("Failed to get communities: {}", e))
+    /// Delete a community and everything that hangs off it
+    pub async fn delete_community(&self, id: Uuid, user_id: Uuid) -> Result<(), CommunityServiceError> {
+        let community = self.get_community(id).await?;
+        if community.creator_id != user_id {
+            return Err(CommunityServiceError::Forbidden);
+        }
+        if !community.status.can_delete() {
+            return Err(CommunityServiceError::CannotDelete(community.status));
+        }
+
+        let deleted = CommunityRepository::delete(self.db.pool(), id).await?;
+        if deleted {
+            Ok(())
+        } else {
+            Err(CommunityServiceError::NotFound)
+        }
     }
+
+    /// Move a community to a new status on its creator's behalf, if the
+    /// transition is allowed and isn't one the round engine makes
+    pub async fn transition(&self, id: Uuid, to: CommunityStatus, user_id: Uuid) -> Result<Community, CommunityServiceError> {
+        let community = self.get_community(id).await?;
+        if community.creator_id != user_id {
+            return Err(CommunityServiceError::Forbidden);
+        }
+
+        let from = community.status;
+        if !from.can_transition_to(to) {
+            return Err(CommunityServiceError::InvalidTransition { from, to });
+        }
+        if to.is_set_by_round_engine() {
+            return Err(CommunityServiceError::SetByRoundEngine(to));
+        }
+
+        self.apply_transition(id, from, to)
+            .await?
+            // The status changed underneath us; report it against the status we saw
+            .ok_or(CommunityServiceError::InvalidTransition { from, to })
+    }
+
+    /// Record a transition and announce it to live subscribers
+    async fn apply_transition(&self, id: Uuid, from: CommunityStatus, to: CommunityStatus) -> Result<Option<Community>, CommunityServiceError> {
+        let community = CommunityRepository::transition_status(self.db.pool(), id, from, to).await?;
+
+        if let Some(community) = &community {
+            self.announce_transition(community, from, to).await?;
+        }
+
+        Ok(community)
+    }
+
+    /// Publish the live event for launches, round starts and round endings.
+    /// Settlements are announced by `settle_expired_rounds`, with their payout.
+    async fn announce_transition(&self, community: &Community, from: CommunityStatus, to: CommunityStatus) -> Result<(), CommunityServiceError> {
+        let event = match to {
+            CommunityStatus::Funding if from == CommunityStatus::Draft => Some(NewCommunityEvent::community_launched(community)),
+            CommunityStatus::Live => Some(NewCommunityEvent::round_started(community)),
+            CommunityStatus::Expired => {
+                let last_content = ContentRepository::find_round_winner(self.db.pool(), community.id).await?;
+                if let Some(winning) = &last_content {
+                    if let Err(e) = NotificationService::new(self.db.clone()).notify_round_won(community, winning).await {
+                        eprintln!("❌ Failed to notify round winner: {}", e);
+                    }
+                }
+                Some(NewCommunityEvent::round_ended(community, last_content.as_ref()))
+            }
+            _ => None,
+        };
+
+        if let Some(event) = event {
+            EventService::new(self.db.clone()).publish_best_effort(event).await;
+        }
+
+        Ok(())
+    }
+
+    /// Expire every live round whose timer has run out.
+    ///
+    /// Rounds are also expired lazily on load; this sweep makes sure
+    /// subscribers hear about the end of a round even if nobody loads it.
+    pub async fn expire_due_rounds(&self) -> Result<usize, CommunityServiceError> {
+        let due = CommunityRepository::find_due_for_expiry(self.db.pool(), 100).await?;
+        let count = due.len();
+
+        for community in due {
+            self.expire_if_timed_out(community).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// Settle every round that has ended, recording the winner's payout.
+    ///
+    /// Settlement is left to this sweep rather than the creator so that a
+    /// round is always paid out once, as soon as it is over.
+    pub async fn settle_expired_rounds(&self) -> Result<usize, CommunityServiceError> {
+        let expired = CommunityRepository::find_due_for_settlement(self.db.pool(), 100).await?;
+        let mut count = 0;
+
+        for community in expired {
+            let winning = ContentRepository::find_round_winner(self.db.pool(), community.id).await?;
+            let settled = CommunityRepository::settle_round(self.db.pool(), community.id, winning.as_ref(), Utc::now()).await?;
+
+            if let Some((community, winnings)) = settled {
+                EventService::new(self.db.clone())
+                    .publish_best_effort(NewCommunityEvent::round_settled(&community, winnings.as_ref()))
+                    .await;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    pub async fn get_status_history(&self, id: Uuid) -> Result<Vec<CommunityStatusTransition>, CommunityServiceError> {
+        // Make sure the community exists so a typo'd id is a 404 rather than an empty list
+        CommunityRepository::find_by_id(self.db.pool(), id)
+            .await?
+            .ok_or(CommunityServiceError::NotFound)?;
+
+        let transitions = CommunityRepository::find_status_transitions(self.db.pool(), id).await?;
+        Ok(transitions)
+    }
+
+    /// Expire a live community whose round timer has run out.
+    ///
+    /// Rounds are expired lazily whenever the community is loaded, so no
+    /// background job is needed for the status to be accurate.
+    ///
+    /// This is the only way a round ends, so the winner is always whoever
+    /// posted last before the deadline.
+    pub async fn expire_if_timed_out(&self, community: Community) -> Result<Community, CommunityServiceError> {
+        if community.status != CommunityStatus::Live {
+            return Ok(community);
+        }
+
+        match community.round_deadline() {
+            Some(deadline) if deadline <= Utc::now() => {}
+            _ => return Ok(community),
+        }
+
+        // The repository re-checks the deadline, so a message that got in
+        // first keeps the round alive
+        let expired = CommunityRepository::expire_round(self.db.pool(), community.id, Utc::now()).await?;
+
+        match expired {
+            Some(expired) => {
+                self.announce_transition(&expired, CommunityStatus::Live, CommunityStatus::Expired).await?;
+                Ok(expired)
+            }
+            // Someone else already moved it on; return whatever it is now
+            None => CommunityRepository::find_by_id(self.db.pool(), community.id)
+                .await?
+                .ok_or(CommunityServiceError::NotFound),
+        }
+    }
+
+    pub async fn create_deposit(&self, community_id: Uuid, user_id: Uuid, mut dto: CreateDepositDto) -> Result<Depositor, CommunityServiceError> {
+        if dto.amount <= rust_decimal::Decimal::ZERO {
+            return Err(CommunityServiceError::InvalidAmount);
+        }
+
+        let community = self.get_community(community_id).await?;
+        if !community.status.accepts_deposits() {
+            return Err(CommunityServiceError::DepositsClosed(community.status));
+        }
+
+        // Mutes only stop posting; banned users can't pay in either
+        let sanctions = SanctionRepository::find_active_for(self.db.pool(), user_id, community_id, Utc::now()).await?;
+        if sanctions.iter().any(|sanction| sanction.kind == SanctionKind::Ban) {
+            return Err(CommunityServiceError::Banned);
+        }
+
+        let user_xid = sqlx::query_scalar!(
+            "SELECT xid FROM users WHERE id = $1",
+            user_id
+        )
+            .fetch_one(self.db.pool())
+            .await?;
+
+        // Whoever brought the depositor in earns a share of the platform fee
+        let reward = ReferralRepository::find_referrer(self.db.pool(), community_id, user_id)
+            .await?
+            .and_then(|referrer_id| {
+                RewardShare::for_deposit(referrer_id, dto.amount, community.base_fee_percentage, referral_reward_percent())
+            });
+
+        dto.user_id = Some(user_id);
+        dto.user_xid = Some(user_xid);
+
+        let deposit = DepositorRepository::create(self.db.pool(), community_id, dto, reward).await?;
+
+        if let Some(community) = CommunityRepository::find_by_id(self.db.pool(), community_id).await? {
+            EventService::new(self.db.clone())
+                .publish_best_effort(NewCommunityEvent::pot_updated(community_id, community.bounty_amount, &deposit))
+                .await;
+        }
+
+        Ok(deposit)
+    }
+
+    pub async fn get_deposits(&self, community_id: Uuid) -> Result<Vec<Depositor>, CommunityServiceError> {
+        let deposits = DepositorRepository::find_by_community_id(self.db.pool(), community_id).await?;
+        Ok(deposits)
+    }
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, CommunityServiceError> {
+    Uuid::parse_str(id).map_err(|_| CommunityServiceError::InvalidUuid)
+}
+
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, CommunityServiceError> {
+    cursor
+        .map(|cursor| Cursor::decode(cursor).ok_or(CommunityServiceError::InvalidCursor))
+        .transpose()
 }
@@ -1,11 +1,107 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use pulse_database::{
     connection::Database,
-    model::content::{Content, CreateContentDto},
-    repository::ContentRepository,
+    model::community::{CommunityStatus, CommunityVisibility},
+    model::content::{
+        CommunityContents, Content, ContentEdit, ContentListQuery, CreateContentDto, ReplyListQuery, UpdateContentDto,
+        EDIT_WINDOW, MAX_PINNED_CONTENTS,
+    },
+    model::event::NewCommunityEvent,
+    model::moderation::{HeldContent, MAX_CONTENT_CHARS},
+    model::pagination::{page_limit, Cursor, Page},
+    model::reaction::{normalize_reaction, ReactionSummary},
+    model::sanction::{strictest, SanctionKind},
+    repository::{
+        AssetRepository, ContentRepository, MemberRepository, ModerationRepository, ReactionRepository, SanctionRepository,
+    },
 };
 use uuid::Uuid;
+use thiserror::Error;
+
+use crate::community_service::{CommunityService, CommunityServiceError};
+use crate::event_service::EventService;
+use crate::link_preview_service::LinkPreviewService;
+use crate::moderation::{ModerationInput, ModerationPipeline, ModerationVerdict};
+use crate::notification_service::NotificationService;
+
+#[derive(Error, Debug)]
+pub enum ContentServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Invalid UUID format")]
+    InvalidUuid,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    #[error(transparent)]
+    Community(#[from] CommunityServiceError),
+
+    #[error("Community is {0} and not accepting messages")]
+    CommunityNotLive(CommunityStatus),
+
+    #[error("The round ended before the message arrived")]
+    RoundEnded,
+
+    #[error("Replied-to message not found in this community")]
+    InvalidReplyTarget,
+
+    #[error("Image asset not found")]
+    InvalidImageAsset,
+
+    #[error("Invalid reaction")]
+    InvalidReaction,
+
+    #[error("Content cannot be empty")]
+    EmptyContent,
+
+    #[error("Content cannot be longer than {} characters", MAX_CONTENT_CHARS)]
+    ContentTooLong,
+
+    #[error("Message refused: {0}")]
+    Rejected(String),
+
+    #[error("Only the sender can do this")]
+    Forbidden,
+
+    #[error("Only the community creator can do this")]
+    CreatorOnly,
+
+    #[error("A community can have at most {} pinned messages", MAX_PINNED_CONTENTS)]
+    TooManyPinned,
+
+    #[error("Only members can post in this community")]
+    NotMember,
+
+    #[error("Only members can read this community")]
+    MembersOnly,
+
+    #[error("You are banned from posting here")]
+    Banned,
+
+    #[error("You are muted{}", until(.0))]
+    Muted(Option<DateTime<Utc>>),
+
+    #[error("Messages can only be edited within {} minutes of posting", EDIT_WINDOW.num_minutes())]
+    EditWindowClosed,
+
+    #[error("Content has been deleted")]
+    ContentDeleted,
+
+    #[error("A reason is required to delete someone else's message")]
+    ReasonRequired,
+}
+
+/// What became of a message sent to a community
+#[derive(Debug)]
+pub enum PostOutcome {
+    Published(Content),
+    /// Waiting for the community's moderator
+    Held(HeldContent),
+}
 
 pub struct ContentService {
     db: Arc<Database>,
@@ -16,12 +112,18 @@ impl ContentService {
         Self { db }
     }
 
-    pub async fn create_content(&self, user_id: String, mut dto: CreateContentDto) -> Result<Content, String> {
+    /// Post a message, unless a moderation filter holds or refuses it
+    pub async fn create_content(
+        &self,
+        user_id: String,
+        mut dto: CreateContentDto,
+        moderation: &ModerationPipeline,
+    ) -> Result<PostOutcome, ContentServiceError> {
         // UUID 문자열을 UUID 타입으로 변환
-        let uuid_id = match uuid::Uuid::parse_str(&user_id) {
-            Ok(uuid) => uuid,
-            Err(_) => return Err("Invalid UUID format".to_string()),
-        };
+        let uuid_id = parse_uuid(&user_id)?;
+        check_length(&dto.content)?;
+
+        self.check_can_post(uuid_id, &dto).await?;
 
         // Try to get the user's xid from the database
         let user_xid = match sqlx::query_scalar!(
@@ -29,49 +131,430 @@ impl ContentService {
         uuid_id
     )
             .fetch_optional(self.db.pool())
-            .await {
-            Ok(Some(xid)) => xid,
-            Ok(None) => {
+            .await? {
+            Some(xid) => xid,
+            None => {
                 // If not found in database, use the user_id as the xid
                 user_id.clone()
             },
-            Err(e) => return Err(format!("Database error: {}", e)),
         };
 
         // uuid_id와 xid를 각각 설정
         dto.sender_id = Some(uuid_id);
         dto.sender_xid = Some(user_xid);
 
-        ContentRepository::create(self.db.pool(), dto)
-            .await
-            .map_err(|e| format!("Failed to create content: {}", e))
+        match self.moderate(moderation, dto.community_id, uuid_id, &dto.content).await? {
+            ModerationVerdict::Allow => Ok(PostOutcome::Published(self.publish(dto).await?)),
+            ModerationVerdict::Hold(reason) => {
+                let held = ModerationRepository::hold(self.db.pool(), &dto, &reason).await?;
+                Ok(PostOutcome::Held(held))
+            },
+            ModerationVerdict::Reject(reason) => Err(ContentServiceError::Rejected(reason)),
+        }
+    }
+
+    /// Everything a sender has to pass to post `dto` right now: a live
+    /// community, no sanction against them, membership if it is private, a
+    /// reply target in the same community and an image of their own
+    async fn check_can_post(&self, sender_id: Uuid, dto: &CreateContentDto) -> Result<(), ContentServiceError> {
+        // Only live communities accept messages
+        let community = CommunityService::new(self.db.clone())
+            .get_community(dto.community_id)
+            .await?;
+        if !community.status.accepts_content() {
+            return Err(ContentServiceError::CommunityNotLive(community.status));
+        }
+        self.check_sanctions(sender_id, dto.community_id).await?;
+        if community.visibility == CommunityVisibility::Private
+            && !MemberRepository::is_active_member(self.db.pool(), dto.community_id, sender_id).await?
+        {
+            return Err(ContentServiceError::NotMember);
+        }
+
+        // Replies have to stay inside the community they were posted in
+        if let Some(reply_to_id) = dto.reply_to_id {
+            let parent = ContentRepository::find_by_id(self.db.pool(), reply_to_id).await?;
+            if parent.is_none_or(|parent| parent.community_id != dto.community_id || parent.deleted_at.is_some()) {
+                return Err(ContentServiceError::InvalidReplyTarget);
+            }
+        }
+
+        // Images have to be uploaded first, by the sender
+        if let Some(asset_id) = dto.image_asset_id {
+            if !AssetRepository::is_usable_by(self.db.pool(), asset_id, Some(sender_id)).await? {
+                return Err(ContentServiceError::InvalidImageAsset);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Store a message that has passed moderation and announce it.
+    /// The sender has to be set on `dto`.
+    async fn publish(&self, dto: CreateContentDto) -> Result<Content, ContentServiceError> {
+        // Every message restarts the round timer; the repository decides
+        // whether it still made it into the round
+        let (content, community) = ContentRepository::create_in_round(self.db.pool(), dto)
+            .await?
+            .ok_or(ContentServiceError::RoundEnded)?;
+
+        let events = EventService::new(self.db.clone());
+        events.publish_best_effort(NewCommunityEvent::content_created(&content)).await;
+        events.publish_best_effort(NewCommunityEvent::timer_reset(&community)).await;
+
+        // The message is in either way; a missed notification is only logged
+        if let Err(e) = NotificationService::new(self.db.clone()).notify_new_content(&content).await {
+            eprintln!("❌ Failed to create notifications: {}", e);
+        }
+        self.queue_link_previews(&content).await;
+
+        Ok(content)
+    }
+
+    /// Post a held message its moderator has approved.
+    ///
+    /// The sender has to still be allowed to post. The message keeps the time
+    /// it was sent at, and since it was never a bid in the round it leaves the
+    /// timer alone, can't win the round and outbids nobody.
+    pub(crate) async fn publish_approved(&self, held: &HeldContent) -> Result<Content, ContentServiceError> {
+        let dto = held.to_create_dto();
+        self.check_can_post(held.sender_id, &dto).await?;
+
+        let content = ContentRepository::create_approved(self.db.pool(), held.id, dto, held.created_at).await?;
+
+        EventService::new(self.db.clone())
+            .publish_best_effort(NewCommunityEvent::content_created(&content))
+            .await;
+        if let Err(e) = NotificationService::new(self.db.clone()).notify_mentions(&content).await {
+            eprintln!("❌ Failed to create notifications: {}", e);
+        }
+        self.queue_link_previews(&content).await;
+
+        Ok(content)
+    }
+
+    pub async fn get_content_by_id(&self, id: String, viewer: Option<Uuid>) -> Result<Option<Content>, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+
+        let content = self.find_readable(uuid_id, viewer).await?;
+        Ok(content)
+    }
+
+    /// Direct replies to a message, oldest first, or `None` if it doesn't exist
+    pub async fn get_replies(&self, id: String, query: &ReplyListQuery, viewer: Option<Uuid>) -> Result<Option<Page<Content>>, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+        let after = parse_cursor(query.cursor.as_deref())?;
+
+        if self.find_readable(uuid_id, viewer).await?.is_none() {
+            return Ok(None);
+        }
+
+        let replies = ContentRepository::find_replies(self.db.pool(), uuid_id, after, page_limit(query.limit)).await?;
+        Ok(Some(replies))
     }
 
-    pub async fn get_content_by_id(&self, id: String) -> Result<Option<Content>, String> {
-        let uuid_id = match uuid::Uuid::parse_str(&id) {
-            Ok(uuid) => uuid,
-            Err(_) => return Err("Invalid UUID format".to_string()),
+    /// Change the text of the caller's own message, or `None` if it doesn't exist
+    pub async fn edit_content(
+        &self,
+        id: String,
+        user_id: Uuid,
+        dto: UpdateContentDto,
+        moderation: &ModerationPipeline,
+    ) -> Result<Option<Content>, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+        let text = dto.content.trim();
+        if text.is_empty() {
+            return Err(ContentServiceError::EmptyContent);
+        }
+        check_length(text)?;
+
+        let Some(content) = ContentRepository::find_by_id(self.db.pool(), uuid_id).await? else {
+            return Ok(None);
         };
+        if content.sender_id != user_id {
+            return Err(ContentServiceError::Forbidden);
+        }
+        if content.deleted_at.is_some() {
+            return Err(ContentServiceError::ContentDeleted);
+        }
+
+        let now = Utc::now();
+        if !content.editable_at(now) {
+            return Err(ContentServiceError::EditWindowClosed);
+        }
+        self.check_sanctions(user_id, content.community_id).await?;
 
-        ContentRepository::find_by_id(self.db.pool(), uuid_id)
-            .await
-            .map_err(|e| format!("Failed to get content: {}", e))
+        // The original is already up, so an edit that needs review can't wait for it
+        match self.moderate(moderation, content.community_id, user_id, text).await? {
+            ModerationVerdict::Allow => {},
+            ModerationVerdict::Hold(reason) | ModerationVerdict::Reject(reason) => {
+                return Err(ContentServiceError::Rejected(reason));
+            },
+        }
+
+        if !ContentRepository::edit(self.db.pool(), uuid_id, user_id, text, now).await? {
+            // Deleted between the read and the edit
+            return Err(ContentServiceError::ContentDeleted);
+        }
+
+        let edited = ContentRepository::find_by_id(self.db.pool(), uuid_id).await?;
+        if let Some(edited) = &edited {
+            EventService::new(self.db.clone())
+                .publish_best_effort(NewCommunityEvent::content_edited(edited))
+                .await;
+            self.queue_link_previews(edited).await;
+        }
+
+        Ok(edited)
     }
 
-    pub async fn get_all_contents(&self) -> Result<Vec<Content>, String> {
-        ContentRepository::find_all(self.db.pool())
-            .await
-            .map_err(|e| format!("Failed to get contents: {}", e))
+    /// Delete a message, leaving a tombstone. Senders can delete their own
+    /// messages; the community's creator moderates and has to say why.
+    /// Returns false if the message doesn't exist.
+    pub async fn delete_content(&self, id: String, user_id: Uuid, reason: Option<String>) -> Result<bool, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+        let reason = reason.as_deref().map(str::trim).filter(|reason| !reason.is_empty());
+
+        let Some(content) = ContentRepository::find_by_id(self.db.pool(), uuid_id).await? else {
+            return Ok(false);
+        };
+
+        let by_moderator = content.sender_id != user_id;
+        if by_moderator {
+            let community = CommunityService::new(self.db.clone())
+                .get_community(content.community_id)
+                .await?;
+            if community.creator_id != user_id {
+                return Err(ContentServiceError::Forbidden);
+            }
+            if reason.is_none() {
+                return Err(ContentServiceError::ReasonRequired);
+            }
+        }
+
+        // Deleting twice is not an error, there is just nothing to announce
+        if ContentRepository::tombstone(self.db.pool(), uuid_id, user_id, reason, Utc::now()).await? {
+            if let Some(deleted) = ContentRepository::find_by_id(self.db.pool(), uuid_id).await? {
+                EventService::new(self.db.clone())
+                    .publish_best_effort(NewCommunityEvent::content_deleted(&deleted, by_moderator))
+                    .await;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Earlier versions of a message, or `None` if it doesn't exist
+    pub async fn get_edit_history(&self, id: String, viewer: Option<Uuid>) -> Result<Option<Vec<ContentEdit>>, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+
+        let Some(content) = self.find_readable(uuid_id, viewer).await? else {
+            return Ok(None);
+        };
+        // A tombstone's history would give away what it said
+        if content.deleted_at.is_some() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let edits = ContentRepository::find_edits(self.db.pool(), uuid_id).await?;
+        Ok(Some(edits))
+    }
+
+    /// Pin a message to the top of its community (creator only).
+    /// Returns the community's pinned messages, or `None` if the message doesn't exist.
+    pub async fn pin_content(&self, id: String, user_id: Uuid) -> Result<Option<Vec<Content>>, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+
+        let Some(content) = ContentRepository::find_by_id(self.db.pool(), uuid_id).await? else {
+            return Ok(None);
+        };
+        self.check_creator(content.community_id, user_id).await?;
+        if content.deleted_at.is_some() {
+            return Err(ContentServiceError::ContentDeleted);
+        }
+
+        // Pinning twice is not an error, there is just nothing to announce
+        if !ContentRepository::is_pinned(self.db.pool(), uuid_id).await? {
+            if !ContentRepository::pin(self.db.pool(), &content, user_id, MAX_PINNED_CONTENTS, Utc::now()).await? {
+                return Err(ContentServiceError::TooManyPinned);
+            }
+            EventService::new(self.db.clone())
+                .publish_best_effort(NewCommunityEvent::pins_changed(&content, true))
+                .await;
+        }
+
+        let pinned = ContentRepository::find_pinned(self.db.pool(), content.community_id).await?;
+        Ok(Some(pinned))
+    }
+
+    /// Take a message off the top of its community (creator only).
+    /// Returns the community's pinned messages, or `None` if the message doesn't exist.
+    pub async fn unpin_content(&self, id: String, user_id: Uuid) -> Result<Option<Vec<Content>>, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+
+        let Some(content) = ContentRepository::find_by_id(self.db.pool(), uuid_id).await? else {
+            return Ok(None);
+        };
+        self.check_creator(content.community_id, user_id).await?;
+
+        if ContentRepository::unpin(self.db.pool(), uuid_id).await? {
+            EventService::new(self.db.clone())
+                .publish_best_effort(NewCommunityEvent::pins_changed(&content, false))
+                .await;
+        }
+
+        let pinned = ContentRepository::find_pinned(self.db.pool(), content.community_id).await?;
+        Ok(Some(pinned))
     }
 
-    pub async fn get_contents_by_community(&self, community_id: String) -> Result<Vec<Content>, String> {
-        let uuid_id = match uuid::Uuid::parse_str(&community_id) {
-            Ok(uuid) => uuid,
-            Err(_) => return Err("Invalid UUID format".to_string()),
+    /// Set the user's reaction to a message, or `None` if the message doesn't exist
+    pub async fn add_reaction(&self, id: String, user_id: Uuid, reaction: &str) -> Result<Option<ReactionSummary>, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+        let reaction = normalize_reaction(reaction).ok_or(ContentServiceError::InvalidReaction)?;
+
+        let Some(content) = self.find_readable(uuid_id, Some(user_id)).await? else {
+            return Ok(None);
         };
+        if content.deleted_at.is_some() {
+            return Err(ContentServiceError::ContentDeleted);
+        }
+
+        let changed = ReactionRepository::upsert(self.db.pool(), uuid_id, user_id, reaction).await?;
+        let summary = self.reaction_summary(uuid_id).await?;
+        if changed {
+            self.announce_reaction(&content, user_id, Some(reaction), &summary).await;
+        }
+
+        Ok(Some(summary))
+    }
 
-        ContentRepository::find_by_community_id(self.db.pool(), uuid_id)
-            .await
-            .map_err(|e| format!("Failed to get community contents: {}", e))
+    /// Remove the user's reaction to a message, or `None` if the message doesn't exist
+    pub async fn remove_reaction(&self, id: String, user_id: Uuid) -> Result<Option<ReactionSummary>, ContentServiceError> {
+        let uuid_id = parse_uuid(&id)?;
+
+        let Some(content) = self.find_readable(uuid_id, Some(user_id)).await? else {
+            return Ok(None);
+        };
+
+        let changed = ReactionRepository::delete(self.db.pool(), uuid_id, user_id).await?;
+        let summary = self.reaction_summary(uuid_id).await?;
+        if changed {
+            self.announce_reaction(&content, user_id, None, &summary).await;
+        }
+
+        Ok(Some(summary))
+    }
+
+    /// A message, as long as `viewer` can read the community it is in. One
+    /// they can't read is treated as missing, so its existence isn't given away.
+    async fn find_readable(&self, id: Uuid, viewer: Option<Uuid>) -> Result<Option<Content>, ContentServiceError> {
+        let Some(content) = ContentRepository::find_by_id(self.db.pool(), id).await? else {
+            return Ok(None);
+        };
+        if !MemberRepository::can_read(self.db.pool(), content.community_id, viewer).await? {
+            return Ok(None);
+        }
+        Ok(Some(content))
     }
-}
\ No newline at end of file
+
+    async fn check_creator(&self, community_id: Uuid, user_id: Uuid) -> Result<(), ContentServiceError> {
+        let community = CommunityService::new(self.db.clone())
+            .get_community(community_id)
+            .await?;
+        if community.creator_id != user_id {
+            return Err(ContentServiceError::CreatorOnly);
+        }
+        Ok(())
+    }
+
+    /// Refuse senders who are banned or muted here or across the platform
+    async fn check_sanctions(&self, user_id: Uuid, community_id: Uuid) -> Result<(), ContentServiceError> {
+        let sanctions = SanctionRepository::find_active_for(self.db.pool(), user_id, community_id, Utc::now()).await?;
+        match strictest(&sanctions) {
+            None => Ok(()),
+            Some(sanction) if sanction.kind == SanctionKind::Ban => Err(ContentServiceError::Banned),
+            Some(sanction) => Err(ContentServiceError::Muted(sanction.expires_at)),
+        }
+    }
+
+    async fn moderate(
+        &self,
+        moderation: &ModerationPipeline,
+        community_id: Uuid,
+        sender_id: Uuid,
+        content: &str,
+    ) -> Result<ModerationVerdict, ContentServiceError> {
+        let rules = ModerationRepository::find_rules(self.db.pool(), community_id)
+            .await?
+            .unwrap_or_default();
+        let input = ModerationInput { community_id, sender_id, content, rules: &rules };
+        Ok(moderation.run(&input).await)
+    }
+
+    /// Previews are extras; a message without them is still a message
+    async fn queue_link_previews(&self, content: &Content) {
+        if let Err(e) = LinkPreviewService::new(self.db.clone()).queue_links(content).await {
+            eprintln!("❌ Failed to queue link previews: {}", e);
+        }
+    }
+
+    async fn reaction_summary(&self, content_id: Uuid) -> Result<ReactionSummary, ContentServiceError> {
+        let reactions = ReactionRepository::counts(self.db.pool(), content_id).await?;
+        Ok(ReactionSummary { content_id, reactions })
+    }
+
+    async fn announce_reaction(&self, content: &Content, user_id: Uuid, reaction: Option<&str>, summary: &ReactionSummary) {
+        EventService::new(self.db.clone())
+            .publish_best_effort(NewCommunityEvent::reaction_changed(content.community_id, user_id, reaction, summary))
+            .await;
+    }
+
+    pub async fn get_contents(&self, query: &ContentListQuery, viewer: Option<Uuid>) -> Result<Page<Content>, ContentServiceError> {
+        let after = parse_cursor(query.cursor.as_deref())?;
+
+        let contents = ContentRepository::find_page(self.db.pool(), None, viewer, query, after, page_limit(query.limit)).await?;
+        Ok(contents)
+    }
+
+    /// A page of a community's messages, with its pinned messages alongside
+    pub async fn get_contents_by_community(
+        &self,
+        community_id: String,
+        query: &ContentListQuery,
+        viewer: Option<Uuid>,
+    ) -> Result<CommunityContents, ContentServiceError> {
+        let uuid_id = parse_uuid(&community_id)?;
+        let after = parse_cursor(query.cursor.as_deref())?;
+
+        let community = CommunityService::new(self.db.clone()).get_community(uuid_id).await?;
+        if !MemberRepository::can_read(self.db.pool(), community.id, viewer).await? {
+            return Err(ContentServiceError::MembersOnly);
+        }
+
+        let page = ContentRepository::find_page(self.db.pool(), Some(uuid_id), viewer, query, after, page_limit(query.limit)).await?;
+        let pinned = ContentRepository::find_pinned(self.db.pool(), uuid_id).await?;
+        Ok(CommunityContents { page, pinned })
+    }
+}
+
+fn check_length(content: &str) -> Result<(), ContentServiceError> {
+    if content.chars().count() > MAX_CONTENT_CHARS {
+        return Err(ContentServiceError::ContentTooLong);
+    }
+    Ok(())
+}
+
+/// " until <time>" for sanctions that expire, nothing for those that don't
+fn until(expires_at: &Option<DateTime<Utc>>) -> String {
+    expires_at.map(|expires_at| format!(" until {}", expires_at.to_rfc3339())).unwrap_or_default()
+}
+
+fn parse_uuid(id: &str) -> Result<uuid::Uuid, ContentServiceError> {
+    uuid::Uuid::parse_str(id).map_err(|_| ContentServiceError::InvalidUuid)
+}
+
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, ContentServiceError> {
+    cursor
+        .map(|cursor| Cursor::decode(cursor).ok_or(ContentServiceError::InvalidCursor))
+        .transpose()
+}
@@ -0,0 +1,109 @@
+use std::sync::{Arc, OnceLock};
+
+use chrono::{DateTime, Utc};
+use pulse_database::{
+    connection::Database,
+    model::event::{CommunityEvent, NewCommunityEvent},
+    repository::EventRepository,
+    sqlx::postgres::PgListener,
+};
+use rust_decimal::Decimal;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// How many events a subscriber can fall behind before it has to catch up
+/// from the database
+const HUB_CAPACITY: usize = 1024;
+
+/// Smallest deposit shown on the global ticker when `GLOBAL_DEPOSIT_THRESHOLD` isn't set
+const DEFAULT_GLOBAL_DEPOSIT_THRESHOLD: Decimal = Decimal::ONE_HUNDRED;
+
+/// How big a deposit has to be to go on the platform-wide ticker, from
+/// `GLOBAL_DEPOSIT_THRESHOLD`
+pub fn global_deposit_threshold() -> Decimal {
+    static THRESHOLD: OnceLock<Decimal> = OnceLock::new();
+    *THRESHOLD.get_or_init(|| {
+        std::env::var("GLOBAL_DEPOSIT_THRESHOLD")
+            .ok()
+            .and_then(|threshold| threshold.trim().parse::<Decimal>().ok())
+            .filter(|threshold| *threshold >= Decimal::ZERO)
+            .unwrap_or(DEFAULT_GLOBAL_DEPOSIT_THRESHOLD)
+    })
+}
+
+/// In-process fan-out of live events to WebSocket subscribers.
+///
+/// The hub is only a delivery mechanism: every event is appended to the
+/// `community_events` log first, and each instance's listener feeds it into
+/// its own hub, so a subscriber that lags or reconnects can always catch up
+/// from the database.
+#[derive(Clone)]
+pub struct EventHub {
+    sender: broadcast::Sender<Arc<CommunityEvent>>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(HUB_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<CommunityEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Deliver an event to everyone currently subscribed
+    pub fn dispatch(&self, event: CommunityEvent) {
+        // No subscribers is not an error, the event is still in the log
+        let _ = self.sender.send(Arc::new(event));
+    }
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct EventService {
+    db: Arc<Database>,
+}
+
+impl EventService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn publish(&self, event: NewCommunityEvent) -> Result<CommunityEvent, sqlx::Error> {
+        EventRepository::append(self.db.pool(), event).await
+    }
+
+    /// Publish an event from a path whose main write has already committed.
+    ///
+    /// Failing the request at this point would make the client retry an
+    /// action that already happened, so the error is only logged.
+    pub async fn publish_best_effort(&self, event: NewCommunityEvent) {
+        let event_type = event.event_type;
+        if let Err(e) = self.publish(event).await {
+            eprintln!("❌ Failed to publish {} event: {}", event_type.as_str(), e);
+        }
+    }
+
+    pub async fn events_after(&self, community_id: Option<Uuid>, after_id: i64, limit: i64) -> Result<Vec<CommunityEvent>, sqlx::Error> {
+        EventRepository::find_after(self.db.pool(), community_id, after_id, limit).await
+    }
+
+    /// Open a listener notified with the id of every event appended by any instance
+    pub async fn listen(&self) -> Result<PgListener, sqlx::Error> {
+        EventRepository::listen(self.db.pool()).await
+    }
+
+    pub async fn latest_event_id(&self, community_id: Option<Uuid>) -> Result<i64, sqlx::Error> {
+        EventRepository::latest_id(self.db.pool(), community_id).await
+    }
+
+    /// Drop events that are too old to resume from
+    pub async fn prune(&self, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        EventRepository::delete_older_than(self.db.pool(), before).await
+    }
+}
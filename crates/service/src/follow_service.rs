@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use pulse_database::{
+    connection::Database,
+    model::community::CommunityVisibility,
+    model::feed::{FeedItem, FeedQuery},
+    model::follow::{Following, MAX_FOLLOWS},
+    model::pagination::{page_limit, Cursor, Page},
+    repository::{CommunityRepository, ContentRepository, FollowRepository, MemberRepository, UserRepository},
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::community_service::{CommunityService, CommunityServiceError};
+
+#[derive(Error, Debug)]
+pub enum FollowServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Community(#[from] CommunityServiceError),
+
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("You can't follow yourself")]
+    CannotFollowSelf,
+
+    #[error("Only members can follow a private community")]
+    MembersOnly,
+
+    #[error("You can follow at most {} users and {} communities", MAX_FOLLOWS, MAX_FOLLOWS)]
+    TooManyFollows,
+
+    #[error("Not following")]
+    NotFollowing,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+}
+
+pub struct FollowService {
+    db: Arc<Database>,
+}
+
+impl FollowService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Follow another user; following them again changes nothing
+    pub async fn follow_user(&self, user_id: Uuid, followed_id: Uuid) -> Result<(), FollowServiceError> {
+        if user_id == followed_id {
+            return Err(FollowServiceError::CannotFollowSelf);
+        }
+        if UserRepository::find_by_id(self.db.pool(), followed_id).await?.is_none() {
+            return Err(FollowServiceError::UserNotFound);
+        }
+
+        let (users, _) = FollowRepository::count(self.db.pool(), user_id).await?;
+        if users >= MAX_FOLLOWS {
+            return Err(FollowServiceError::TooManyFollows);
+        }
+
+        FollowRepository::follow_user(self.db.pool(), user_id, followed_id, Utc::now()).await?;
+        Ok(())
+    }
+
+    pub async fn unfollow_user(&self, user_id: Uuid, followed_id: Uuid) -> Result<(), FollowServiceError> {
+        if FollowRepository::unfollow_user(self.db.pool(), user_id, followed_id).await? {
+            Ok(())
+        } else {
+            Err(FollowServiceError::NotFollowing)
+        }
+    }
+
+    /// Follow a community; private ones only by their members
+    pub async fn follow_community(&self, user_id: Uuid, community_id: Uuid) -> Result<(), FollowServiceError> {
+        let community = CommunityService::new(self.db.clone()).get_community(community_id).await?;
+        if community.visibility == CommunityVisibility::Private
+            && !MemberRepository::is_active_member(self.db.pool(), community_id, user_id).await?
+        {
+            return Err(FollowServiceError::MembersOnly);
+        }
+
+        let (_, communities) = FollowRepository::count(self.db.pool(), user_id).await?;
+        if communities >= MAX_FOLLOWS {
+            return Err(FollowServiceError::TooManyFollows);
+        }
+
+        FollowRepository::follow_community(self.db.pool(), user_id, community_id, Utc::now()).await?;
+        Ok(())
+    }
+
+    pub async fn unfollow_community(&self, user_id: Uuid, community_id: Uuid) -> Result<(), FollowServiceError> {
+        if FollowRepository::unfollow_community(self.db.pool(), user_id, community_id).await? {
+            Ok(())
+        } else {
+            Err(FollowServiceError::NotFollowing)
+        }
+    }
+
+    pub async fn get_following(&self, user_id: Uuid) -> Result<Following, FollowServiceError> {
+        let following = FollowRepository::find_following(self.db.pool(), user_id).await?;
+        Ok(following)
+    }
+
+    /// Messages from followed users and communities, and the results of
+    /// rounds in followed communities, newest first
+    pub async fn get_feed(&self, user_id: Uuid, query: &FeedQuery) -> Result<Page<FeedItem>, FollowServiceError> {
+        let limit = page_limit(query.limit);
+        let after = parse_cursor(query.cursor.as_deref())?;
+
+        let messages = ContentRepository::find_feed(self.db.pool(), user_id, after, limit).await?;
+        let results = CommunityRepository::find_round_results(self.db.pool(), user_id, after, limit).await?;
+
+        Ok(FeedItem::merge_page(messages, results, limit))
+    }
+}
+
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, FollowServiceError> {
+    cursor
+        .map(|cursor| Cursor::decode(cursor).ok_or(FollowServiceError::InvalidCursor))
+        .transpose()
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use pulse_database::{
+    connection::Database,
+    model::idempotency::{IdempotencyStatus, StoredResponse, IDEMPOTENCY_KEY_TTL, IN_PROGRESS_TIMEOUT},
+    repository::IdempotencyRepository,
+};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum IdempotencyServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// What to do with a request that came with an idempotency key
+#[derive(Debug)]
+pub enum IdempotencyClaim {
+    /// First time this key has been seen: handle the request, then store
+    /// its response or release the key
+    New,
+    /// The request was handled before; send back what it got then
+    Replay(StoredResponse),
+    /// The key was used for a different request
+    Mismatch,
+    /// The request that first used the key hasn't finished yet
+    InProgress,
+}
+
+pub struct IdempotencyService {
+    db: Arc<Database>,
+}
+
+impl IdempotencyService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Take a key for a request, or find out what became of the request that has it
+    pub async fn begin(&self, user_id: Uuid, key: &str, fingerprint: &str) -> Result<IdempotencyClaim, IdempotencyServiceError> {
+        let now = Utc::now();
+        let claimed = IdempotencyRepository::claim(
+            self.db.pool(),
+            user_id,
+            key,
+            fingerprint,
+            now,
+            now - IDEMPOTENCY_KEY_TTL,
+            now - IN_PROGRESS_TIMEOUT,
+        )
+        .await?;
+        if claimed {
+            return Ok(IdempotencyClaim::New);
+        }
+
+        // Released between the claim and this read: the first request failed
+        // without a response worth keeping, and the client may try again
+        let Some(record) = IdempotencyRepository::find(self.db.pool(), user_id, key).await? else {
+            return Ok(IdempotencyClaim::InProgress);
+        };
+
+        if record.fingerprint != fingerprint {
+            return Ok(IdempotencyClaim::Mismatch);
+        }
+        match (record.status, record.response_status) {
+            (IdempotencyStatus::Completed, Some(status)) => Ok(IdempotencyClaim::Replay(StoredResponse {
+                status: status as u16,
+                content_type: record.response_content_type,
+                body: record.response_body.unwrap_or_default(),
+            })),
+            _ => Ok(IdempotencyClaim::InProgress),
+        }
+    }
+
+    /// Store the response to a request so retries get it too
+    pub async fn complete(&self, user_id: Uuid, key: &str, response: &StoredResponse) -> Result<(), IdempotencyServiceError> {
+        IdempotencyRepository::complete(self.db.pool(), user_id, key, response, Utc::now()).await?;
+        Ok(())
+    }
+
+    /// Give up a key, so a retry is handled from scratch
+    pub async fn release(&self, user_id: Uuid, key: &str) -> Result<(), IdempotencyServiceError> {
+        IdempotencyRepository::release(self.db.pool(), user_id, key).await?;
+        Ok(())
+    }
+
+    /// Forget keys created before `before`
+    pub async fn prune(&self, before: DateTime<Utc>) -> Result<u64, IdempotencyServiceError> {
+        let removed = IdempotencyRepository::delete_expired(self.db.pool(), before).await?;
+        Ok(removed)
+    }
+}
+
+/// What identifies a request for idempotency: where it went and what it said
+pub fn request_fingerprint(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b" ");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
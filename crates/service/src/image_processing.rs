@@ -0,0 +1,285 @@
+use std::io::Cursor;
+
+use image::codecs::gif::{GifDecoder, GifEncoder, Repeat};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{AnimationDecoder, DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use pulse_database::model::asset::ImageMime;
+use thiserror::Error;
+
+/// Largest width or height accepted, in pixels
+pub const MAX_IMAGE_DIMENSION: u32 = 8192;
+
+/// Longest side of a thumbnail, in pixels
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+/// Most decoded pixel data an animated GIF may unpack to, in bytes
+const MAX_GIF_DECODED_BYTES: usize = 256 * 1024 * 1024;
+
+const JPEG_QUALITY: u8 = 85;
+
+#[derive(Error, Debug)]
+pub enum ImageProcessingError {
+    #[error("Only JPEG, PNG, GIF and WebP images are accepted")]
+    UnsupportedType,
+
+    #[error("Image is too large to process; at most {MAX_IMAGE_DIMENSION}x{MAX_IMAGE_DIMENSION} pixels")]
+    TooManyPixels,
+
+    #[error("Invalid image: {0}")]
+    Invalid(String),
+}
+
+impl From<image::ImageError> for ImageProcessingError {
+    fn from(err: image::ImageError) -> Self {
+        match err {
+            image::ImageError::Limits(_) => ImageProcessingError::TooManyPixels,
+            err => ImageProcessingError::Invalid(err.to_string().trim_end().to_string()),
+        }
+    }
+}
+
+/// An encoded image ready to be stored
+pub struct EncodedImage {
+    pub mime: ImageMime,
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An upload after processing: the cleaned original and its thumbnail
+pub struct ProcessedImage {
+    pub original: EncodedImage,
+    pub thumbnail: EncodedImage,
+}
+
+/// Check an upload is the image it looks like and re-encode it.
+///
+/// The type comes from the file's magic bytes alone. Decoding and encoding
+/// again drops EXIF and every other metadata block, so camera details and
+/// GPS positions never reach storage; the EXIF orientation is applied to the
+/// pixels first so photos still come out the right way up. This is CPU-bound
+/// and belongs on a blocking thread.
+pub fn process_image(bytes: &[u8]) -> Result<ProcessedImage, ImageProcessingError> {
+    let mime = ImageMime::sniff(bytes).ok_or(ImageProcessingError::UnsupportedType)?;
+
+    let (image, original) = match mime {
+        // Animated GIFs would lose their animation through `DynamicImage`
+        ImageMime::Gif => reencode_gif(bytes)?,
+        _ => {
+            let image = decode(bytes, mime)?;
+            let encoded = encode(&image, mime)?;
+            (image, encoded)
+        },
+    };
+
+    // Thumbnails keep JPEG's smaller files for photos and go lossless otherwise
+    let thumbnail_mime = match mime {
+        ImageMime::Jpeg => ImageMime::Jpeg,
+        _ => ImageMime::Png,
+    };
+    let thumbnail = encode(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE), thumbnail_mime)?;
+
+    Ok(ProcessedImage { original, thumbnail })
+}
+
+fn limits() -> Limits {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits
+}
+
+fn image_format(mime: ImageMime) -> ImageFormat {
+    match mime {
+        ImageMime::Jpeg => ImageFormat::Jpeg,
+        ImageMime::Png => ImageFormat::Png,
+        ImageMime::Gif => ImageFormat::Gif,
+        ImageMime::Webp => ImageFormat::WebP,
+    }
+}
+
+fn decode(bytes: &[u8], mime: ImageMime) -> Result<DynamicImage, ImageProcessingError> {
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), image_format(mime));
+    reader.limits(limits());
+
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+fn encode(image: &DynamicImage, mime: ImageMime) -> Result<EncodedImage, ImageProcessingError> {
+    let mut bytes = Vec::new();
+    match mime {
+        // JPEG has no alpha channel
+        ImageMime::Jpeg => image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))?,
+        ImageMime::Png => image.write_with_encoder(PngEncoder::new(&mut bytes))?,
+        // The WebP encoder only takes 8-bit RGB(A)
+        ImageMime::Webp => image
+            .to_rgba8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut bytes))?,
+        ImageMime::Gif => image
+            .to_rgba8()
+            .write_with_encoder(GifEncoder::new(&mut bytes))?,
+    }
+
+    Ok(EncodedImage {
+        mime,
+        bytes,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// Re-encode every frame of a GIF, returning the first frame for the thumbnail
+fn reencode_gif(bytes: &[u8]) -> Result<(DynamicImage, EncodedImage), ImageProcessingError> {
+    let mut decoder = GifDecoder::new(Cursor::new(bytes))?;
+    decoder.set_limits(limits())?;
+    let (width, height) = decoder.dimensions();
+
+    // Each frame is unpacked to full-size RGBA, so a long animation of
+    // small files can still need a lot of memory
+    let mut frames = Vec::new();
+    let mut decoded_bytes = 0;
+    for frame in decoder.into_frames() {
+        let frame = frame?;
+        decoded_bytes += frame.buffer().as_raw().len();
+        if decoded_bytes > MAX_GIF_DECODED_BYTES {
+            return Err(ImageProcessingError::TooManyPixels);
+        }
+        frames.push(frame);
+    }
+    let first = frames
+        .first()
+        .map(|frame| DynamicImage::ImageRgba8(frame.buffer().clone()))
+        .ok_or_else(|| ImageProcessingError::Invalid("GIF has no frames".to_string()))?;
+
+    let mut encoded = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut encoded);
+        encoder.set_repeat(Repeat::Infinite)?;
+        encoder.encode_frames(frames)?;
+    }
+
+    Ok((
+        first,
+        EncodedImage {
+            mime: ImageMime::Gif,
+            bytes: encoded,
+            width,
+            height,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([200, 40, 40])));
+        encode(&image, ImageMime::Jpeg).unwrap().bytes
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([40, 200, 40])));
+        encode(&image, ImageMime::Png).unwrap().bytes
+    }
+
+    /// Insert an APP1 EXIF segment with the given orientation and a camera
+    /// make right after the JPEG's start-of-image marker
+    fn with_exif(jpeg: &[u8], orientation: u16) -> Vec<u8> {
+        let make = b"SpyCam\0";
+        let mut tiff = Vec::new();
+        tiff.extend_from_slice(b"II*\0");
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        // Make, ASCII, stored after the IFD
+        tiff.extend_from_slice(&0x010Fu16.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        tiff.extend_from_slice(&(make.len() as u32).to_le_bytes());
+        tiff.extend_from_slice(&38u32.to_le_bytes());
+        // Orientation, SHORT, stored inline
+        tiff.extend_from_slice(&0x0112u16.to_le_bytes());
+        tiff.extend_from_slice(&3u16.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        tiff.extend_from_slice(&(orientation as u32).to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(make);
+
+        let mut segment = b"Exif\0\0".to_vec();
+        segment.extend_from_slice(&tiff);
+
+        let mut bytes = jpeg[..2].to_vec();
+        bytes.extend_from_slice(&[0xFF, 0xE1]);
+        bytes.extend_from_slice(&((segment.len() + 2) as u16).to_be_bytes());
+        bytes.extend_from_slice(&segment);
+        bytes.extend_from_slice(&jpeg[2..]);
+        bytes
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    #[test]
+    fn test_type_comes_from_content_not_claims() {
+        // Markup saved with an image extension is still markup
+        let svg = b"<svg xmlns=\"http://www.w3.org/2000/svg\"><script>alert(1)</script></svg>";
+        assert!(matches!(process_image(svg), Err(ImageProcessingError::UnsupportedType)));
+
+        // A PNG passed off as a JPEG is stored as the PNG it is
+        let processed = process_image(&png(8, 8)).unwrap();
+        assert_eq!(processed.original.mime, ImageMime::Png);
+
+        // Right magic bytes, nothing behind them
+        let mut fake = vec![0xFF, 0xD8, 0xFF, 0xE0];
+        fake.extend_from_slice(b"<html>not really a jpeg</html>");
+        assert!(matches!(process_image(&fake), Err(ImageProcessingError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_exif_is_stripped() {
+        let upload = with_exif(&jpeg(40, 20), 1);
+        assert!(contains(&upload, b"Exif"));
+        assert!(contains(&upload, b"SpyCam"));
+
+        let processed = process_image(&upload).unwrap();
+        assert_eq!(processed.original.mime, ImageMime::Jpeg);
+        assert!(!contains(&processed.original.bytes, b"Exif"));
+        assert!(!contains(&processed.original.bytes, b"SpyCam"));
+        assert!(!contains(&processed.thumbnail.bytes, b"Exif"));
+    }
+
+    #[test]
+    fn test_exif_orientation_is_applied() {
+        // Orientation 6 means the camera was turned a quarter; the stored
+        // image comes out upright with width and height swapped
+        let processed = process_image(&with_exif(&jpeg(40, 20), 6)).unwrap();
+        assert_eq!((processed.original.width, processed.original.height), (20, 40));
+    }
+
+    #[test]
+    fn test_thumbnail_dimensions() {
+        let processed = process_image(&png(1000, 500)).unwrap();
+        assert_eq!((processed.original.width, processed.original.height), (1000, 500));
+        assert_eq!((processed.thumbnail.width, processed.thumbnail.height), (THUMBNAIL_SIZE, THUMBNAIL_SIZE / 2));
+        assert_eq!(processed.thumbnail.mime, ImageMime::Png);
+
+        let processed = process_image(&jpeg(300, 960)).unwrap();
+        assert_eq!((processed.thumbnail.width, processed.thumbnail.height), (100, THUMBNAIL_SIZE));
+        assert_eq!(processed.thumbnail.mime, ImageMime::Jpeg);
+    }
+
+    #[test]
+    fn test_rejects_oversized_dimensions() {
+        let processed = process_image(&png(MAX_IMAGE_DIMENSION + 1, 1));
+        assert!(matches!(processed, Err(ImageProcessingError::TooManyPixels)));
+    }
+}
@@ -1,7 +1,42 @@
 pub mod user_service;
+pub mod asset_service;
+pub mod blob_store;
 pub mod community_service;
 pub mod content_service;
+pub mod event_service;
+pub mod follow_service;
+pub mod idempotency_service;
+pub mod image_processing;
+pub mod link_preview_service;
+pub mod member_service;
+pub mod moderation;
+pub mod moderation_service;
+pub mod notification_service;
+pub mod payout_service;
+pub mod presence_service;
+pub mod rate_limit_service;
+pub mod rate_limit_store;
+pub mod reward_service;
+pub mod sanction_service;
+pub mod search_service;
 
 pub use user_service::UserService;
+pub use asset_service::AssetService;
+pub use blob_store::{blob_store_from_env, BlobStore, LocalBlobStore, S3BlobStore};
 pub use community_service::CommunityService;
-pub use content_service::ContentService;
\ No newline at end of file
+pub use content_service::ContentService;
+pub use event_service::{EventHub, EventService};
+pub use follow_service::FollowService;
+pub use idempotency_service::IdempotencyService;
+pub use link_preview_service::LinkPreviewService;
+pub use member_service::MemberService;
+pub use moderation::{ModerationFilter, ModerationPipeline, ModerationVerdict};
+pub use moderation_service::ModerationService;
+pub use notification_service::NotificationService;
+pub use payout_service::{PayoutService, PayoutWebhook};
+pub use presence_service::PresenceService;
+pub use rate_limit_service::RateLimitService;
+pub use rate_limit_store::{rate_limit_store_from_env, MemoryRateLimitStore, RateLimitStore, RedisRateLimitStore};
+pub use reward_service::RewardService;
+pub use sanction_service::SanctionService;
+pub use search_service::SearchService;
@@ -0,0 +1,444 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use pulse_database::{
+    connection::Database,
+    model::content::Content,
+    model::link_preview::{extract_urls, parse_page_metadata, LinkPreview, MAX_LINKS_PER_MESSAGE, MAX_URL_BYTES, PREVIEW_TTL},
+    repository::LinkPreviewRepository,
+};
+use reqwest::{header, redirect, Url};
+use thiserror::Error;
+use tokio::task::JoinSet;
+
+/// Links fetched per run of the unfurler
+const UNFURL_BATCH_SIZE: i64 = 8;
+
+/// How long a claimed link can go unfinished before another unfurler takes it
+const CLAIM_TIMEOUT: chrono::Duration = chrono::Duration::minutes(2);
+
+/// Time allowed to connect to a site
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Time allowed for a whole fetch, redirects included
+const FETCH_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Most redirects followed
+const MAX_REDIRECTS: usize = 3;
+
+/// Most of a page read looking for metadata, in bytes. The tags we want are
+/// in the `<head>`, so a partial page is still useful.
+const MAX_PAGE_BYTES: usize = 512 * 1024;
+
+/// Longest title or site name kept, in characters
+const MAX_TITLE_CHARS: usize = 200;
+
+/// Longest description kept, in characters
+const MAX_DESCRIPTION_CHARS: usize = 500;
+
+const USER_AGENT: &str = "PulseBot/1.0 (link previews)";
+
+#[derive(Error, Debug)]
+pub enum LinkPreviewServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Why a link got no preview. Only logged; the link is simply marked failed.
+#[derive(Error, Debug)]
+enum FetchError {
+    #[error("invalid URL")]
+    InvalidUrl,
+
+    #[error("{0} resolves to a non-public address")]
+    Blocked(String),
+
+    #[error("could not resolve {0}")]
+    Unresolved(String),
+
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("status {0}")]
+    Status(reqwest::StatusCode),
+
+    #[error("too many redirects")]
+    TooManyRedirects,
+
+    #[error("not an HTML page")]
+    NotHtml,
+
+    #[error("timed out")]
+    Timeout,
+}
+
+pub struct LinkPreviewService {
+    db: Arc<Database>,
+}
+
+impl LinkPreviewService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Record the links in a new or edited message and queue previews for them
+    pub async fn queue_links(&self, content: &Content) -> Result<(), LinkPreviewServiceError> {
+        let mut urls: Vec<String> = Vec::new();
+        for url in extract_urls(&content.content).into_iter().filter_map(normalize_url) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls.truncate(MAX_LINKS_PER_MESSAGE);
+
+        let now = Utc::now();
+        LinkPreviewRepository::link_content(self.db.pool(), content.id, &urls, now, now - PREVIEW_TTL).await?;
+        Ok(())
+    }
+
+    /// Fetch a batch of queued links, returning how many were processed
+    pub async fn unfurl_pending(&self) -> Result<usize, LinkPreviewServiceError> {
+        let now = Utc::now();
+        let urls = LinkPreviewRepository::claim_pending(self.db.pool(), now, now - CLAIM_TIMEOUT, UNFURL_BATCH_SIZE).await?;
+        let claimed = urls.len();
+
+        let mut fetches = JoinSet::new();
+        for url in urls {
+            fetches.spawn(async move {
+                let preview = match tokio::time::timeout(FETCH_TIMEOUT, fetch_preview(&url)).await {
+                    Ok(Ok(preview)) => preview,
+                    Ok(Err(e)) => {
+                        eprintln!("⚠️ No preview for {}: {}", url, e);
+                        None
+                    },
+                    Err(_) => {
+                        eprintln!("⚠️ No preview for {}: {}", url, FetchError::Timeout);
+                        None
+                    },
+                };
+                (url, preview)
+            });
+        }
+
+        while let Some(result) = fetches.join_next().await {
+            // A panicked fetch leaves its claim to expire and be retried
+            let Ok((url, preview)) = result else { continue };
+            LinkPreviewRepository::complete(self.db.pool(), &url, preview.as_ref(), Utc::now()).await?;
+        }
+
+        Ok(claimed)
+    }
+}
+
+/// Canonical form of a link as stored: parsed, without its fragment, and
+/// only if it is an http(s) URL with a host
+fn normalize_url(url: &str) -> Option<String> {
+    let mut url = Url::parse(url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    url.set_fragment(None);
+
+    let url = String::from(url);
+    (url.len() <= MAX_URL_BYTES).then_some(url)
+}
+
+/// Fetch a page and build its preview, or `None` if it has nothing to show.
+///
+/// Every hop is resolved and checked before connecting, and the connection is
+/// pinned to the checked address, so neither a redirect nor a DNS answer that
+/// changes between check and connect can point the fetch at an internal
+/// service.
+async fn fetch_preview(url: &str) -> Result<Option<LinkPreview>, FetchError> {
+    let mut current = Url::parse(url).map_err(|_| FetchError::InvalidUrl)?;
+
+    for _ in 0..=MAX_REDIRECTS {
+        let client = pinned_client(&current).await?;
+        let mut response = client
+            .get(current.clone())
+            .header(header::ACCEPT, "text/html,application/xhtml+xml")
+            .send()
+            .await?;
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .ok_or(FetchError::Status(response.status()))?;
+            current = redirect_target(&current, location)?;
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(FetchError::Status(response.status()));
+        }
+
+        let is_html = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| {
+                let content_type = content_type.to_ascii_lowercase();
+                content_type.starts_with("text/html") || content_type.starts_with("application/xhtml+xml")
+            });
+        if !is_html {
+            return Err(FetchError::NotHtml);
+        }
+
+        let mut page = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            let room = MAX_PAGE_BYTES - page.len();
+            page.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            if page.len() == MAX_PAGE_BYTES {
+                break;
+            }
+        }
+
+        return Ok(build_preview(url, &current, &String::from_utf8_lossy(&page)));
+    }
+
+    Err(FetchError::TooManyRedirects)
+}
+
+/// Where a redirect from `current` leads; the next hop goes through
+/// `pinned_client` like the first one
+fn redirect_target(current: &Url, location: &str) -> Result<Url, FetchError> {
+    let target = current.join(location).map_err(|_| FetchError::InvalidUrl)?;
+    if !matches!(target.scheme(), "http" | "https") {
+        return Err(FetchError::InvalidUrl);
+    }
+    Ok(target)
+}
+
+/// A client that can only reach `url`'s host at an address we have checked
+async fn pinned_client(url: &Url) -> Result<reqwest::Client, FetchError> {
+    let host = url.host_str().ok_or(FetchError::InvalidUrl)?.to_string();
+    let port = url.port_or_known_default().ok_or(FetchError::InvalidUrl)?;
+
+    // IP literals come back unchanged; names go through DNS
+    let lookup_host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((lookup_host, port))
+        .await
+        .map_err(|_| FetchError::Unresolved(host.clone()))?
+        .collect();
+
+    // One private answer is enough to refuse: which one the client would use is not ours to pick
+    if addresses.iter().any(|address| !is_public_address(address.ip())) {
+        return Err(FetchError::Blocked(host));
+    }
+    let address = *addresses.first().ok_or_else(|| FetchError::Unresolved(host.clone()))?;
+
+    let client = reqwest::Client::builder()
+        .redirect(redirect::Policy::none())
+        .no_proxy()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(USER_AGENT)
+        .resolve(&host, address)
+        .build()?;
+    Ok(client)
+}
+
+/// Whether an address is on the public internet, as opposed to loopback,
+/// private, link-local, shared, reserved or otherwise special ranges
+fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => is_public_ipv4(address),
+        IpAddr::V6(address) => is_public_ipv6(address),
+    }
+}
+
+fn is_public_ipv4(address: Ipv4Addr) -> bool {
+    let [a, b, c, _] = address.octets();
+    !(address.is_unspecified()
+        || address.is_loopback()
+        || address.is_private()
+        || address.is_link_local()
+        || address.is_broadcast()
+        || address.is_multicast()
+        || address.is_documentation()
+        // 0.0.0.0/8 "this network"
+        || a == 0
+        // 100.64.0.0/10 carrier-grade NAT
+        || (a == 100 && (64..128).contains(&b))
+        // 192.0.0.0/24 protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // 198.18.0.0/15 benchmarking
+        || (a == 198 && (b == 18 || b == 19))
+        // 240.0.0.0/4 reserved
+        || a >= 240)
+}
+
+fn is_public_ipv6(address: Ipv6Addr) -> bool {
+    // IPv4-mapped and -compatible addresses reach the IPv4 host they embed
+    if let Some(embedded) = address.to_ipv4() {
+        return is_public_ipv4(embedded);
+    }
+
+    let segments = address.segments();
+    !(address.is_unspecified()
+        || address.is_loopback()
+        || address.is_multicast()
+        || address.is_unique_local()
+        || address.is_unicast_link_local()
+        // 2001:db8::/32 documentation
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+        // 64:ff9b::/96 and 64:ff9b:1::/48 NAT64, which can reach IPv4 hosts
+        || (segments[0] == 0x0064 && segments[1] == 0xff9b)
+        // 2002::/16 6to4, likewise
+        || segments[0] == 0x2002)
+}
+
+/// Turn a page's metadata into a preview, or `None` if there is no title to show
+fn build_preview(url: &str, final_url: &Url, html: &str) -> Option<LinkPreview> {
+    let metadata = parse_page_metadata(html);
+    let title = truncate(metadata.title?, MAX_TITLE_CHARS);
+
+    // Relative images are relative to where we ended up after redirects
+    let image_url = metadata
+        .image
+        .and_then(|image| final_url.join(&image).ok())
+        .filter(|image| matches!(image.scheme(), "http" | "https"))
+        .map(String::from)
+        .filter(|image| image.len() <= MAX_URL_BYTES);
+
+    Some(LinkPreview {
+        url: url.to_string(),
+        title: Some(title),
+        description: metadata.description.map(|description| truncate(description, MAX_DESCRIPTION_CHARS)),
+        image_url,
+        site_name: metadata.site_name.map(|site_name| truncate(site_name, MAX_TITLE_CHARS)),
+    })
+}
+
+fn truncate(text: String, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_public_ipv4_is_blocked() {
+        let blocked = [
+            "127.0.0.1",
+            "127.255.255.254",
+            "10.0.0.1",
+            "10.255.255.255",
+            "172.16.0.1",
+            "172.31.255.255",
+            "192.168.0.1",
+            "192.168.255.255",
+            "169.254.169.254",
+            "100.64.0.1",
+            "100.127.255.255",
+            "0.0.0.0",
+            "0.1.2.3",
+            "255.255.255.255",
+            "224.0.0.1",
+            "192.0.0.8",
+            "198.18.0.1",
+            "240.0.0.1",
+        ];
+        for address in blocked {
+            assert!(!is_public_ipv4(address.parse().unwrap()), "{address} should be blocked");
+        }
+    }
+
+    #[test]
+    fn test_public_ipv4_is_allowed() {
+        let allowed = ["1.1.1.1", "8.8.8.8", "93.184.216.34", "172.15.255.255", "172.32.0.1", "100.63.255.255", "100.128.0.1"];
+        for address in allowed {
+            assert!(is_public_ipv4(address.parse().unwrap()), "{address} should be allowed");
+        }
+    }
+
+    #[test]
+    fn test_non_public_ipv6_is_blocked() {
+        let blocked = [
+            "::",
+            "::1",
+            "fc00::1",
+            "fd12:3456:789a::1",
+            "fe80::1",
+            "febf:ffff::1",
+            "ff02::1",
+            "::ffff:127.0.0.1",
+            "::ffff:10.0.0.1",
+            "::ffff:169.254.169.254",
+            "::127.0.0.1",
+            "2001:db8::1",
+            "64:ff9b::7f00:1",
+            "2002:7f00:1::1",
+        ];
+        for address in blocked {
+            assert!(!is_public_ipv6(address.parse().unwrap()), "{address} should be blocked");
+        }
+    }
+
+    #[test]
+    fn test_public_ipv6_is_allowed() {
+        let allowed = ["2606:4700:4700::1111", "2001:4860:4860::8888", "::ffff:8.8.8.8"];
+        for address in allowed {
+            assert!(is_public_ipv6(address.parse().unwrap()), "{address} should be allowed");
+        }
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        let cases = [
+            ("https://example.com/page#section", Some("https://example.com/page")),
+            ("http://Example.COM", Some("http://example.com/")),
+            ("https://example.com/a?b=c", Some("https://example.com/a?b=c")),
+            ("ftp://example.com/file", None),
+            ("file:///etc/passwd", None),
+            ("javascript:alert(1)", None),
+            ("data:text/html,hi", None),
+            ("not a url", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(normalize_url(url).as_deref(), expected, "{url}");
+        }
+
+        let too_long = format!("https://example.com/{}", "a".repeat(MAX_URL_BYTES));
+        assert_eq!(normalize_url(&too_long), None);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_client_refuses_private_hosts() {
+        let urls = [
+            "http://127.0.0.1/",
+            "http://localhost:8080/",
+            "http://10.0.0.1/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://[fe80::1]/",
+        ];
+        for url in urls {
+            let result = pinned_client(&Url::parse(url).unwrap()).await;
+            assert!(matches!(result, Err(FetchError::Blocked(_))), "{url} should be blocked");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_private_address_is_refused() {
+        let page = Url::parse("https://example.com/article").unwrap();
+
+        for location in ["http://169.254.169.254/latest/meta-data/", "http://[::ffff:10.0.0.1]:6379/", "//127.0.0.1/admin"] {
+            let target = redirect_target(&page, location).unwrap();
+            let result = pinned_client(&target).await;
+            assert!(matches!(result, Err(FetchError::Blocked(_))), "{location} should be blocked");
+        }
+
+        assert!(matches!(redirect_target(&page, "file:///etc/passwd"), Err(FetchError::InvalidUrl)));
+        assert!(matches!(redirect_target(&page, "gopher://127.0.0.1:6379/"), Err(FetchError::InvalidUrl)));
+    }
+}
@@ -0,0 +1,361 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use pulse_database::{
+    connection::Database,
+    model::community::{Community, CommunityVisibility},
+    model::invite::{generate_invite_code, CommunityInvite, CreateInviteDto, MAX_INVITE_USES},
+    model::member::{CommunityMember, JoinCommunityDto, MemberListQuery, MemberStatus},
+    model::pagination::{page_limit, Cursor, Page},
+    model::referral::ReferralStats,
+    model::sanction::SanctionKind,
+    repository::{InviteRepository, MemberRepository, ReferralRepository, SanctionRepository},
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::community_service::{CommunityService, CommunityServiceError};
+
+#[derive(Error, Debug)]
+pub enum MemberServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Community(#[from] CommunityServiceError),
+
+    #[error("Not a member of this community")]
+    NotMember,
+
+    #[error("No pending request from this user")]
+    NoRequest,
+
+    #[error("Invite not found")]
+    InviteNotFound,
+
+    #[error("Invite code is invalid, expired or used up")]
+    InvalidInvite,
+
+    #[error("Invites can be used between 1 and {} times", MAX_INVITE_USES)]
+    InvalidMaxUses,
+
+    #[error("Expiry must be in the future")]
+    InvalidExpiry,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    #[error("Only the community creator can do this")]
+    Forbidden,
+
+    #[error("Only members can do this")]
+    MembersOnly,
+
+    #[error("You are banned from this community")]
+    Banned,
+
+    #[error("The creator cannot leave or be removed from their community")]
+    CreatorCannotLeave,
+}
+
+pub struct MemberService {
+    db: Arc<Database>,
+}
+
+impl MemberService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Whether a user, or an anonymous caller as `None`, can read a
+    /// community's messages and live events
+    pub async fn can_read(&self, community_id: Uuid, user_id: Option<Uuid>) -> Result<bool, sqlx::Error> {
+        MemberRepository::can_read(self.db.pool(), community_id, user_id).await
+    }
+
+    /// Whether a user is an active member of a community
+    pub async fn is_active_member(&self, community_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        MemberRepository::is_active_member(self.db.pool(), community_id, user_id).await
+    }
+
+    /// Join a community. Public communities and valid invite codes let the
+    /// caller straight in; otherwise a private community gets a request for
+    /// its creator to approve.
+    pub async fn join(&self, community_id: Uuid, user_id: Uuid, dto: JoinCommunityDto) -> Result<CommunityMember, MemberServiceError> {
+        let community = self.community(community_id).await?;
+
+        let now = Utc::now();
+        let sanctions = SanctionRepository::find_active_for(self.db.pool(), user_id, community_id, now).await?;
+        if sanctions.iter().any(|sanction| sanction.kind == SanctionKind::Ban) {
+            return Err(MemberServiceError::Banned);
+        }
+
+        // Joining twice is not an error, and doesn't use up an invite
+        let existing = MemberRepository::find(self.db.pool(), community_id, user_id).await?;
+        if let Some(member) = existing.as_ref().filter(|member| member.status == MemberStatus::Active) {
+            return Ok(member.clone());
+        }
+
+        let invite_code = match dto.invite_code.as_deref().map(str::trim) {
+            Some(code) => {
+                let invite = InviteRepository::find_by_code(self.db.pool(), code).await?;
+                if !invite.is_some_and(|invite| invite.community_id == community_id && invite.is_usable_at(now)) {
+                    return Err(MemberServiceError::InvalidInvite);
+                }
+                Some(code)
+            },
+            None => None,
+        };
+
+        let status = if community.visibility == CommunityVisibility::Public || invite_code.is_some() {
+            MemberStatus::Active
+        } else {
+            MemberStatus::Pending
+        };
+
+        let joined = match existing {
+            // Still waiting for approval
+            Some(member) if status == MemberStatus::Pending => return Ok(member),
+            Some(_) => MemberRepository::activate(self.db.pool(), community_id, user_id, invite_code, now).await?,
+            None => MemberRepository::add(self.db.pool(), community_id, user_id, status, invite_code, now).await?,
+        };
+        if let Some(member) = joined {
+            return Ok(member);
+        }
+
+        // Either someone else's request got there first, or the invite ran
+        // out in the meantime
+        match MemberRepository::find(self.db.pool(), community_id, user_id).await? {
+            Some(member) if member.status == MemberStatus::Active || status == MemberStatus::Pending => Ok(member),
+            _ => Err(MemberServiceError::InvalidInvite),
+        }
+    }
+
+    /// Leave a community, or withdraw a request to join it
+    pub async fn leave(&self, community_id: Uuid, user_id: Uuid) -> Result<(), MemberServiceError> {
+        let community = self.community(community_id).await?;
+        if community.creator_id == user_id {
+            return Err(MemberServiceError::CreatorCannotLeave);
+        }
+
+        MemberRepository::remove(self.db.pool(), community_id, user_id)
+            .await?
+            .ok_or(MemberServiceError::NotMember)?;
+        Ok(())
+    }
+
+    /// A community's members in the order they joined. A private
+    /// community's members are only shown to its members, and pending
+    /// requests only to the creator.
+    pub async fn get_members(
+        &self,
+        community_id: Uuid,
+        user_id: Option<Uuid>,
+        query: &MemberListQuery,
+    ) -> Result<Page<CommunityMember>, MemberServiceError> {
+        let community = self.community(community_id).await?;
+        if !MemberRepository::can_read(self.db.pool(), community_id, user_id).await? {
+            return Err(MemberServiceError::MembersOnly);
+        }
+        let status = query.status.unwrap_or(MemberStatus::Active);
+        if status == MemberStatus::Pending && user_id != Some(community.creator_id) {
+            return Err(MemberServiceError::Forbidden);
+        }
+        let after = query
+            .cursor
+            .as_deref()
+            .map(|cursor| Cursor::decode(cursor).ok_or(MemberServiceError::InvalidCursor))
+            .transpose()?;
+
+        let members = MemberRepository::find_page(self.db.pool(), community_id, status, after, page_limit(query.limit)).await?;
+        Ok(members)
+    }
+
+    /// Let someone who asked to join in (creator only)
+    pub async fn approve(&self, community_id: Uuid, user_id: Uuid, member_id: Uuid) -> Result<CommunityMember, MemberServiceError> {
+        self.authorize(community_id, user_id).await?;
+
+        match MemberRepository::find(self.db.pool(), community_id, member_id).await? {
+            Some(member) if member.status == MemberStatus::Active => Ok(member),
+            Some(_) => MemberRepository::activate(self.db.pool(), community_id, member_id, None, Utc::now())
+                .await?
+                .ok_or(MemberServiceError::NoRequest),
+            None => Err(MemberServiceError::NoRequest),
+        }
+    }
+
+    /// Remove a member, or turn down a request to join (creator only).
+    /// Members who are removed can ask again; a ban keeps them out.
+    pub async fn remove(&self, community_id: Uuid, user_id: Uuid, member_id: Uuid) -> Result<(), MemberServiceError> {
+        let community = self.authorize(community_id, user_id).await?;
+        if community.creator_id == member_id {
+            return Err(MemberServiceError::CreatorCannotLeave);
+        }
+
+        MemberRepository::remove(self.db.pool(), community_id, member_id)
+            .await?
+            .ok_or(MemberServiceError::NotMember)?;
+        Ok(())
+    }
+
+    /// Make a new invite code for a community. Any member can; the people
+    /// who join through it are credited to them.
+    pub async fn create_invite(
+        &self,
+        community_id: Uuid,
+        user_id: Uuid,
+        dto: CreateInviteDto,
+    ) -> Result<CommunityInvite, MemberServiceError> {
+        self.community(community_id).await?;
+        if !MemberRepository::is_active_member(self.db.pool(), community_id, user_id).await? {
+            return Err(MemberServiceError::MembersOnly);
+        }
+
+        if dto.max_uses.is_some_and(|max_uses| !(1..=MAX_INVITE_USES).contains(&max_uses)) {
+            return Err(MemberServiceError::InvalidMaxUses);
+        }
+        let now = Utc::now();
+        if dto.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(MemberServiceError::InvalidExpiry);
+        }
+
+        let invite = InviteRepository::create(self.db.pool(), &generate_invite_code(), community_id, user_id, &dto, now).await?;
+        Ok(invite)
+    }
+
+    /// A community's invite codes that haven't been revoked. The creator
+    /// sees all of them, other members their own.
+    pub async fn get_invites(&self, community_id: Uuid, user_id: Uuid) -> Result<Vec<CommunityInvite>, MemberServiceError> {
+        let community = self.community(community_id).await?;
+        let created_by = if community.creator_id == user_id {
+            None
+        } else if MemberRepository::is_active_member(self.db.pool(), community_id, user_id).await? {
+            Some(user_id)
+        } else {
+            return Err(MemberServiceError::MembersOnly);
+        };
+
+        let invites = InviteRepository::find_by_community(self.db.pool(), community_id, created_by).await?;
+        Ok(invites)
+    }
+
+    /// Stop an invite code working. Members who used it stay. The creator
+    /// can revoke any invite, other members their own.
+    pub async fn revoke_invite(&self, community_id: Uuid, user_id: Uuid, code: &str) -> Result<CommunityInvite, MemberServiceError> {
+        let community = self.community(community_id).await?;
+
+        let invite = InviteRepository::find_by_code(self.db.pool(), code)
+            .await?
+            .filter(|invite| invite.community_id == community_id)
+            .ok_or(MemberServiceError::InviteNotFound)?;
+        if invite.created_by != user_id && community.creator_id != user_id {
+            return Err(MemberServiceError::Forbidden);
+        }
+        if invite.revoked_at.is_some() {
+            return Ok(invite);
+        }
+
+        // `None` if it was revoked from another request in the meantime
+        let revoked = InviteRepository::revoke(self.db.pool(), code, Utc::now()).await?;
+        Ok(revoked.unwrap_or(invite))
+    }
+
+    /// How many people each member has brought into a community (creator only)
+    pub async fn get_referral_stats(&self, community_id: Uuid, user_id: Uuid) -> Result<Vec<ReferralStats>, MemberServiceError> {
+        self.authorize(community_id, user_id).await?;
+
+        let stats = ReferralRepository::find_stats(self.db.pool(), Some(community_id), None).await?;
+        Ok(stats)
+    }
+
+    /// How many people the user has brought into each community
+    pub async fn get_my_referral_stats(&self, user_id: Uuid) -> Result<Vec<ReferralStats>, MemberServiceError> {
+        let stats = ReferralRepository::find_stats(self.db.pool(), None, Some(user_id)).await?;
+        Ok(stats)
+    }
+
+    async fn community(&self, community_id: Uuid) -> Result<Community, MemberServiceError> {
+        let community = CommunityService::new(self.db.clone())
+            .get_community(community_id)
+            .await?;
+        Ok(community)
+    }
+
+    async fn authorize(&self, community_id: Uuid, user_id: Uuid) -> Result<Community, MemberServiceError> {
+        let community = self.community(community_id).await?;
+        if community.creator_id != user_id {
+            return Err(MemberServiceError::Forbidden);
+        }
+        Ok(community)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulse_database::model::community::CreateCommunityDto;
+    use pulse_database::model::user::CreateUserDto;
+    use pulse_database::repository::UserRepository;
+
+    #[tokio::test]
+    async fn test_private_members_are_only_listed_to_members() {
+        // This test only runs if the DATABASE_URL environment variable is set
+        if std::env::var("DATABASE_URL").is_err() {
+            return;
+        }
+        let db = Arc::new(Database::new().await.expect("Database connection failed"));
+        let pool = db.pool();
+
+        let mut users = Vec::new();
+        for _ in 0..2 {
+            let name = format!("members-{}", Uuid::new_v4().simple());
+            let user = UserRepository::create(pool, CreateUserDto {
+                username: name.clone(),
+                profile_image_asset_id: None,
+                wallet_address: String::new(),
+                email: format!("{}@example.com", name),
+                password: "password".to_string(),
+            })
+                .await
+                .unwrap();
+            users.push(user.id);
+        }
+        let (creator_id, outsider_id) = (users[0], users[1]);
+
+        let community = CommunityService::new(db.clone())
+            .create_community(creator_id.to_string(), CreateCommunityDto {
+                name: "private".to_string(),
+                description: None,
+                creator_id: None,
+                creator_xid: None,
+                contract_address: None,
+                bounty_amount: None,
+                time_limit: None,
+                base_fee_percentage: None,
+                wallet_address: None,
+                image_asset_id: None,
+                visibility: Some(CommunityVisibility::Private),
+            })
+            .await
+            .unwrap();
+
+        let service = MemberService::new(db.clone());
+        let query = MemberListQuery::default();
+        let anonymous = service.get_members(community.id, None, &query).await;
+        let outsider = service.get_members(community.id, Some(outsider_id), &query).await;
+        let member = service.get_members(community.id, Some(creator_id), &query).await;
+
+        sqlx::query("DELETE FROM community_status_transitions WHERE community_id = $1").bind(community.id).execute(pool).await.unwrap();
+        sqlx::query("DELETE FROM community_members WHERE community_id = $1").bind(community.id).execute(pool).await.unwrap();
+        sqlx::query("DELETE FROM communities WHERE id = $1").bind(community.id).execute(pool).await.unwrap();
+        for user_id in users {
+            UserRepository::delete(pool, user_id).await.unwrap();
+        }
+
+        assert!(matches!(anonymous, Err(MemberServiceError::MembersOnly)));
+        assert!(matches!(outsider, Err(MemberServiceError::MembersOnly)));
+        let members = member.unwrap();
+        assert_eq!(members.items.len(), 1);
+        assert_eq!(members.items[0].user_id, creator_id);
+    }
+}
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use pulse_database::model::link_preview::extract_urls;
+use pulse_database::model::moderation::{domain_allowed, find_listed_word, longest_repeated_run, CommunityModeration, MAX_REPEATED_CHARS};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Time allowed for the classifier to answer before the message goes through
+const CLASSIFIER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What a filter decided about a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModerationVerdict {
+    /// Post it
+    Allow,
+    /// Keep it back until a moderator has looked at it
+    Hold(String),
+    /// Refuse it outright
+    Reject(String),
+}
+
+/// A message about to be posted, with the rules of its community
+pub struct ModerationInput<'a> {
+    pub community_id: Uuid,
+    pub sender_id: Uuid,
+    pub content: &'a str,
+    pub rules: &'a CommunityModeration,
+}
+
+/// One check run on every message before it is stored
+#[async_trait]
+pub trait ModerationFilter: Send + Sync {
+    async fn check(&self, input: &ModerationInput<'_>) -> ModerationVerdict;
+}
+
+/// Refuses messages with a blocked word and holds those with a held word
+pub struct WordListFilter;
+
+#[async_trait]
+impl ModerationFilter for WordListFilter {
+    async fn check(&self, input: &ModerationInput<'_>) -> ModerationVerdict {
+        if let Some(word) = find_listed_word(input.content, &input.rules.blocked_words) {
+            return ModerationVerdict::Reject(format!("contains blocked word \"{}\"", word));
+        }
+        if let Some(word) = find_listed_word(input.content, &input.rules.held_words) {
+            return ModerationVerdict::Hold(format!("contains \"{}\"", word));
+        }
+        ModerationVerdict::Allow
+    }
+}
+
+/// Holds messages linking outside the community's allowed domains, if it has any
+pub struct LinkAllowlistFilter;
+
+#[async_trait]
+impl ModerationFilter for LinkAllowlistFilter {
+    async fn check(&self, input: &ModerationInput<'_>) -> ModerationVerdict {
+        if input.rules.allowed_domains.is_empty() {
+            return ModerationVerdict::Allow;
+        }
+
+        for url in extract_urls(input.content) {
+            // A link we can't make sense of can't be shown to be allowed either
+            let host = Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string));
+            match host {
+                Some(host) if domain_allowed(&host, &input.rules.allowed_domains) => {},
+                Some(host) => return ModerationVerdict::Hold(format!("links to {}", host)),
+                None => return ModerationVerdict::Hold("contains a malformed link".to_string()),
+            }
+        }
+        ModerationVerdict::Allow
+    }
+}
+
+/// Refuses messages that are mostly one key held down
+pub struct RepeatedCharacterFilter;
+
+#[async_trait]
+impl ModerationFilter for RepeatedCharacterFilter {
+    async fn check(&self, input: &ModerationInput<'_>) -> ModerationVerdict {
+        if longest_repeated_run(input.content) >= MAX_REPEATED_CHARS {
+            return ModerationVerdict::Reject("repeats a character too many times".to_string());
+        }
+        ModerationVerdict::Allow
+    }
+}
+
+#[derive(Serialize)]
+struct ClassifierRequest<'a> {
+    content: &'a str,
+    #[serde(rename = "communityId")]
+    community_id: Uuid,
+    #[serde(rename = "senderId")]
+    sender_id: Uuid,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ClassifierVerdict {
+    Allow,
+    Hold,
+    Reject,
+}
+
+#[derive(Deserialize)]
+struct ClassifierResponse {
+    verdict: ClassifierVerdict,
+    reason: Option<String>,
+}
+
+/// Asks an external service about each message.
+///
+/// The service gets `{"content", "communityId", "senderId"}` and answers
+/// `{"verdict": "allow" | "hold" | "reject", "reason"}`. If it is down or
+/// slow the message is allowed, so an outage doesn't stop every chat.
+pub struct HttpClassifierFilter {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpClassifierFilter {
+    pub fn new(url: String) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder().timeout(CLASSIFIER_TIMEOUT).build()?;
+        Ok(Self { client, url })
+    }
+
+    async fn classify(&self, input: &ModerationInput<'_>) -> Result<ClassifierResponse, reqwest::Error> {
+        let request = ClassifierRequest {
+            content: input.content,
+            community_id: input.community_id,
+            sender_id: input.sender_id,
+        };
+        self.client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+}
+
+#[async_trait]
+impl ModerationFilter for HttpClassifierFilter {
+    async fn check(&self, input: &ModerationInput<'_>) -> ModerationVerdict {
+        match self.classify(input).await {
+            Ok(response) => {
+                let reason = response.reason.unwrap_or_else(|| "flagged by classifier".to_string());
+                match response.verdict {
+                    ClassifierVerdict::Allow => ModerationVerdict::Allow,
+                    ClassifierVerdict::Hold => ModerationVerdict::Hold(reason),
+                    ClassifierVerdict::Reject => ModerationVerdict::Reject(reason),
+                }
+            },
+            Err(e) => {
+                eprintln!("⚠️ Moderation classifier unavailable, allowing message: {}", e);
+                ModerationVerdict::Allow
+            },
+        }
+    }
+}
+
+/// The filters every message goes through, in order
+#[derive(Clone)]
+pub struct ModerationPipeline {
+    filters: Vec<Arc<dyn ModerationFilter>>,
+}
+
+impl ModerationPipeline {
+    pub fn new(filters: Vec<Arc<dyn ModerationFilter>>) -> Self {
+        Self { filters }
+    }
+
+    /// The built-in filters, plus the classifier at
+    /// `MODERATION_CLASSIFIER_URL` if one is configured
+    pub fn from_env() -> Result<Self, reqwest::Error> {
+        let mut pipeline = Self::default();
+        if let Some(url) = std::env::var("MODERATION_CLASSIFIER_URL").ok().filter(|url| !url.is_empty()) {
+            pipeline.filters.push(Arc::new(HttpClassifierFilter::new(url)?));
+        }
+        Ok(pipeline)
+    }
+
+    /// Run every filter. A rejection ends the run; otherwise the first hold wins.
+    pub async fn run(&self, input: &ModerationInput<'_>) -> ModerationVerdict {
+        let mut verdict = ModerationVerdict::Allow;
+        for filter in &self.filters {
+            match filter.check(input).await {
+                ModerationVerdict::Allow => {},
+                reject @ ModerationVerdict::Reject(_) => return reject,
+                hold @ ModerationVerdict::Hold(_) => {
+                    if verdict == ModerationVerdict::Allow {
+                        verdict = hold;
+                    }
+                },
+            }
+        }
+        verdict
+    }
+}
+
+/// The built-in filters
+impl Default for ModerationPipeline {
+    fn default() -> Self {
+        Self::new(vec![
+            Arc::new(RepeatedCharacterFilter),
+            Arc::new(WordListFilter),
+            Arc::new(LinkAllowlistFilter),
+        ])
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use pulse_database::{
+    connection::Database,
+    model::content::Content,
+    model::moderation::{
+        normalize_list, CommunityModeration, HeldContent, HeldContentStatus, ModerationQueueQuery, UpdateModerationDto,
+        MAX_LIST_ENTRIES,
+    },
+    model::pagination::{page_limit, Cursor, Page},
+    repository::ModerationRepository,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::community_service::{CommunityService, CommunityServiceError};
+use crate::content_service::{ContentService, ContentServiceError};
+
+#[derive(Error, Debug)]
+pub enum ModerationServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Community(#[from] CommunityServiceError),
+
+    #[error(transparent)]
+    Content(#[from] ContentServiceError),
+
+    #[error("Held message not found")]
+    NotFound,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    #[error("Only the community creator can moderate it")]
+    Forbidden,
+
+    #[error("This message has already been reviewed")]
+    AlreadyReviewed,
+
+    #[error("{0} cannot have more than {} entries", MAX_LIST_ENTRIES)]
+    TooManyEntries(&'static str),
+}
+
+pub struct ModerationService {
+    db: Arc<Database>,
+}
+
+impl ModerationService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// A community's moderation rules, for its creator
+    pub async fn get_rules(&self, community_id: Uuid, user_id: Uuid) -> Result<CommunityModeration, ModerationServiceError> {
+        self.authorize(community_id, user_id).await?;
+
+        let rules = ModerationRepository::find_rules(self.db.pool(), community_id)
+            .await?
+            .unwrap_or_default();
+        Ok(rules)
+    }
+
+    /// Replace whichever of a community's moderation lists are given
+    pub async fn update_rules(
+        &self,
+        community_id: Uuid,
+        user_id: Uuid,
+        dto: UpdateModerationDto,
+    ) -> Result<CommunityModeration, ModerationServiceError> {
+        let mut rules = self.get_rules(community_id, user_id).await?;
+
+        let lists = [
+            ("blockedWords", dto.blocked_words, &mut rules.blocked_words),
+            ("heldWords", dto.held_words, &mut rules.held_words),
+            ("allowedDomains", dto.allowed_domains, &mut rules.allowed_domains),
+        ];
+        for (name, update, list) in lists {
+            let Some(update) = update else { continue };
+            let update = normalize_list(&update);
+            if update.len() > MAX_LIST_ENTRIES {
+                return Err(ModerationServiceError::TooManyEntries(name));
+            }
+            *list = update;
+        }
+
+        ModerationRepository::save_rules(self.db.pool(), community_id, &rules).await?;
+        Ok(rules)
+    }
+
+    /// Messages waiting for review in a community, oldest first
+    pub async fn get_queue(
+        &self,
+        community_id: Uuid,
+        user_id: Uuid,
+        query: &ModerationQueueQuery,
+    ) -> Result<Page<HeldContent>, ModerationServiceError> {
+        self.authorize(community_id, user_id).await?;
+        let after = query
+            .cursor
+            .as_deref()
+            .map(|cursor| Cursor::decode(cursor).ok_or(ModerationServiceError::InvalidCursor))
+            .transpose()?;
+
+        let queue = ModerationRepository::find_queue(self.db.pool(), community_id, after, page_limit(query.limit)).await?;
+        Ok(queue)
+    }
+
+    /// Post a held message at the time it was sent.
+    ///
+    /// It only goes up if the sender could still post it now; otherwise,
+    /// e.g. because the round ended or they were banned since, it goes back
+    /// in the queue and the reason is returned. It never counts as a bid, so
+    /// approving a message can't extend or win a round.
+    pub async fn approve(&self, held_id: Uuid, user_id: Uuid) -> Result<Content, ModerationServiceError> {
+        let held = self.claim(held_id, user_id, HeldContentStatus::Approved).await?;
+
+        match ContentService::new(self.db.clone()).publish_approved(&held).await {
+            Ok(content) => Ok(content),
+            Err(e) => {
+                ModerationRepository::reopen(self.db.pool(), held_id).await?;
+                Err(e.into())
+            },
+        }
+    }
+
+    /// Turn a held message down. The sender is not told.
+    pub async fn reject(&self, held_id: Uuid, user_id: Uuid) -> Result<HeldContent, ModerationServiceError> {
+        self.claim(held_id, user_id, HeldContentStatus::Rejected).await?;
+
+        ModerationRepository::find_held(self.db.pool(), held_id)
+            .await?
+            .ok_or(ModerationServiceError::NotFound)
+    }
+
+    /// Record a decision on a pending message, so two moderators can't both act on it
+    async fn claim(&self, held_id: Uuid, user_id: Uuid, status: HeldContentStatus) -> Result<HeldContent, ModerationServiceError> {
+        let held = ModerationRepository::find_held(self.db.pool(), held_id)
+            .await?
+            .ok_or(ModerationServiceError::NotFound)?;
+        self.authorize(held.community_id, user_id).await?;
+
+        if !ModerationRepository::review(self.db.pool(), held_id, status, user_id, Utc::now()).await? {
+            return Err(ModerationServiceError::AlreadyReviewed);
+        }
+        Ok(held)
+    }
+
+    /// The community's creator is its moderator
+    async fn authorize(&self, community_id: Uuid, user_id: Uuid) -> Result<(), ModerationServiceError> {
+        let community = CommunityService::new(self.db.clone())
+            .get_community(community_id)
+            .await?;
+        if community.creator_id != user_id {
+            return Err(ModerationServiceError::Forbidden);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use pulse_database::{
+    connection::Database,
+    model::community::Community,
+    model::content::Content,
+    model::notification::{parse_mentions, NewNotification, NotificationInbox, NotificationListQuery},
+    model::pagination::{page_limit, Cursor},
+    repository::{ContentRepository, MemberRepository, NotificationRepository, UserRepository},
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum NotificationServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Invalid UUID format")]
+    InvalidUuid,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+}
+
+pub struct NotificationService {
+    db: Arc<Database>,
+}
+
+impl NotificationService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Notify the users a new message mentions, and whoever it outbid
+    pub async fn notify_new_content(&self, content: &Content) -> Result<(), sqlx::Error> {
+        let mut notifications = self.mentions(content).await?;
+
+        let before = Cursor::new(content.created_at, content.id);
+        let previous = ContentRepository::find_previous(self.db.pool(), content.community_id, before).await?;
+        if let Some(previous) = previous.filter(|previous| previous.sender_id != content.sender_id) {
+            notifications.push(NewNotification::outbid(&previous, content));
+        }
+
+        self.send_to_readers(content, notifications).await
+    }
+
+    /// Notify the users a message mentions, for messages that weren't a bid
+    pub async fn notify_mentions(&self, content: &Content) -> Result<(), sqlx::Error> {
+        let notifications = self.mentions(content).await?;
+        self.send_to_readers(content, notifications).await
+    }
+
+    async fn mentions(&self, content: &Content) -> Result<Vec<NewNotification>, sqlx::Error> {
+        let mentioned = parse_mentions(&content.content);
+        if mentioned.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let user_ids = UserRepository::find_ids_by_usernames(self.db.pool(), &mentioned).await?;
+        Ok(user_ids
+            .into_iter()
+            .filter(|user_id| *user_id != content.sender_id)
+            .map(|user_id| NewNotification::mention(user_id, content))
+            .collect())
+    }
+
+    /// Store notifications about `content`, dropping any for users who can't
+    /// read it, say after leaving a private community
+    async fn send_to_readers(&self, content: &Content, mut notifications: Vec<NewNotification>) -> Result<(), sqlx::Error> {
+        if notifications.is_empty() {
+            return Ok(());
+        }
+
+        let recipients: Vec<Uuid> = notifications.iter().map(|notification| notification.user_id).collect();
+        let readers = MemberRepository::find_readers(self.db.pool(), content.community_id, &recipients).await?;
+        notifications.retain(|notification| readers.contains(&notification.user_id));
+
+        NotificationRepository::create_many(self.db.pool(), &notifications).await
+    }
+
+    /// Tell the sender of the last message that they won the round
+    pub async fn notify_round_won(&self, community: &Community, winning: &Content) -> Result<(), sqlx::Error> {
+        NotificationRepository::create_many(self.db.pool(), &[NewNotification::round_won(community, winning)]).await
+    }
+
+    pub async fn get_inbox(&self, user_id: Uuid, query: &NotificationListQuery) -> Result<NotificationInbox, NotificationServiceError> {
+        let after = query
+            .cursor
+            .as_deref()
+            .map(|cursor| Cursor::decode(cursor).ok_or(NotificationServiceError::InvalidCursor))
+            .transpose()?;
+
+        let page = NotificationRepository::find_page(self.db.pool(), user_id, query.unread, after, page_limit(query.limit)).await?;
+        let unread_count = NotificationRepository::count_unread(self.db.pool(), user_id).await?;
+
+        Ok(NotificationInbox { page, unread_count })
+    }
+
+    /// Mark one notification as read; false if the user has no such notification
+    pub async fn mark_read(&self, user_id: Uuid, id: String) -> Result<bool, NotificationServiceError> {
+        let uuid_id = Uuid::parse_str(&id).map_err(|_| NotificationServiceError::InvalidUuid)?;
+
+        let marked = NotificationRepository::mark_read(self.db.pool(), user_id, uuid_id).await?;
+        Ok(marked)
+    }
+
+    /// Mark the whole inbox as read and return how many were unread
+    pub async fn mark_all_read(&self, user_id: Uuid) -> Result<u64, NotificationServiceError> {
+        let marked = NotificationRepository::mark_all_read(self.db.pool(), user_id).await?;
+        Ok(marked)
+    }
+}
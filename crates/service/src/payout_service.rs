@@ -0,0 +1,135 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use pulse_database::{
+    connection::Database,
+    model::payout::{Payout, PayoutKind},
+    repository::PayoutRepository,
+};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Payouts sent per run of the processor
+const PAYOUT_BATCH_SIZE: i64 = 20;
+
+/// How long a claimed payout can go unfinished before another processor takes
+/// it; a whole batch timing out at `WEBHOOK_TIMEOUT` fits inside it
+const CLAIM_TIMEOUT: chrono::Duration = chrono::Duration::minutes(5);
+
+/// How long to wait before trying a payout again after a failed attempt
+const RETRY_DELAY: chrono::Duration = chrono::Duration::minutes(5);
+
+/// Attempts made before a payout is left for someone to look at, about a
+/// day's worth at `RETRY_DELAY`
+const MAX_ATTEMPTS: i32 = 288;
+
+/// Time allowed for the payment service to accept a payout
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug)]
+pub enum PayoutServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Serialize)]
+struct PayoutRequest<'a> {
+    id: Uuid,
+    #[serde(rename = "userId")]
+    user_id: Uuid,
+    kind: PayoutKind,
+    amount: Decimal,
+    #[serde(rename = "walletAddress")]
+    wallet_address: &'a str,
+}
+
+/// Hands payouts to an external payment service.
+///
+/// The service gets `{"id", "userId", "kind", "amount", "walletAddress"}`
+/// and any 2xx answer means it has taken the payout on. A payout can be
+/// sent more than once if we lose track of an attempt, so the service has
+/// to treat `id` (also sent as `Idempotency-Key`) as the payment's identity.
+pub struct PayoutWebhook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl PayoutWebhook {
+    pub fn new(url: String) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build()?;
+        Ok(Self { client, url })
+    }
+
+    /// The payment service at `PAYOUT_WEBHOOK_URL`, if one is configured
+    pub fn from_env() -> Result<Option<Self>, reqwest::Error> {
+        std::env::var("PAYOUT_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .map(Self::new)
+            .transpose()
+    }
+
+    async fn send(&self, payout: &Payout, wallet_address: &str) -> Result<(), reqwest::Error> {
+        let request = PayoutRequest {
+            id: payout.id,
+            user_id: payout.user_id,
+            kind: payout.kind,
+            amount: payout.amount,
+            wallet_address,
+        };
+        self.client
+            .post(&self.url)
+            .header("Idempotency-Key", payout.id.to_string())
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+pub struct PayoutService {
+    db: Arc<Database>,
+}
+
+impl PayoutService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Send every payout that is due, referral rewards and round winnings
+    /// alike, and return how many were taken on by the payment service.
+    ///
+    /// A failed attempt is retried later, and a payout whose user has no
+    /// wallet yet waits for one. Referral rewards on a payout that ends up
+    /// failed go back into the user's balance.
+    pub async fn process_due(&self, webhook: &PayoutWebhook) -> Result<usize, PayoutServiceError> {
+        let now = Utc::now();
+        let payouts = PayoutRepository::claim_due(self.db.pool(), now, now + CLAIM_TIMEOUT, PAYOUT_BATCH_SIZE).await?;
+        let mut sent = 0;
+
+        for payout in payouts {
+            let Some(wallet_address) = payout.wallet_address.as_deref() else {
+                eprintln!("❌ Payout {} has no wallet address to send to", payout.id);
+                PayoutRepository::release(self.db.pool(), payout.id, Utc::now() + RETRY_DELAY, MAX_ATTEMPTS).await?;
+                continue;
+            };
+
+            match webhook.send(&payout, wallet_address).await {
+                Ok(()) => {
+                    PayoutRepository::mark_sent(self.db.pool(), payout.id, Utc::now()).await?;
+                    sent += 1;
+                },
+                Err(e) => {
+                    eprintln!("❌ Failed to send payout {}: {}", payout.id, e);
+                    PayoutRepository::release(self.db.pool(), payout.id, Utc::now() + RETRY_DELAY, MAX_ATTEMPTS).await?;
+                },
+            }
+        }
+
+        Ok(sent)
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use pulse_database::{
+    connection::Database,
+    model::event::NewCommunityEvent,
+    model::presence::{Presence, PresenceChange},
+    repository::PresenceRepository,
+};
+use uuid::Uuid;
+
+use crate::event_service::EventService;
+
+/// Connections not refreshed for this long are considered gone
+const PRESENCE_TTL: Duration = Duration::seconds(90);
+
+/// Tracks who is watching each community.
+///
+/// Presence lives in the database rather than in memory so every instance
+/// behind the load balancer sees the same counts.
+pub struct PresenceService {
+    db: Arc<Database>,
+}
+
+impl PresenceService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Register a new live connection and return its id
+    pub async fn join(&self, community_id: Uuid, user_id: Option<Uuid>) -> Result<Uuid, sqlx::Error> {
+        let connection_id = Uuid::new_v4();
+        PresenceRepository::join(self.db.pool(), connection_id, community_id, user_id, Utc::now()).await?;
+
+        self.announce(community_id, PresenceChange::Joined).await?;
+
+        Ok(connection_id)
+    }
+
+    pub async fn leave(&self, connection_id: Uuid) -> Result<(), sqlx::Error> {
+        if let Some(community_id) = PresenceRepository::leave(self.db.pool(), connection_id).await? {
+            self.announce(community_id, PresenceChange::Left).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Keep a connection from being swept; false if it already was
+    pub async fn heartbeat(&self, connection_id: Uuid) -> Result<bool, sqlx::Error> {
+        PresenceRepository::touch(self.db.pool(), connection_id, Utc::now()).await
+    }
+
+    /// Counts for a community, plus who is online if `include_users` is set
+    pub async fn get_presence(&self, community_id: Uuid, include_users: bool) -> Result<Presence, sqlx::Error> {
+        let cutoff = cutoff(Utc::now());
+        let counts = PresenceRepository::counts(self.db.pool(), community_id, cutoff).await?;
+
+        let users = if include_users {
+            Some(PresenceRepository::find_online_users(self.db.pool(), community_id, cutoff).await?)
+        } else {
+            None
+        };
+
+        Ok(Presence { community_id, counts, users })
+    }
+
+    /// Drop connections whose instance stopped refreshing them and announce
+    /// the new counts of the communities they were watching
+    pub async fn sweep_stale(&self) -> Result<usize, sqlx::Error> {
+        let stale = PresenceRepository::delete_stale(self.db.pool(), cutoff(Utc::now())).await?;
+        let count = stale.len();
+
+        let communities: HashSet<Uuid> = stale.into_iter().collect();
+        for community_id in communities {
+            self.announce(community_id, PresenceChange::Left).await?;
+        }
+
+        Ok(count)
+    }
+
+    async fn announce(&self, community_id: Uuid, change: PresenceChange) -> Result<(), sqlx::Error> {
+        let counts = PresenceRepository::counts(self.db.pool(), community_id, cutoff(Utc::now())).await?;
+
+        EventService::new(self.db.clone())
+            .publish_best_effort(NewCommunityEvent::presence_changed(community_id, change, counts))
+            .await;
+
+        Ok(())
+    }
+}
+
+fn cutoff(now: DateTime<Utc>) -> DateTime<Utc> {
+    now - PRESENCE_TTL
+}
@@ -0,0 +1,110 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use chrono::Duration;
+use pulse_database::{
+    connection::Database,
+    model::rate_limit::{CommunityRateLimits, DEFAULT_IP_LIMIT, DEFAULT_USER_LIMIT, MAX_POSTS_PER_MINUTE},
+    repository::RateLimitRepository,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::community_service::{CommunityService, CommunityServiceError};
+use crate::rate_limit_store::{Bucket, RateLimitDecision, RateLimitStore};
+
+#[derive(Error, Debug)]
+pub enum RateLimitServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Community(#[from] CommunityServiceError),
+
+    #[error("Too many messages, try again in {} seconds", retry_after_secs(*.0))]
+    Limited(Duration),
+
+    #[error("Rates must be between 1 and {} posts per minute", MAX_POSTS_PER_MINUTE)]
+    InvalidLimits,
+
+    #[error("Only the community creator can do this")]
+    Forbidden,
+}
+
+pub struct RateLimitService {
+    db: Arc<Database>,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimitService {
+    pub fn new(db: Arc<Database>, store: Arc<dyn RateLimitStore>) -> Self {
+        Self { db, store }
+    }
+
+    /// Count a post against the sender, their address and the community,
+    /// failing with how long to wait if any of them is over its limit.
+    ///
+    /// If the store can't be reached the post goes through; a broken
+    /// limiter shouldn't take posting down with it.
+    pub async fn check_post(&self, ip: Option<IpAddr>, user_id: Uuid, community_id: Uuid) -> Result<(), RateLimitServiceError> {
+        let limits = RateLimitRepository::find_by_community(self.db.pool(), community_id)
+            .await?
+            .unwrap_or_default();
+
+        let mut buckets = vec![
+            Bucket { key: format!("post:user:{}", user_id), limit: DEFAULT_USER_LIMIT },
+            Bucket { key: format!("post:community:{}", community_id), limit: limits.community_limit() },
+        ];
+        if let Some(ip) = ip {
+            buckets.push(Bucket { key: format!("post:ip:{}", ip), limit: DEFAULT_IP_LIMIT });
+        }
+        if let Some(limit) = limits.member_limit() {
+            buckets.push(Bucket { key: format!("post:community:{}:user:{}", community_id, user_id), limit });
+        }
+
+        match self.store.take(&buckets).await {
+            Ok(RateLimitDecision::Allowed) => Ok(()),
+            Ok(RateLimitDecision::Limited { retry_after }) => Err(RateLimitServiceError::Limited(retry_after)),
+            Err(e) => {
+                eprintln!("❌ Rate limit store unavailable, allowing post: {}", e);
+                Ok(())
+            },
+        }
+    }
+
+    /// A community's posting limits. Unset fields are the server defaults.
+    pub async fn get_limits(&self, community_id: Uuid) -> Result<CommunityRateLimits, RateLimitServiceError> {
+        // 404 for communities that don't exist rather than an empty default
+        CommunityService::new(self.db.clone()).get_community(community_id).await?;
+
+        let limits = RateLimitRepository::find_by_community(self.db.pool(), community_id)
+            .await?
+            .unwrap_or_default();
+        Ok(limits)
+    }
+
+    /// Replace a community's posting limits (creator only)
+    pub async fn update_limits(
+        &self,
+        community_id: Uuid,
+        user_id: Uuid,
+        limits: CommunityRateLimits,
+    ) -> Result<CommunityRateLimits, RateLimitServiceError> {
+        let community = CommunityService::new(self.db.clone()).get_community(community_id).await?;
+        if community.creator_id != user_id {
+            return Err(RateLimitServiceError::Forbidden);
+        }
+        if !limits.is_valid() {
+            return Err(RateLimitServiceError::InvalidLimits);
+        }
+
+        RateLimitRepository::save(self.db.pool(), community_id, &limits).await?;
+        Ok(limits)
+    }
+}
+
+/// Whole seconds to wait, as sent in `Retry-After`. Never 0, or clients
+/// would retry straight away.
+pub fn retry_after_secs(retry_after: Duration) -> i64 {
+    ((retry_after.num_milliseconds() + 999) / 1000).max(1)
+}
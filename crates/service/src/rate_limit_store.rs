@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use pulse_database::model::rate_limit::{RateLimit, TokenBucket};
+use redis::aio::ConnectionManager;
+use thiserror::Error;
+
+/// Takes between sweeps of idle buckets out of the in-process store
+const PRUNE_EVERY: usize = 1024;
+
+/// Prefix for every key the Redis store writes
+const REDIS_KEY_PREFIX: &str = "pulse:ratelimit:";
+
+#[derive(Error, Debug)]
+pub enum RateLimitStoreError {
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[error("Rate limit store misconfigured: {0}")]
+    Config(String),
+}
+
+/// One token bucket to take from
+#[derive(Debug, Clone)]
+pub struct Bucket {
+    pub key: String,
+    pub limit: RateLimit,
+}
+
+/// Whether a request got its tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    Allowed,
+    /// At least one bucket is empty; nothing was taken
+    Limited { retry_after: Duration },
+}
+
+/// Where token buckets are kept
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Take a token from every bucket, or from none of them if any is empty,
+    /// so a request turned away by one limit doesn't use up the others
+    async fn take(&self, buckets: &[Bucket]) -> Result<RateLimitDecision, RateLimitStoreError>;
+}
+
+/// Build the rate limit store described by the environment.
+///
+/// `RATE_LIMIT_STORE=memory` (the default) keeps buckets in this process,
+/// which is right for a single instance. `RATE_LIMIT_STORE=redis` shares
+/// them between instances through the Redis-compatible server at `REDIS_URL`.
+pub async fn rate_limit_store_from_env() -> Result<Arc<dyn RateLimitStore>, RateLimitStoreError> {
+    match env::var("RATE_LIMIT_STORE").as_deref().unwrap_or("memory") {
+        "memory" => Ok(Arc::new(MemoryRateLimitStore::new())),
+        "redis" => {
+            let url = env::var("REDIS_URL").map_err(|_| RateLimitStoreError::Config("REDIS_URL is not set".to_string()))?;
+            Ok(Arc::new(RedisRateLimitStore::connect(&url).await?))
+        },
+        other => Err(RateLimitStoreError::Config(format!("unknown RATE_LIMIT_STORE {:?}", other))),
+    }
+}
+
+/// Buckets in a map in this process
+#[derive(Default)]
+pub struct MemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, (TokenBucket, RateLimit)>>,
+    takes: AtomicUsize,
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn take(&self, buckets: &[Bucket]) -> Result<RateLimitDecision, RateLimitStoreError> {
+        let now = Utc::now();
+        let mut stored = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // Full buckets hold nothing worth keeping
+        if self.takes.fetch_add(1, Ordering::Relaxed).is_multiple_of(PRUNE_EVERY) {
+            stored.retain(|_, (bucket, limit)| !bucket.refilled(*limit, now).is_full(*limit));
+        }
+
+        let refilled: Vec<TokenBucket> = buckets
+            .iter()
+            .map(|bucket| match stored.get(&bucket.key) {
+                Some((stored, _)) => stored.refilled(bucket.limit, now),
+                None => TokenBucket::full(bucket.limit, now),
+            })
+            .collect();
+
+        let retry_after = buckets
+            .iter()
+            .zip(&refilled)
+            .filter_map(|(bucket, refilled)| refilled.wait(bucket.limit))
+            .max();
+        if let Some(retry_after) = retry_after {
+            return Ok(RateLimitDecision::Limited { retry_after });
+        }
+
+        for (bucket, refilled) in buckets.iter().zip(refilled) {
+            let taken = TokenBucket { tokens: refilled.tokens - 1.0, updated_at: now };
+            stored.insert(bucket.key.clone(), (taken, bucket.limit));
+        }
+        Ok(RateLimitDecision::Allowed)
+    }
+}
+
+/// Refill and take from every bucket in one step, using the server's clock
+/// so instances with drifting clocks agree. Returns 0 if the tokens were
+/// taken, otherwise the milliseconds to wait.
+///
+/// KEYS are the buckets; ARGV holds each one's burst and tokens per minute.
+const TAKE_SCRIPT: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local levels = {}
+local wait = 0
+
+for i, key in ipairs(KEYS) do
+    local burst = tonumber(ARGV[i * 2 - 1])
+    local rate = tonumber(ARGV[i * 2]) / 60000
+    local state = redis.call('HMGET', key, 'tokens', 'at')
+    local tokens = tonumber(state[1]) or burst
+    local at = tonumber(state[2]) or now
+    tokens = math.min(burst, tokens + math.max(0, now - at) * rate)
+    levels[i] = tokens
+    if tokens < 1 then
+        wait = math.max(wait, math.ceil((1 - tokens) / rate))
+    end
+end
+
+if wait > 0 then
+    return wait
+end
+
+for i, key in ipairs(KEYS) do
+    local burst = tonumber(ARGV[i * 2 - 1])
+    local rate = tonumber(ARGV[i * 2]) / 60000
+    redis.call('HSET', key, 'tokens', tostring(levels[i] - 1), 'at', now)
+    -- Once the bucket would be full again it can be forgotten
+    redis.call('PEXPIRE', key, math.ceil(burst / rate))
+end
+return 0
+"#;
+
+/// Buckets in a Redis-compatible server, shared between instances
+pub struct RedisRateLimitStore {
+    connection: ConnectionManager,
+    script: redis::Script,
+}
+
+impl RedisRateLimitStore {
+    pub async fn connect(url: &str) -> Result<Self, RateLimitStoreError> {
+        let client = redis::Client::open(url)?;
+        let connection = ConnectionManager::new(client).await?;
+        Ok(Self { connection, script: redis::Script::new(TAKE_SCRIPT) })
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn take(&self, buckets: &[Bucket]) -> Result<RateLimitDecision, RateLimitStoreError> {
+        let mut invocation = self.script.prepare_invoke();
+        for bucket in buckets {
+            invocation
+                .key(format!("{}{}", REDIS_KEY_PREFIX, bucket.key))
+                .arg(bucket.limit.burst)
+                .arg(bucket.limit.per_minute);
+        }
+
+        let wait_ms: i64 = invocation.invoke_async(&mut self.connection.clone()).await?;
+        if wait_ms > 0 {
+            return Ok(RateLimitDecision::Limited { retry_after: Duration::milliseconds(wait_ms) });
+        }
+        Ok(RateLimitDecision::Allowed)
+    }
+}
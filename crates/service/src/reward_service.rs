@@ -0,0 +1,130 @@
+use std::sync::{Arc, OnceLock};
+
+use chrono::Utc;
+use pulse_database::{
+    connection::Database,
+    model::payout::Payout,
+    model::reward::{RewardBalance, RECENT_REWARDS},
+    repository::{RewardRepository, UserRepository},
+};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Share of platform fees referrers earn when `REFERRAL_REWARD_PERCENT` isn't set
+const DEFAULT_REFERRAL_REWARD_PERCENT: Decimal = Decimal::TEN;
+
+#[derive(Error, Debug)]
+pub enum RewardServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("No rewards to claim")]
+    NothingToClaim,
+
+    #[error("Add a wallet address to your account before claiming rewards")]
+    NoWallet,
+}
+
+/// The percentage of the platform fee on each deposit that goes to whoever
+/// referred the depositor, from `REFERRAL_REWARD_PERCENT` (0 to 100)
+pub fn referral_reward_percent() -> Decimal {
+    static PERCENT: OnceLock<Decimal> = OnceLock::new();
+    *PERCENT.get_or_init(|| {
+        std::env::var("REFERRAL_REWARD_PERCENT")
+            .ok()
+            .and_then(|percent| percent.trim().parse::<Decimal>().ok())
+            .filter(|percent| *percent >= Decimal::ZERO && *percent <= Decimal::ONE_HUNDRED)
+            .unwrap_or(DEFAULT_REFERRAL_REWARD_PERCENT)
+    })
+}
+
+pub struct RewardService {
+    db: Arc<Database>,
+}
+
+impl RewardService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// What a user has earned from referrals and what they can still claim
+    pub async fn get_balance(&self, user_id: Uuid) -> Result<RewardBalance, RewardServiceError> {
+        let (accrued, claimed) = RewardRepository::find_totals(self.db.pool(), user_id).await?;
+        let recent_rewards = RewardRepository::find_recent(self.db.pool(), user_id, RECENT_REWARDS).await?;
+
+        Ok(RewardBalance {
+            accrued,
+            claimed,
+            balance: accrued - claimed,
+            reward_percent: referral_reward_percent(),
+            recent_rewards,
+        })
+    }
+
+    /// Pay out a user's whole balance to the wallet on their account. Without
+    /// a wallet nothing is claimed, so the balance stays where it is.
+    pub async fn claim(&self, user_id: Uuid) -> Result<Payout, RewardServiceError> {
+        let has_wallet = UserRepository::find_by_id(self.db.pool(), user_id)
+            .await?
+            .is_some_and(|user| !user.wallet_address.trim().is_empty());
+        if !has_wallet {
+            return Err(RewardServiceError::NoWallet);
+        }
+
+        RewardRepository::claim(self.db.pool(), user_id, Utc::now())
+            .await?
+            .ok_or(RewardServiceError::NothingToClaim)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulse_database::model::user::CreateUserDto;
+
+    #[tokio::test]
+    async fn test_claim_without_wallet_keeps_balance() {
+        // This test only runs if the DATABASE_URL environment variable is set
+        if std::env::var("DATABASE_URL").is_err() {
+            return;
+        }
+        let db = Arc::new(Database::new().await.expect("Database connection failed"));
+        let pool = db.pool();
+
+        let name = format!("no-wallet-{}", Uuid::new_v4().simple());
+        let user = UserRepository::create(pool, CreateUserDto {
+            username: name.clone(),
+            profile_image_asset_id: None,
+            wallet_address: String::new(),
+            email: format!("{}@example.com", name),
+            password: "password".to_string(),
+        })
+            .await
+            .unwrap();
+        let reward_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO referral_rewards (id, user_id, community_id, deposit_id, referred_user_id, platform_fee, amount, created_at)
+             VALUES ($1, $2, $3, $4, $2, 10, 1, now())",
+        )
+            .bind(reward_id)
+            .bind(user.id)
+            .bind(Uuid::new_v4())
+            .bind(Uuid::new_v4())
+            .execute(pool)
+            .await
+            .unwrap();
+
+        let service = RewardService::new(db.clone());
+        let result = service.claim(user.id).await;
+        let balance = service.get_balance(user.id).await;
+
+        sqlx::query("DELETE FROM referral_rewards WHERE id = $1").bind(reward_id).execute(pool).await.unwrap();
+        UserRepository::delete(pool, user.id).await.unwrap();
+
+        assert!(matches!(result, Err(RewardServiceError::NoWallet)));
+        let balance = balance.unwrap();
+        assert_eq!(balance.claimed, Decimal::ZERO);
+        assert_eq!(balance.balance, Decimal::ONE);
+    }
+}
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use pulse_database::{
+    connection::Database,
+    model::pagination::{page_limit, Cursor, Page},
+    model::sanction::{AuditEntry, AuditLogQuery, CreateSanctionDto, Sanction, SanctionListQuery},
+    repository::{SanctionRepository, UserRepository},
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::community_service::{CommunityService, CommunityServiceError};
+
+#[derive(Error, Debug)]
+pub enum SanctionServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Community(#[from] CommunityServiceError),
+
+    #[error("Sanction not found")]
+    NotFound,
+
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    #[error("Only the community creator or a platform admin can do this")]
+    Forbidden,
+
+    #[error("Only platform admins can do this")]
+    AdminOnly,
+
+    #[error("A reason is required")]
+    ReasonRequired,
+
+    #[error("Expiry must be in the future")]
+    InvalidExpiry,
+
+    #[error("This user cannot be sanctioned here")]
+    CannotSanction,
+
+    #[error("This sanction has already been revoked")]
+    AlreadyRevoked,
+}
+
+/// Bans and mutes, in a community or across the platform (`community_id` of `None`).
+/// A community's creator manages its sanctions; platform admins manage all of them.
+pub struct SanctionService {
+    db: Arc<Database>,
+}
+
+impl SanctionService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Ban or mute a user, recording who did it
+    pub async fn issue(
+        &self,
+        community_id: Option<Uuid>,
+        user_id: Uuid,
+        mut dto: CreateSanctionDto,
+    ) -> Result<Sanction, SanctionServiceError> {
+        let creator_id = self.authorize(community_id, user_id).await?;
+
+        dto.reason = dto.reason.trim().to_string();
+        if dto.reason.is_empty() {
+            return Err(SanctionServiceError::ReasonRequired);
+        }
+        let now = Utc::now();
+        if dto.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(SanctionServiceError::InvalidExpiry);
+        }
+
+        // Nobody can lock themselves out, and a community's creator can't be
+        // sanctioned in it; the platform's admins answer for that
+        if dto.user_id == user_id || creator_id == Some(dto.user_id) {
+            return Err(SanctionServiceError::CannotSanction);
+        }
+        if UserRepository::find_by_id(self.db.pool(), dto.user_id).await?.is_none() {
+            return Err(SanctionServiceError::UserNotFound);
+        }
+
+        let sanction = SanctionRepository::create(self.db.pool(), community_id, &dto, user_id, now).await?;
+        Ok(sanction)
+    }
+
+    /// Lift a sanction early, recording who did it
+    pub async fn revoke(&self, community_id: Option<Uuid>, sanction_id: Uuid, user_id: Uuid) -> Result<Sanction, SanctionServiceError> {
+        self.authorize(community_id, user_id).await?;
+
+        // Sanctions are only reachable through the scope they were issued in
+        let sanction = SanctionRepository::find_by_id(self.db.pool(), sanction_id)
+            .await?
+            .filter(|sanction| sanction.community_id == community_id)
+            .ok_or(SanctionServiceError::NotFound)?;
+        if sanction.revoked_at.is_some() {
+            return Err(SanctionServiceError::AlreadyRevoked);
+        }
+
+        SanctionRepository::revoke(self.db.pool(), sanction_id, user_id, Utc::now())
+            .await?
+            .ok_or(SanctionServiceError::AlreadyRevoked)
+    }
+
+    /// Sanctions in force, newest first, or every sanction with `all`
+    pub async fn list(
+        &self,
+        community_id: Option<Uuid>,
+        user_id: Uuid,
+        query: &SanctionListQuery,
+    ) -> Result<Page<Sanction>, SanctionServiceError> {
+        self.authorize(community_id, user_id).await?;
+        let after = parse_cursor(query.cursor.as_deref())?;
+
+        let sanctions = SanctionRepository::find_page(
+            self.db.pool(),
+            community_id,
+            query.all,
+            Utc::now(),
+            after,
+            page_limit(query.limit),
+        )
+            .await?;
+        Ok(sanctions)
+    }
+
+    /// Who banned, muted and lifted sanctions, newest first
+    pub async fn audit_log(
+        &self,
+        community_id: Option<Uuid>,
+        user_id: Uuid,
+        query: &AuditLogQuery,
+    ) -> Result<Page<AuditEntry>, SanctionServiceError> {
+        self.authorize(community_id, user_id).await?;
+        let after = parse_cursor(query.cursor.as_deref())?;
+
+        let entries = SanctionRepository::find_audit_page(self.db.pool(), community_id, after, page_limit(query.limit)).await?;
+        Ok(entries)
+    }
+
+    /// Check the user may manage sanctions in this scope, returning the
+    /// community's creator if there is one
+    async fn authorize(&self, community_id: Option<Uuid>, user_id: Uuid) -> Result<Option<Uuid>, SanctionServiceError> {
+        let is_admin = SanctionRepository::is_platform_admin(self.db.pool(), user_id).await?;
+
+        let Some(community_id) = community_id else {
+            return if is_admin { Ok(None) } else { Err(SanctionServiceError::AdminOnly) };
+        };
+        let community = CommunityService::new(self.db.clone())
+            .get_community(community_id)
+            .await?;
+        if community.creator_id != user_id && !is_admin {
+            return Err(SanctionServiceError::Forbidden);
+        }
+        Ok(Some(community.creator_id))
+    }
+}
+
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, SanctionServiceError> {
+    cursor
+        .map(|cursor| Cursor::decode(cursor).ok_or(SanctionServiceError::InvalidCursor))
+        .transpose()
+}
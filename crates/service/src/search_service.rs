@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use pulse_database::{
+    connection::Database,
+    model::pagination::page_limit,
+    model::search::{prefix_tsquery, SearchQuery, SearchResult},
+    repository::SearchRepository,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Longest query we are willing to run
+const MAX_QUERY_LENGTH: usize = 200;
+
+#[derive(Error, Debug)]
+pub enum SearchServiceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Search query must contain at least one word")]
+    EmptyQuery,
+
+    #[error("Search query is too long")]
+    QueryTooLong,
+}
+
+pub struct SearchService {
+    db: Arc<Database>,
+}
+
+impl SearchService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Search as `viewer`, who only finds messages in communities they can read
+    pub async fn search(&self, query: &SearchQuery, viewer: Option<Uuid>) -> Result<Vec<SearchResult>, SearchServiceError> {
+        let text = query.q.trim();
+        if text.chars().count() > MAX_QUERY_LENGTH {
+            return Err(SearchServiceError::QueryTooLong);
+        }
+
+        let prefix_query = prefix_tsquery(text).ok_or(SearchServiceError::EmptyQuery)?;
+
+        let results = SearchRepository::search(
+            self.db.pool(),
+            text,
+            &prefix_query,
+            query.kind,
+            viewer,
+            page_limit(query.limit),
+        )
+            .await?;
+
+        Ok(results)
+    }
+}
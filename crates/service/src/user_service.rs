@@ -1,4 +1,6 @@
-use pulse_database::model::user::{User, CreateUserDto, UpdateUserDto};
+use pulse_database::model::pagination::{page_limit, Cursor, Page};
+use pulse_database::model::user::{User, CreateUserDto, UserListQuery};
+use pulse_database::repository::asset_repository::AssetRepository;
 use pulse_database::repository::user_repository::UserRepository;
 use sqlx::{Pool, Postgres};
 use thiserror::Error;
@@ -14,13 +16,23 @@ pub enum UserServiceError {
 
     #[error("Email already exists")]
     EmailExists,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    #[error("Image asset not found")]
+    InvalidImageAsset,
 }
 
 pub struct UserService;
 
 impl UserService {
-    pub async fn get_all_users(pool: &Pool<Postgres>) -> Result<Vec<User>, UserServiceError> {
-        let users = UserRepository::find_all(pool).await?;
+    pub async fn get_users(pool: &Pool<Postgres>, query: &UserListQuery) -> Result<Page<User>, UserServiceError> {
+        let after = query.cursor.as_deref()
+            .map(|cursor| Cursor::decode(cursor).ok_or(UserServiceError::InvalidCursor))
+            .transpose()?;
+
+        let users = UserRepository::find_page(pool, after, page_limit(query.limit)).await?;
         Ok(users)
     }
 
@@ -40,6 +52,13 @@ impl UserService {
             return Err(UserServiceError::EmailExists);
         }
 
+        // Nobody owns an upload made before signing up, so only those can be used here
+        if let Some(asset_id) = dto.profile_image_asset_id {
+            if !AssetRepository::is_usable_by(pool, asset_id, None).await? {
+                return Err(UserServiceError::InvalidImageAsset);
+            }
+        }
+
         let user = UserRepository::create(pool, dto).await?;
         Ok(user)
     }
@@ -1,5 +1,32 @@
 -- Drop all tables in the proper order to handle foreign key constraints
+DROP MATERIALIZED VIEW IF EXISTS community_trending;
+DROP VIEW IF EXISTS content_view;
+-- assets and users reference each other, so assets goes first and takes the FKs with it
+DROP TABLE IF EXISTS assets CASCADE;
+DROP TABLE IF EXISTS community_follows;
+DROP TABLE IF EXISTS user_follows;
+DROP TABLE IF EXISTS referral_rewards;
+DROP TABLE IF EXISTS payouts;
+DROP TABLE IF EXISTS community_referrals;
+DROP TABLE IF EXISTS community_invites;
+DROP TABLE IF EXISTS community_members;
+DROP TABLE IF EXISTS pinned_contents;
+DROP TABLE IF EXISTS idempotency_keys;
+DROP TABLE IF EXISTS moderation_audit_log;
+DROP TABLE IF EXISTS sanctions;
+DROP TABLE IF EXISTS platform_admins;
+DROP TABLE IF EXISTS notifications;
+DROP TABLE IF EXISTS community_rate_limits;
+DROP TABLE IF EXISTS held_contents;
+DROP TABLE IF EXISTS community_moderation;
+DROP TABLE IF EXISTS community_presence;
+DROP TABLE IF EXISTS community_events;
+DROP TABLE IF EXISTS community_status_transitions;
 DROP TABLE IF EXISTS depositor;
+DROP TABLE IF EXISTS content_edits;
+DROP TABLE IF EXISTS content_reactions;
+DROP TABLE IF EXISTS content_links;
+DROP TABLE IF EXISTS link_previews;
 DROP TABLE IF EXISTS content;
 DROP TABLE IF EXISTS communities;
 DROP TABLE IF EXISTS users;